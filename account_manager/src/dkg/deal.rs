@@ -0,0 +1,210 @@
+use super::{
+    commitments_filename, encode_hex, participant_filename, read_json, share_filename,
+    share_password, DealerCommitments, EncryptedShare, Participant, CEREMONY_FLAG, INDEX_FLAG,
+};
+use account_utils::create_with_600_perms;
+use clap::{App, Arg, ArgMatches};
+use directory::ensure_dir_exists;
+use eth2_dkg::{check_threshold, Polynomial};
+use eth2_keystore::KeystoreBuilder;
+use rand::thread_rng;
+use std::path::{Path, PathBuf};
+use types::Keypair;
+
+pub const CMD: &str = "deal";
+pub const THRESHOLD_FLAG: &str = "threshold";
+pub const OPERATORS_FLAG: &str = "operators";
+pub const PARTICIPANTS_DIR_FLAG: &str = "participants-dir";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates the commitments and shares of this operator for a DKG ceremony. The \
+            commitments must be sent to every operator, and each share only to its recipient.",
+        )
+        .arg(
+            Arg::with_name(CEREMONY_FLAG)
+                .long(CEREMONY_FLAG)
+                .value_name("NAME")
+                .help(
+                    "A name for the ceremony which has been agreed by all operators, so that \
+                    files from different ceremonies cannot be mixed up.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(THRESHOLD_FLAG)
+                .long(THRESHOLD_FLAG)
+                .value_name("THRESHOLD")
+                .help("The number of operators which are required to sign.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OPERATORS_FLAG)
+                .long(OPERATORS_FLAG)
+                .value_name("OPERATORS")
+                .help("The number of operators in the ceremony.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(INDEX_FLAG)
+                .long(INDEX_FLAG)
+                .value_name("INDEX")
+                .help("The index of this operator, from 1 to --operators.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PARTICIPANTS_DIR_FLAG)
+                .long(PARTICIPANTS_DIR_FLAG)
+                .value_name("DIR")
+                .help(
+                    "The directory containing the participant file created by init of every \
+                    operator. Each share is encrypted to the transport key of its recipient.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("DIR")
+                .help("The directory in which the commitments and shares are created.")
+                .required(true)
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let ceremony: String = clap_utils::parse_required(matches, CEREMONY_FLAG)?;
+    let threshold: usize = clap_utils::parse_required(matches, THRESHOLD_FLAG)?;
+    let operators: u64 = clap_utils::parse_required(matches, OPERATORS_FLAG)?;
+    let index: u64 = clap_utils::parse_required(matches, INDEX_FLAG)?;
+    let participants_dir: PathBuf = clap_utils::parse_required(matches, PARTICIPANTS_DIR_FLAG)?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+
+    check_threshold(threshold, operators as usize)
+        .map_err(|e| format!("Invalid --{}: {:?}", THRESHOLD_FLAG, e))?;
+    if index == 0 || index > operators {
+        return Err(format!(
+            "--{} must be between 1 and {}",
+            INDEX_FLAG, operators
+        ));
+    }
+
+    ensure_dir_exists(&output_dir)?;
+    let commitments_path = output_dir.join(commitments_filename(index));
+    let share_paths = (1..=operators)
+        .map(|recipient| output_dir.join(share_filename(index, recipient)))
+        .collect::<Vec<_>>();
+    if let Some(path) = Some(&commitments_path)
+        .into_iter()
+        .chain(&share_paths)
+        .find(|path| path.exists())
+    {
+        return Err(format!("Refusing to overwrite existing {:?}", path));
+    }
+
+    let participants = (1..=operators)
+        .map(|recipient| read_participant(&participants_dir, &ceremony, recipient))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let polynomial =
+        Polynomial::random(threshold).map_err(|e| format!("Unable to deal: {:?}", e))?;
+    let commitments = DealerCommitments {
+        ceremony: ceremony.clone(),
+        threshold,
+        operators,
+        dealer: index,
+        commitments: polynomial
+            .commitments()
+            .map_err(|e| format!("Unable to create commitments: {:?}", e))?,
+    };
+
+    for ((recipient, share_path), participant) in
+        (1..=operators).zip(&share_paths).zip(&participants)
+    {
+        let secret_key = polynomial
+            .share(recipient)
+            .map_err(|e| format!("Unable to create share for {}: {:?}", recipient, e))?;
+        let keypair = Keypair::from_components(secret_key.public_key(), secret_key);
+
+        // A new ephemeral key is used for every share, so that the password of each share is only
+        // known to its dealer and recipient.
+        let ephemeral_key = secp256k1::SecretKey::random(&mut thread_rng());
+        let password = share_password(&participant.transport_public_key()?, &ephemeral_key)?;
+        let keystore = KeystoreBuilder::new(&keypair, password.as_ref(), "".into())
+            .and_then(|builder| {
+                builder
+                    .description(format!(
+                        "DKG share from operator {} to operator {} of {}",
+                        index, recipient, ceremony
+                    ))
+                    .build()
+            })
+            .map_err(|e| format!("Unable to encrypt share: {:?}", e))?;
+
+        let share = EncryptedShare {
+            ceremony: ceremony.clone(),
+            dealer: index,
+            recipient,
+            ephemeral_public_key: encode_hex(
+                &secp256k1::PublicKey::from_secret_key(&ephemeral_key).serialize_compressed(),
+            ),
+            keystore,
+        };
+        let json = serde_json::to_vec_pretty(&share)
+            .map_err(|e| format!("Unable to encode share: {:?}", e))?;
+        create_with_600_perms(share_path, &json)
+            .map_err(|e| format!("Unable to write {:?}: {:?}", share_path, e))?;
+    }
+
+    // The commitments are written last, so that their presence indicates that the shares are
+    // complete.
+    let json = serde_json::to_vec_pretty(&commitments)
+        .map_err(|e| format!("Unable to encode commitments: {:?}", e))?;
+    std::fs::write(&commitments_path, &json)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", commitments_path, e))?;
+
+    eprintln!();
+    eprintln!(
+        "Created the commitments and {} shares of operator {} in {:?}.",
+        operators, index, output_dir
+    );
+    eprintln!(" - Send {} to every operator.", commitments_filename(index));
+    eprintln!(
+        " - Send share-{}-to-N.json only to operator N, over a private channel.",
+        index
+    );
+    eprintln!(
+        " - Keep {} for this operator.",
+        share_filename(index, index)
+    );
+
+    Ok(())
+}
+
+/// Reads the participant file of `index`, checking that it belongs to the same ceremony.
+fn read_participant(
+    participants_dir: &Path,
+    ceremony: &str,
+    index: u64,
+) -> Result<Participant, String> {
+    let path = participants_dir.join(participant_filename(index));
+    let participant: Participant = read_json(&path)?;
+
+    if participant.ceremony != ceremony || participant.index != index {
+        return Err(format!(
+            "{:?} is not the participant file of operator {} of {}",
+            path, index, ceremony
+        ));
+    }
+    // Check the key before any shares are written.
+    participant.transport_public_key()?;
+
+    Ok(participant)
+}
@@ -0,0 +1,286 @@
+use super::{
+    commitments_filename, decode_hex, read_json, read_transport_key, share_filename,
+    share_password, ClusterDefinition, DealerCommitments, EncryptedShare, KeyShare, CEREMONY_FLAG,
+    CLUSTER_FILENAME, INDEX_FLAG, TRANSPORT_KEY_FILE_FLAG,
+};
+use crate::validator::common::read_password_file;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{create_with_600_perms, read_password_from_user, ZeroizeString};
+use clap::{App, Arg, ArgMatches};
+use directory::ensure_dir_exists;
+use eth2_dkg::{combine_commitments, combine_shares, evaluate_commitments, verify_share};
+use eth2_keystore::KeystoreBuilder;
+use std::path::{Path, PathBuf};
+use types::Keypair;
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+pub const CMD: &str = "finalize";
+pub const INPUT_DIR_FLAG: &str = "input-dir";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+pub const KEYSTORE_PASSWORD_FILE_FLAG: &str = "keystore-password-file";
+
+pub const KEYSTORE_PASSWORD_PROMPT: &str = "Enter a password for the key share keystore:";
+pub const REPEAT_PASSWORD_PROMPT: &str = "Repeat the password:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Verifies the shares received from every operator in a DKG ceremony against their \
+            commitments, and combines them into the key share of this operator. The key share is \
+            written as an EIP-2335 keystore, which may be imported into the validator client \
+            alongside a distributed validator middleware, with the cluster metadata.",
+        )
+        .arg(
+            Arg::with_name(CEREMONY_FLAG)
+                .long(CEREMONY_FLAG)
+                .value_name("NAME")
+                .help("The name of the ceremony, as given to deal.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(INDEX_FLAG)
+                .long(INDEX_FLAG)
+                .value_name("INDEX")
+                .help("The index of this operator, as given to deal.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(INPUT_DIR_FLAG)
+                .long(INPUT_DIR_FLAG)
+                .value_name("DIR")
+                .help(
+                    "The directory containing the commitments of every operator and the shares \
+                    sent to this operator.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("DIR")
+                .help("The directory in which the keystore and the cluster metadata are created.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(TRANSPORT_KEY_FILE_FLAG)
+                .long(TRANSPORT_KEY_FILE_FLAG)
+                .value_name("PATH")
+                .help("The path to the transport key of this operator, created by init.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_PASSWORD_FILE_FLAG)
+                .long(KEYSTORE_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path to a file containing the password for the key share keystore. If \
+                    omitted, it is prompted for.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let ceremony: String = clap_utils::parse_required(matches, CEREMONY_FLAG)?;
+    let index: u64 = clap_utils::parse_required(matches, INDEX_FLAG)?;
+    let input_dir: PathBuf = clap_utils::parse_required(matches, INPUT_DIR_FLAG)?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+    let transport_key_path: PathBuf = clap_utils::parse_required(matches, TRANSPORT_KEY_FILE_FLAG)?;
+    let keystore_password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, KEYSTORE_PASSWORD_FILE_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let keystore_path = output_dir.join(VOTING_KEYSTORE_FILE);
+    let cluster_path = output_dir.join(CLUSTER_FILENAME);
+    if let Some(path) = [&keystore_path, &cluster_path]
+        .iter()
+        .find(|path| path.exists())
+    {
+        return Err(format!("Refusing to overwrite existing {:?}", path));
+    }
+
+    // The parameters of the ceremony are taken from the commitments of this operator, and every
+    // other operator must agree with them.
+    let own_commitments: DealerCommitments =
+        read_json(&input_dir.join(commitments_filename(index)))?;
+    let threshold = own_commitments.threshold;
+    let operators = own_commitments.operators;
+    if index == 0 || index > operators {
+        return Err(format!(
+            "--{} must be between 1 and {}",
+            INDEX_FLAG, operators
+        ));
+    }
+
+    let dealer_commitments = (1..=operators)
+        .map(|dealer| read_commitments(&input_dir, &ceremony, threshold, operators, dealer))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let transport_key = read_transport_key(&transport_key_path)?;
+
+    let mut shares = Vec::with_capacity(dealer_commitments.len());
+    for commitments in &dealer_commitments {
+        let dealer = commitments.dealer;
+        let share_path = input_dir.join(share_filename(dealer, index));
+        let share: EncryptedShare = read_json(&share_path)?;
+        if share.ceremony != ceremony || share.dealer != dealer || share.recipient != index {
+            return Err(format!(
+                "{:?} is not the share from operator {} to operator {} of {}",
+                share_path, dealer, index, ceremony
+            ));
+        }
+
+        let ephemeral_public_key = decode_hex(&share.ephemeral_public_key)
+            .and_then(|bytes| {
+                secp256k1::PublicKey::parse_slice(&bytes, None)
+                    .map_err(|e| format!("Invalid ephemeral public key: {:?}", e))
+            })
+            .map_err(|e| format!("Unable to read {:?}: {}", share_path, e))?;
+        let password = share_password(&ephemeral_public_key, &transport_key)?;
+        let keypair = share
+            .keystore
+            .decrypt_keypair(password.as_ref())
+            .map_err(|e| format!("Unable to decrypt {:?}: {:?}", share_path, e))?;
+        let valid = verify_share(&commitments.commitments, index, &keypair.sk)
+            .map_err(|e| format!("Unable to verify {:?}: {:?}", share_path, e))?;
+        if !valid {
+            return Err(format!(
+                "The share from operator {} does not match its commitments. The ceremony must \
+                be repeated without operator {}.",
+                dealer, dealer
+            ));
+        }
+        shares.push(keypair.sk);
+    }
+
+    let secret_key =
+        combine_shares(&shares).map_err(|e| format!("Unable to combine shares: {:?}", e))?;
+    let commitments = combine_commitments(
+        &dealer_commitments
+            .into_iter()
+            .map(|c| c.commitments)
+            .collect::<Vec<_>>(),
+    )
+    .map_err(|e| format!("Unable to combine commitments: {:?}", e))?;
+    let key_shares = (1..=operators)
+        .map(|i| {
+            evaluate_commitments(&commitments, i)
+                .map(|public_key| KeyShare {
+                    index: i,
+                    public_key,
+                })
+                .map_err(|e| format!("Unable to derive key share {}: {:?}", i, e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let keypair = Keypair::from_components(secret_key.public_key(), secret_key);
+    if key_shares[index as usize - 1].public_key != keypair.pk {
+        return Err("The key share does not match the commitments".to_string());
+    }
+
+    let cluster = ClusterDefinition {
+        ceremony: ceremony.clone(),
+        threshold,
+        operators,
+        validator_public_key: commitments[0].clone(),
+        commitments,
+        key_shares,
+    };
+
+    let keystore_password = read_keystore_password(keystore_password_path, stdin_inputs)?;
+    let keystore = KeystoreBuilder::new(&keypair, keystore_password.as_ref(), "".into())
+        .and_then(|builder| {
+            builder
+                .description(format!("DKG key share {} of {}", index, ceremony))
+                .build()
+        })
+        .map_err(|e| format!("Unable to create keystore: {:?}", e))?;
+
+    ensure_dir_exists(&output_dir)?;
+    let json = keystore
+        .to_json_string()
+        .map_err(|e| format!("Unable to encode keystore: {:?}", e))?;
+    create_with_600_perms(&keystore_path, json.as_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", keystore_path, e))?;
+    let json = serde_json::to_vec_pretty(&cluster)
+        .map_err(|e| format!("Unable to encode cluster metadata: {:?}", e))?;
+    std::fs::write(&cluster_path, &json)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", cluster_path, e))?;
+
+    eprintln!();
+    eprintln!(
+        "Created key share {} of {} ({} of {} required to sign).",
+        index, ceremony, threshold, operators
+    );
+    eprintln!(" - Validator public key: {}", cluster.validator_public_key);
+    eprintln!(" - Key share public key: {}", keypair.pk);
+    eprintln!(" - Keystore: {:?}", keystore_path);
+    eprintln!(" - Cluster metadata: {:?}", cluster_path);
+    eprintln!();
+    eprintln!(
+        "Please check that every operator has the same validator public key before making a \
+        deposit. The shares received from other operators may now be deleted."
+    );
+
+    Ok(())
+}
+
+/// Reads the commitments of `dealer`, checking that they belong to the same ceremony.
+fn read_commitments(
+    input_dir: &Path,
+    ceremony: &str,
+    threshold: usize,
+    operators: u64,
+    dealer: u64,
+) -> Result<DealerCommitments, String> {
+    let path = input_dir.join(commitments_filename(dealer));
+    let commitments: DealerCommitments = read_json(&path)?;
+
+    if commitments.ceremony != ceremony || commitments.dealer != dealer {
+        return Err(format!(
+            "{:?} are not the commitments of operator {} of {}",
+            path, dealer, ceremony
+        ));
+    }
+    if commitments.threshold != threshold
+        || commitments.operators != operators
+        || commitments.commitments.len() != threshold
+    {
+        return Err(format!(
+            "The commitments of operator {} are for a threshold of {} of {} operators, \
+            rather than {} of {}",
+            dealer, commitments.threshold, commitments.operators, threshold, operators
+        ));
+    }
+
+    Ok(commitments)
+}
+
+fn read_keystore_password(
+    path: Option<PathBuf>,
+    stdin_inputs: bool,
+) -> Result<ZeroizeString, String> {
+    match path {
+        Some(path) => read_password_file(&path),
+        None => {
+            eprintln!();
+            eprintln!("{}", KEYSTORE_PASSWORD_PROMPT);
+            let password = read_password_from_user(stdin_inputs)?;
+            eprintln!("{}", REPEAT_PASSWORD_PROMPT);
+            if read_password_from_user(stdin_inputs)? != password {
+                return Err("The passwords do not match".to_string());
+            }
+            Ok(password)
+        }
+    }
+}
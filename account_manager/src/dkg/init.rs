@@ -0,0 +1,99 @@
+use super::{
+    encode_hex, participant_filename, Participant, CEREMONY_FLAG, INDEX_FLAG,
+    TRANSPORT_KEY_FILENAME,
+};
+use account_utils::create_with_600_perms;
+use clap::{App, Arg, ArgMatches};
+use directory::ensure_dir_exists;
+use rand::thread_rng;
+use std::path::PathBuf;
+use zeroize::Zeroizing;
+
+pub const CMD: &str = "init";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Creates the transport key of this operator for a DKG ceremony, to which the shares \
+            sent to this operator are encrypted. The participant file must be sent to every \
+            operator, and the transport key kept until finalize has been run.",
+        )
+        .arg(
+            Arg::with_name(CEREMONY_FLAG)
+                .long(CEREMONY_FLAG)
+                .value_name("NAME")
+                .help(
+                    "A name for the ceremony which has been agreed by all operators, so that \
+                    files from different ceremonies cannot be mixed up.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(INDEX_FLAG)
+                .long(INDEX_FLAG)
+                .value_name("INDEX")
+                .help("The index of this operator, starting from 1.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_DIR_FLAG)
+                .long(OUTPUT_DIR_FLAG)
+                .value_name("DIR")
+                .help("The directory in which the transport key and participant file are created.")
+                .required(true)
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    let ceremony: String = clap_utils::parse_required(matches, CEREMONY_FLAG)?;
+    let index: u64 = clap_utils::parse_required(matches, INDEX_FLAG)?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+
+    if index == 0 {
+        return Err(format!("--{} must be at least 1", INDEX_FLAG));
+    }
+
+    ensure_dir_exists(&output_dir)?;
+    let key_path = output_dir.join(TRANSPORT_KEY_FILENAME);
+    let participant_path = output_dir.join(participant_filename(index));
+    if let Some(path) = [&key_path, &participant_path]
+        .iter()
+        .find(|path| path.exists())
+    {
+        return Err(format!("Refusing to overwrite existing {:?}", path));
+    }
+
+    let secret_key = secp256k1::SecretKey::random(&mut thread_rng());
+    let participant = Participant {
+        ceremony,
+        index,
+        transport_public_key: encode_hex(
+            &secp256k1::PublicKey::from_secret_key(&secret_key).serialize_compressed(),
+        ),
+    };
+
+    let secret_key_hex = Zeroizing::new(encode_hex(&secret_key.serialize()));
+    create_with_600_perms(&key_path, secret_key_hex.as_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", key_path, e))?;
+    let json = serde_json::to_vec_pretty(&participant)
+        .map_err(|e| format!("Unable to encode participant: {:?}", e))?;
+    std::fs::write(&participant_path, &json)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", participant_path, e))?;
+
+    eprintln!();
+    eprintln!(
+        "Created the transport key of operator {} in {:?}.",
+        index, output_dir
+    );
+    eprintln!(" - Send {} to every operator.", participant_filename(index));
+    eprintln!(
+        " - Keep {} private, it is required by finalize.",
+        TRANSPORT_KEY_FILENAME
+    );
+
+    Ok(())
+}
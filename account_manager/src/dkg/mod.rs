@@ -0,0 +1,173 @@
+pub mod deal;
+pub mod finalize;
+pub mod init;
+
+use account_utils::ZeroizeString;
+use clap::{App, ArgMatches};
+use eth2_keystore::Keystore;
+use serde_derive::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::path::Path;
+use types::PublicKey;
+use zeroize::Zeroizing;
+
+pub const CMD: &str = "dkg";
+pub const CEREMONY_FLAG: &str = "ceremony";
+pub const INDEX_FLAG: &str = "index";
+pub const TRANSPORT_KEY_FILE_FLAG: &str = "transport-key-file";
+
+/// The name of the cluster metadata file written by `finalize`.
+pub const CLUSTER_FILENAME: &str = "cluster.json";
+/// The name of the file containing the transport secret key, written by `init`.
+pub const TRANSPORT_KEY_FILENAME: &str = "transport-key.txt";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Participates in a distributed key generation (DKG) ceremony, which creates a \
+            validator key that is shared between several operators such that a threshold of them \
+            is required to sign. Each operator first runs init and sends its participant file to \
+            every operator, then runs deal with the participants of every operator, sends the \
+            commitments to every operator and each share to its recipient, then runs finalize.",
+        )
+        .subcommand(init::cli_app())
+        .subcommand(deal::cli_app())
+        .subcommand(finalize::cli_app())
+}
+
+pub fn cli_run(matches: &ArgMatches) -> Result<(), String> {
+    match matches.subcommand() {
+        (init::CMD, Some(matches)) => init::cli_run(matches),
+        (deal::CMD, Some(matches)) => deal::cli_run(matches),
+        (finalize::CMD, Some(matches)) => finalize::cli_run(matches),
+        (unknown, _) => Err(format!(
+            "{} does not have a {} command. See --help",
+            CMD, unknown
+        )),
+    }
+}
+
+/// An operator of a ceremony, which is sent to every operator by `init`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Participant {
+    pub ceremony: String,
+    pub index: u64,
+    /// The 0x-prefixed, compressed secp256k1 public key to which the shares for this operator are
+    /// encrypted.
+    pub transport_public_key: String,
+}
+
+impl Participant {
+    pub fn transport_public_key(&self) -> Result<secp256k1::PublicKey, String> {
+        let bytes = decode_hex(&self.transport_public_key)?;
+        secp256k1::PublicKey::parse_slice(&bytes, None).map_err(|e| {
+            format!(
+                "Invalid transport public key of operator {}: {:?}",
+                self.index, e
+            )
+        })
+    }
+}
+
+/// The public commitments of a dealer, which are sent to every operator.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DealerCommitments {
+    pub ceremony: String,
+    pub threshold: usize,
+    pub operators: u64,
+    pub dealer: u64,
+    pub commitments: Vec<PublicKey>,
+}
+
+/// The share created by a dealer for a single operator, which must only be sent to that operator.
+///
+/// The share is the secret key of the keystore. The password of the keystore is agreed by ECDH
+/// between the ephemeral key of the share and the transport key of the recipient, so only the
+/// recipient can decrypt it.
+#[derive(Serialize, Deserialize)]
+pub struct EncryptedShare {
+    pub ceremony: String,
+    pub dealer: u64,
+    pub recipient: u64,
+    /// The 0x-prefixed, compressed secp256k1 public key of the ephemeral key of the share.
+    pub ephemeral_public_key: String,
+    pub keystore: Keystore,
+}
+
+/// The public key of the key share of an operator.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyShare {
+    pub index: u64,
+    pub public_key: PublicKey,
+}
+
+/// The public metadata of the cluster created by a ceremony, which is identical for every
+/// operator.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClusterDefinition {
+    pub ceremony: String,
+    pub threshold: usize,
+    pub operators: u64,
+    pub validator_public_key: PublicKey,
+    /// The commitments to the polynomial of the validator key, from which the public key of any
+    /// key share can be derived.
+    pub commitments: Vec<PublicKey>,
+    pub key_shares: Vec<KeyShare>,
+}
+
+/// Returns the name of the file containing the commitments of `dealer`.
+pub fn commitments_filename(dealer: u64) -> String {
+    format!("commitments-{}.json", dealer)
+}
+
+/// Returns the name of the file containing the share from `dealer` to `recipient`.
+pub fn share_filename(dealer: u64, recipient: u64) -> String {
+    format!("share-{}-to-{}.json", dealer, recipient)
+}
+
+/// Returns the name of the file containing the participant of `index`, written by `init`.
+pub fn participant_filename(index: u64) -> String {
+    format!("participant-{}.json", index)
+}
+
+/// Returns the password of the keystore of a share, agreed by ECDH between `secret_key` and
+/// `public_key`.
+///
+/// The dealer uses the ephemeral secret key of the share and the transport public key of the
+/// recipient, and the recipient uses its transport secret key and the ephemeral public key.
+fn share_password(
+    public_key: &secp256k1::PublicKey,
+    secret_key: &secp256k1::SecretKey,
+) -> Result<ZeroizeString, String> {
+    let shared_secret = secp256k1::SharedSecret::new(public_key, secret_key)
+        .map_err(|e| format!("Unable to agree on the share password: {:?}", e))?;
+    Ok(ZeroizeString::from(hex::encode(shared_secret.as_ref())))
+}
+
+/// Reads the transport secret key written by `init` from `path`.
+fn read_transport_key(path: &Path) -> Result<secp256k1::SecretKey, String> {
+    let contents = Zeroizing::new(
+        fs::read_to_string(path).map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?,
+    );
+    let bytes = Zeroizing::new(decode_hex(contents.trim())?);
+    secp256k1::SecretKey::parse_slice(&bytes)
+        .map_err(|e| format!("Invalid transport key in {:?}: {:?}", path, e))
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn decode_hex(string: &str) -> Result<Vec<u8>, String> {
+    hex::decode(string.strip_prefix("0x").unwrap_or(string))
+        .map_err(|e| format!("Invalid hex: {:?}", e))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    File::open(path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+        .and_then(|file| {
+            serde_json::from_reader(file)
+                .map_err(|e| format!("Unable to parse {:?}: {:?}", path, e))
+        })
+}
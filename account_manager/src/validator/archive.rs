@@ -0,0 +1,124 @@
+//! Provides creation and extraction of keystore archives, so that keystores can be exported and
+//! imported without the user copying or extracting them by hand.
+
+use std::fs::{self, File};
+use std::io::{self, Cursor, Write};
+use std::path::{Component, Path, PathBuf};
+use tempfile::TempDir;
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+/// Returns the bytes of a `.zip` archive containing each `(name, contents)` in `files`.
+///
+/// The archive is built in memory, so that no partial archive is written if this fails.
+pub fn create_archive(files: &[(String, Vec<u8>)]) -> Result<Vec<u8>, String> {
+    let mut writer = ZipWriter::new(Cursor::new(vec![]));
+
+    for (name, contents) in files {
+        writer
+            .start_file(name, FileOptions::default())
+            .map_err(|e| format!("Error adding {:?} to zip file: {}", name, e))?;
+        writer
+            .write_all(contents)
+            .map_err(|e| format!("Error writing {:?} to zip file: {}", name, e))?;
+    }
+
+    writer
+        .finish()
+        .map(Cursor::into_inner)
+        .map_err(|e| format!("Error with zip file: {}", e))
+}
+
+/// The contents of an archive, extracted into a temporary directory.
+///
+/// The extracted files are overwritten with zeros and the directory is removed when this is
+/// dropped.
+pub struct ExtractedArchive {
+    dir: TempDir,
+    files: Vec<PathBuf>,
+}
+
+impl ExtractedArchive {
+    /// Extracts the `.zip` archive at `archive_path` into a new temporary directory.
+    ///
+    /// The directory is only accessible by the current user. An error is returned if any entry
+    /// in the archive would be extracted outside of the directory.
+    pub fn extract(archive_path: &Path) -> Result<Self, String> {
+        let is_zip = archive_path
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("zip"));
+        if !is_zip {
+            return Err(format!(
+                "Unsupported archive {:?}, only .zip archives are supported",
+                archive_path
+            ));
+        }
+
+        let archive_file = File::open(archive_path)
+            .map_err(|e| format!("Failed to open archive file {:?}: {:?}", archive_path, e))?;
+        let mut archive =
+            ZipArchive::new(archive_file).map_err(|e| format!("Error with zip file: {}", e))?;
+
+        let dir = tempfile::Builder::new()
+            .prefix("lighthouse-import-")
+            .tempdir()
+            .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?;
+        let mut extracted = Self { dir, files: vec![] };
+
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| format!("Error retrieving file {} inside zip: {}", i, e))?;
+
+            // Only plain relative paths are permitted, so that an entry such as `../keystore.json`
+            // cannot be written outside of the temporary directory.
+            let name = Path::new(entry.name());
+            let is_enclosed = name.components().next().is_some()
+                && name
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+            if !is_enclosed {
+                return Err(format!(
+                    "Refusing to extract {:?} from {:?}, it is not a relative path within the \
+                     archive",
+                    entry.name(),
+                    archive_path
+                ));
+            }
+            let path = extracted.dir.path().join(name);
+
+            if entry.is_dir() {
+                fs::create_dir_all(&path)
+                    .map_err(|e| format!("Unable to create directory {:?}: {:?}", path, e))?;
+                continue;
+            }
+
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|e| format!("Unable to create directory {:?}: {:?}", parent, e))?;
+            }
+            let mut outfile = File::create(&path)
+                .map_err(|e| format!("Error while creating file {:?}: {}", path, e))?;
+            extracted.files.push(path.clone());
+            io::copy(&mut entry, &mut outfile)
+                .map_err(|e| format!("Error writing file {:?}: {}", path, e))?;
+        }
+
+        Ok(extracted)
+    }
+
+    /// The directory which contains the extracted files.
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl Drop for ExtractedArchive {
+    fn drop(&mut self) {
+        // The keystores are encrypted, but there is no need to leave them on disk.
+        for path in &self.files {
+            if let Ok(metadata) = fs::metadata(path) {
+                let _ = fs::write(path, vec![0; metadata.len() as usize]);
+            }
+        }
+    }
+}
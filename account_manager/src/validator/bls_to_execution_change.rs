@@ -0,0 +1,335 @@
+use super::common::genesis_validators_root;
+use super::recover::{FIRST_INDEX_FLAG, MNEMONIC_FLAG};
+use crate::common::read_mnemonic_from_cli;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{create_with_600_perms, eth2_keystore::keypair_from_secret};
+use bls::{get_withdrawal_credentials, Keypair};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Url,
+};
+use eth2_wallet::bip39::Seed;
+use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use tokio_compat_02::FutureExt;
+use types::{
+    Address, BlsToExecutionChange, ChainSpec, EthSpec, Hash256, SignedBlsToExecutionChange,
+};
+
+pub const CMD: &str = "bls-to-execution-change";
+pub const VALIDATOR_INDICES_FLAG: &str = "validator-indices";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const OUTPUT_FLAG: &str = "output";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const I_UNDERSTAND_UNCHECKED_CHANGES_FLAG: &str = "i-understand-unchecked-changes";
+
+pub const CONFIRMATION_PROMPT: &str =
+    "Do you want to submit these changes? They cannot be undone. [y/N]";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Signs changes of the withdrawal credentials of validators from their BLS withdrawal \
+            keys to an execution address. The withdrawal keys are derived from a BIP-39 mnemonic \
+            following EIP-2334. The signed changes are either written to a file for later \
+            broadcast or submitted to a beacon node.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDICES_FLAG)
+                .long(VALIDATOR_INDICES_FLAG)
+                .value_name("INDICES")
+                .help(
+                    "A comma-separated list of the indices of the validators in the beacon chain. \
+                    The first validator uses the withdrawal key at --first-index, and each of the \
+                    following validators uses the next key index.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The key index of the withdrawal key of the first validator.")
+                .default_value("0")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("ADDRESS")
+                .help("The 0x-prefixed execution address to which the validators will withdraw.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_FLAG)
+                .long(OUTPUT_FLAG)
+                .value_name("JSON_FILE")
+                .help(
+                    "The path of the file to which the signed changes are written, in the format \
+                    accepted by the beacon node API. An existing file is not overwritten. The \
+                    withdrawal credentials of the validators are not checked, so \
+                    --i-understand-unchecked-changes is required.",
+                )
+                .conflicts_with(BEACON_NODE_FLAG)
+                .required_unless(BEACON_NODE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BEACON_NODE_FLAG)
+                .long(BEACON_NODE_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help(
+                    "Address to a beacon node HTTP API to which the signed changes are submitted. \
+                    The withdrawal credentials of each validator are checked against its \
+                    withdrawal key first.",
+                )
+                .conflicts_with(OUTPUT_FLAG)
+                .required_unless(OUTPUT_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(I_UNDERSTAND_UNCHECKED_CHANGES_FLAG)
+                .long(I_UNDERSTAND_UNCHECKED_CHANGES_FLAG)
+                .help(
+                    "Acknowledges that the changes written to --output are not checked against \
+                    the withdrawal credentials of the validators, and that a change for the wrong \
+                    validator cannot be undone once it is broadcast.",
+                )
+                .requires(OUTPUT_FLAG),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
+    let validator_indices: Vec<u64> = matches
+        .value_of(VALIDATOR_INDICES_FLAG)
+        .ok_or_else(|| format!("--{} is required", VALIDATOR_INDICES_FLAG))?
+        .split(',')
+        .map(|index| {
+            index
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid validator index {}: {:?}", index, e))
+        })
+        .collect::<Result<_, _>>()?;
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let execution_address = parse_execution_address(
+        matches
+            .value_of(EXECUTION_ADDRESS_FLAG)
+            .ok_or_else(|| format!("--{} is required", EXECUTION_ADDRESS_FLAG))?,
+    )?;
+    let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let output_path: Option<PathBuf> = clap_utils::parse_optional(matches, OUTPUT_FLAG)?;
+    let beacon_node: Option<String> = clap_utils::parse_optional(matches, BEACON_NODE_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    // As with `validator recover`, the range is checked before the mnemonic is read.
+    u32::try_from(validator_indices.len())
+        .ok()
+        .and_then(|count| first_index.checked_add(count))
+        .ok_or_else(|| {
+            format!(
+                "--{} plus the number of validators is too large",
+                FIRST_INDEX_FLAG
+            )
+        })?;
+
+    if let Some(output_path) = &output_path {
+        if !matches.is_present(I_UNDERSTAND_UNCHECKED_CHANGES_FLAG) {
+            return Err(format!(
+                "The changes written to --{} are not checked against the withdrawal credentials \
+                 of the validators. Check them, or submit them with --{} instead, then use --{} \
+                 to write them",
+                OUTPUT_FLAG, BEACON_NODE_FLAG, I_UNDERSTAND_UNCHECKED_CHANGES_FLAG
+            ));
+        }
+        if output_path.exists() {
+            return Err(format!("Refusing to overwrite existing {:?}", output_path));
+        }
+    }
+
+    let spec = env.eth2_config().spec.clone();
+    let genesis_validators_root = genesis_validators_root(&env)?;
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let mut changes = Vec::with_capacity(validator_indices.len());
+    let mut withdrawal_keypairs = Vec::with_capacity(validator_indices.len());
+    for (key_index, validator_index) in (first_index..).zip(validator_indices.iter().copied()) {
+        let (secret, path) =
+            recover_validator_secret_from_mnemonic(seed.as_bytes(), key_index, KeyType::Withdrawal)
+                .map_err(|e| format!("Unable to recover withdrawal key: {:?}", e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to build withdrawal keypair: {:?}", e))?;
+
+        eprintln!(
+            "Validator {}: withdrawal key {} at {}",
+            validator_index, keypair.pk, path
+        );
+
+        let change = BlsToExecutionChange {
+            validator_index,
+            from_bls_pubkey: (&keypair.pk).into(),
+            to_execution_address: execution_address,
+        };
+        changes.push(change.sign(&keypair.sk, genesis_validators_root, &spec));
+        withdrawal_keypairs.push(keypair);
+    }
+
+    match (output_path, beacon_node) {
+        (Some(output_path), _) => {
+            let json = serde_json::to_vec_pretty(&changes)
+                .map_err(|e| format!("Unable to encode changes: {:?}", e))?;
+            create_with_600_perms(&output_path, &json)
+                .map_err(|e| format!("Unable to write {:?}: {:?}", output_path, e))?;
+            eprintln!(
+                "Wrote {} signed changes to {:?}.",
+                changes.len(),
+                output_path
+            );
+        }
+        (None, Some(beacon_node)) => {
+            let client = BeaconNodeHttpClient::new(
+                Url::parse(&beacon_node)
+                    .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+            );
+            env.runtime().block_on(
+                publish_changes(
+                    &client,
+                    &changes,
+                    &withdrawal_keypairs,
+                    genesis_validators_root,
+                    &spec,
+                    stdin_inputs,
+                )
+                .compat(),
+            )?;
+        }
+        (None, None) => {
+            return Err(format!(
+                "Either --{} or --{} is required",
+                OUTPUT_FLAG, BEACON_NODE_FLAG
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that each of the `changes` applies to a validator whose withdrawal credentials were
+/// derived from the corresponding key in `withdrawal_keypairs`, then submits the changes to the
+/// beacon node.
+async fn publish_changes(
+    client: &BeaconNodeHttpClient,
+    changes: &[SignedBlsToExecutionChange],
+    withdrawal_keypairs: &[Keypair],
+    genesis_validators_root: Hash256,
+    spec: &ChainSpec,
+    stdin_inputs: bool,
+) -> Result<(), String> {
+    let genesis_data = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get beacon genesis: {}", e))?
+        .data;
+    if genesis_data.genesis_validators_root != genesis_validators_root {
+        return Err(
+            "Invalid genesis state. Please ensure that your beacon node is on the same network \
+            as the validators you are changing the withdrawal credentials of"
+                .to_string(),
+        );
+    }
+
+    for (change, keypair) in changes.iter().zip(withdrawal_keypairs) {
+        let validator_index = change.message.validator_index;
+        let validator = client
+            .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(validator_index))
+            .await
+            .map_err(|e| format!("Failed to get validator details: {:?}", e))?
+            .ok_or_else(|| {
+                format!(
+                    "Validator {} is not present in the beacon state",
+                    validator_index
+                )
+            })?
+            .data
+            .validator;
+
+        let expected = get_withdrawal_credentials(&keypair.pk, spec.bls_withdrawal_prefix_byte);
+        if validator.withdrawal_credentials.as_bytes() != expected.as_slice() {
+            return Err(format!(
+                "The withdrawal credentials of validator {} ({:?}) were not derived from the \
+                withdrawal key {}. Please check --{} and --{}.",
+                validator_index,
+                validator.withdrawal_credentials,
+                keypair.pk,
+                VALIDATOR_INDICES_FLAG,
+                FIRST_INDEX_FLAG
+            ));
+        }
+    }
+
+    eprintln!();
+    eprintln!(
+        "Changing the withdrawal credentials of {} validators to {:?}.",
+        changes.len(),
+        changes
+            .first()
+            .map(|change| change.message.to_execution_address)
+            .unwrap_or_default()
+    );
+    eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION");
+    eprintln!("{}", CONFIRMATION_PROMPT);
+
+    let input = account_utils::read_input_from_user(stdin_inputs)?;
+    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        eprintln!("The changes were not submitted.");
+        return Ok(());
+    }
+
+    client
+        .post_beacon_pool_bls_to_execution_changes(changes)
+        .await
+        .map_err(|e| format!("Failed to publish changes: {}", e))?;
+    eprintln!("Successfully published {} changes.", changes.len());
+
+    Ok(())
+}
+
+/// Parses a 0x-prefixed, 20 byte execution address.
+fn parse_execution_address(address: &str) -> Result<Address, String> {
+    let bytes = address
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Execution address {} must start with 0x", address))
+        .and_then(|hex_address| {
+            hex::decode(hex_address)
+                .map_err(|e| format!("Invalid execution address {}: {:?}", address, e))
+        })?;
+
+    if bytes.len() == Address::len_bytes() {
+        Ok(Address::from_slice(&bytes))
+    } else {
+        Err(format!(
+            "Execution address {} must be {} bytes",
+            address,
+            Address::len_bytes()
+        ))
+    }
+}
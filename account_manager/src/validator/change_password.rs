@@ -0,0 +1,323 @@
+use super::common::{
+    read_password_file, reencrypt_keystore, update_definitions, ReencryptionKdf, KDF_COST_FLAG,
+    KDF_FLAG,
+};
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    create_with_600_perms,
+    eth2_keystore::Keystore,
+    is_password_sufficiently_complex, read_password_from_user,
+    validator_definitions::{
+        DefinitionsFormat, SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+    },
+    ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "change-password";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const OLD_PASSWORD_FILE_FLAG: &str = "old-password-file";
+pub const NEW_PASSWORD_FILE_FLAG: &str = "new-password-file";
+
+pub const OLD_PASSWORD_PROMPT: &str = "Enter the current keystore password:";
+pub const NEW_PASSWORD_PROMPT: &str = "Enter the new keystore password:";
+pub const REPEAT_PASSWORD_PROMPT: &str = "Repeat the new keystore password:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Changes the password of an EIP-2335 voting keystore. The keystore is re-encrypted \
+            with the new password and replaced atomically. If the keystore is in the validator \
+            definitions, the password stored for it is updated too. The validator client should \
+            be restarted afterwards.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to the EIP-2335 voting keystore.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OLD_PASSWORD_FILE_FLAG)
+                .long(OLD_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path to a file containing the current password. If omitted, the \
+                    password stored in the validator definitions is used, or it is prompted for.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(NEW_PASSWORD_FILE_FLAG)
+                .long(NEW_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path to a file containing the new password. If omitted, it is prompted \
+                    for.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help("The key derivation function of the new keystore. Defaults to scrypt.")
+                .possible_values(&["scrypt", "pbkdf2"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("N")
+                .help(
+                    "The cost parameter of the --kdf, i.e. the scrypt \"n\" or the pbkdf2 \
+                    iteration count \"c\". Defaults to 262144. Values below 16384 are refused, \
+                    and scrypt requires a power of two.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let old_password_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, OLD_PASSWORD_FILE_FLAG)?;
+    let new_password_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, NEW_PASSWORD_FILE_FLAG)?;
+    let kdf = ReencryptionKdf::new(
+        matches.value_of(KDF_FLAG).unwrap_or("scrypt"),
+        clap_utils::parse_optional(matches, KDF_COST_FLAG)?,
+    )?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let keystore = Keystore::from_json_file(&keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+    let pubkey = keystore
+        .public_key()
+        .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+
+    // The definition is found by public key, since the keystore may be given by a different path
+    // to that in the definitions.
+    let defs_exist = DefinitionsFormat::detect(&validator_dir)
+        .map_err(|e| format!("Unable to find {}: {:?}", CONFIG_FILENAME, e))?
+        .is_some();
+    let mut defs = if defs_exist {
+        Some(ValidatorDefinitions::open(&validator_dir).map_err(|e| {
+            format!(
+                "Unable to open {} in {:?}: {:?}",
+                CONFIG_FILENAME, validator_dir, e
+            )
+        })?)
+    } else {
+        None
+    };
+    let definition = defs.as_ref().and_then(|defs| {
+        defs.as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == pubkey)
+            .cloned()
+    });
+
+    // A password file which is shared with other validators cannot be replaced without changing
+    // their passwords too.
+    if let (Some(def), Some(defs)) = (&definition, &defs) {
+        if let SigningDefinition::LocalKeystore {
+            voting_keystore_password_path: Some(password_path),
+            ..
+        } = &def.signing_definition
+        {
+            let shared = defs.as_slice().iter().any(|other| {
+                other.voting_public_key != pubkey
+                    && matches!(
+                        &other.signing_definition,
+                        SigningDefinition::LocalKeystore {
+                            voting_keystore_password_path: Some(path),
+                            ..
+                        } if path == password_path
+                    )
+            });
+            if shared {
+                return Err(format!(
+                    "The password file {:?} of validator {} is used by other validators too. \
+                     Give the validator its own password file in {} before changing its password.",
+                    password_path, pubkey, CONFIG_FILENAME
+                ));
+            }
+        }
+    }
+
+    let old_password = match (&old_password_file, &definition) {
+        (Some(path), _) => read_password_file(path)?,
+        (None, Some(def)) => match def
+            .signing_definition
+            .voting_keystore_password()
+            .map_err(|e| format!("Unable to read the stored password: {:?}", e))?
+        {
+            Some(password) => String::from_utf8(password.as_bytes().to_vec())
+                .map(ZeroizeString::from)
+                .map_err(|_| "The stored password is not valid UTF-8".to_string())?,
+            None => prompt_for_password(OLD_PASSWORD_PROMPT, stdin_inputs)?,
+        },
+        (None, None) => prompt_for_password(OLD_PASSWORD_PROMPT, stdin_inputs)?,
+    };
+    keystore
+        .decrypt_keypair(old_password.as_ref())
+        .map_err(|e| match e {
+            account_utils::eth2_keystore::Error::InvalidPassword => {
+                "The current password is incorrect".to_string()
+            }
+            e => format!("Unable to decrypt keystore: {:?}", e),
+        })?;
+
+    let new_password = match &new_password_file {
+        Some(path) => read_password_file(path)?,
+        None => {
+            let password = prompt_for_password(NEW_PASSWORD_PROMPT, stdin_inputs)?;
+            if prompt_for_password(REPEAT_PASSWORD_PROMPT, stdin_inputs)? != password {
+                return Err("The new passwords do not match".to_string());
+            }
+            password
+        }
+    };
+    is_password_sufficiently_complex(new_password.as_ref())?;
+
+    let new_keystore = reencrypt_keystore(&keystore, &old_password, &new_password, kdf)?;
+    new_keystore
+        .decrypt_keypair(new_password.as_ref())
+        .map_err(|e| format!("Unable to decrypt the re-encrypted keystore: {:?}", e))?;
+
+    // The new keystore is written alongside the old one, so that it can be renamed over it.
+    let temp_keystore_path = temp_path(&keystore_path);
+    let result = new_keystore
+        .to_json_string()
+        .map_err(|e| format!("Unable to encode keystore: {:?}", e))
+        .and_then(|json| {
+            create_with_600_perms(&temp_keystore_path, json.as_bytes())
+                .map_err(|e| format!("Unable to write {:?}: {:?}", temp_keystore_path, e))
+        });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp_keystore_path);
+        return Err(e);
+    }
+
+    // The stored password is updated before the keystore is replaced, and restored if the keystore
+    // cannot be replaced, so that the stored password always decrypts the keystore on disk.
+    let stored = match (&definition, &mut defs) {
+        (Some(def), Some(defs)) => {
+            match store_password(defs, &validator_dir, &def.signing_definition, &new_password) {
+                Ok(stored) => stored,
+                Err(e) => {
+                    let _ = fs::remove_file(&temp_keystore_path);
+                    return Err(e);
+                }
+            }
+        }
+        _ => false,
+    };
+
+    if let Err(e) = fs::rename(&temp_keystore_path, &keystore_path) {
+        let _ = fs::remove_file(&temp_keystore_path);
+        if let (true, Some(def), Some(defs)) = (stored, &definition, &mut defs) {
+            if let Err(e) =
+                store_password(defs, &validator_dir, &def.signing_definition, &old_password)
+            {
+                eprintln!("Unable to restore the stored password: {}", e);
+            }
+        }
+        return Err(format!(
+            "Unable to replace {:?}: {:?}. The keystore was not changed.",
+            keystore_path, e
+        ));
+    }
+
+    eprintln!("Changed the password of keystore {:?}.", keystore_path);
+    if stored {
+        eprintln!("Updated the password stored for validator {}.", pubkey);
+    } else if definition.is_some() {
+        eprintln!(
+            "No password is stored for validator {}, it will be prompted for by the validator \
+            client.",
+            pubkey
+        );
+    }
+
+    Ok(())
+}
+
+/// Replaces the password stored for the keystore of `signing_definition`, either in the
+/// definitions file or in its password file. Returns `false` if no password is stored.
+fn store_password(
+    defs: &mut ValidatorDefinitions,
+    validator_dir: &Path,
+    signing_definition: &SigningDefinition,
+    password: &ZeroizeString,
+) -> Result<bool, String> {
+    match signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password: Some(_),
+            voting_keystore_path,
+            ..
+        } => {
+            update_definitions(defs, validator_dir, |latest| {
+                for def in latest.as_mut_slice() {
+                    match &mut def.signing_definition {
+                        SigningDefinition::LocalKeystore {
+                            voting_keystore_path: path,
+                            voting_keystore_password: Some(stored),
+                            ..
+                        } if path == voting_keystore_path => {
+                            *stored = password.clone();
+                        }
+                        _ => (),
+                    }
+                }
+                Ok(())
+            })?;
+            Ok(true)
+        }
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password_path: Some(password_path),
+            ..
+        } => {
+            let temp_password_path = temp_path(password_path);
+            create_with_600_perms(&temp_password_path, password.as_ref())
+                .map_err(|e| format!("Unable to write {:?}: {:?}", temp_password_path, e))
+                .and_then(|()| {
+                    fs::rename(&temp_password_path, password_path).map_err(|e| {
+                        format!(
+                            "Unable to replace password file {:?}: {:?}",
+                            password_path, e
+                        )
+                    })
+                })
+                .map_err(|e| {
+                    let _ = fs::remove_file(&temp_password_path);
+                    e
+                })?;
+            Ok(true)
+        }
+        SigningDefinition::LocalKeystore { .. } => Ok(false),
+    }
+}
+
+/// Returns the path of the temporary file which is renamed to `path` once it has been written.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+fn prompt_for_password(prompt: &str, stdin_inputs: bool) -> Result<ZeroizeString, String> {
+    eprintln!();
+    eprintln!("{}", prompt);
+    read_password_from_user(stdin_inputs)
+}
@@ -0,0 +1,335 @@
+//! Provides the helpers which are shared by the validator subcommands, such as updating the
+//! validator definitions file, reading passwords and exporting slashing protection history.
+
+use account_utils::{
+    eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
+        Keystore, KeystoreBuilder, DEFAULT_PBKDF2_C, DKLEN, SALT_SIZE,
+    },
+    read_password,
+    validator_definitions::{ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+    ZeroizeString,
+};
+use environment::Environment;
+use lockfile::{Lockfile, LockfileError};
+use rand::Rng;
+use slashing_protection::{
+    interchange::{Interchange, InterchangeData},
+    SlashingDatabase,
+};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+use types::{BeaconState, EthSpec, GraffitiString, Hash256, PublicKey};
+
+/// The flags which choose how keystores are re-encrypted by `import` and `change-password`.
+pub const KDF_FLAG: &str = "kdf";
+pub const KDF_COST_FLAG: &str = "kdf-cost";
+
+/// The lowest `--kdf-cost` accepted for each KDF, below which keystores are too cheap to brute
+/// force. Both are a sixteenth of the cost used when creating keystores by default.
+pub const MIN_SCRYPT_N: u32 = 1 << 14;
+pub const MIN_PBKDF2_C: u32 = 1 << 14;
+
+/// How many times, and how often, to retry obtaining the lock on the definitions file whilst
+/// another process holds it.
+const DEFINITIONS_LOCK_ATTEMPTS: usize = 100;
+const DEFINITIONS_LOCK_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Returns the genesis validators root of the network in `env`, which is required by EIP-3076
+/// interchange files.
+pub(crate) fn genesis_validators_root<T: EthSpec>(env: &Environment<T>) -> Result<Hash256, String> {
+    env.testnet
+        .as_ref()
+        .ok_or("Unable to get testnet configuration from the environment")?
+        .beacon_state::<T>()
+        .map(|state: BeaconState<T>| state.genesis_validators_root)
+        .map_err(|e| {
+            format!(
+                "Unable to get genesis state, has genesis occurred? Detail: {:?}",
+                e
+            )
+        })
+}
+
+/// Writes the history of each of `pubkeys` in the `slashing_protection` database to an EIP-3076
+/// interchange file at `export_path`.
+pub(crate) fn export_slashing_protection(
+    slashing_protection: &SlashingDatabase,
+    export_path: &Path,
+    pubkeys: &[PublicKey],
+    genesis_validators_root: Hash256,
+) -> Result<(), String> {
+    let interchange =
+        slashing_protection_interchange(slashing_protection, pubkeys, genesis_validators_root)?;
+
+    let export_file = File::create(export_path).map_err(|e| {
+        format!(
+            "Unable to create interchange file at {}: {:?}",
+            export_path.display(),
+            e
+        )
+    })?;
+    interchange
+        .write_to(&export_file)
+        .map_err(|e| format!("Error writing interchange file: {:?}", e))
+}
+
+/// Returns an EIP-3076 interchange containing the history of each of `pubkeys` in the
+/// `slashing_protection` database.
+///
+/// Public keys without any history are included with empty records, so that the interchange is a
+/// complete baseline for those validators.
+pub(crate) fn slashing_protection_interchange(
+    slashing_protection: &SlashingDatabase,
+    pubkeys: &[PublicKey],
+    genesis_validators_root: Hash256,
+) -> Result<Interchange, String> {
+    let mut interchange = slashing_protection
+        .export_interchange_info(genesis_validators_root)
+        .map_err(|e| format!("Error exporting slashing protection: {:?}", e))?;
+
+    interchange
+        .data
+        .retain(|record| pubkeys.contains(&record.pubkey));
+    for pubkey in pubkeys {
+        if !interchange
+            .data
+            .iter()
+            .any(|record| &record.pubkey == pubkey)
+        {
+            interchange.data.push(InterchangeData {
+                pubkey: pubkey.clone(),
+                signed_blocks: vec![],
+                signed_attestations: vec![],
+            });
+        }
+    }
+
+    Ok(interchange)
+}
+
+/// Creates the validator definition for the keystore at `keystore_path`.
+///
+/// If `shared_password_path` is supplied, a definition with a `password` refers to the password in
+/// that file rather than storing its own copy.
+pub fn new_validator_definition(
+    keystore_path: &Path,
+    password: Option<ZeroizeString>,
+    shared_password_path: Option<&PathBuf>,
+    enabled: bool,
+    graffiti: &Option<GraffitiString>,
+) -> Result<ValidatorDefinition, String> {
+    let mut validator_def = match (password, shared_password_path) {
+        (Some(_), Some(password_path)) => ValidatorDefinition::new_keystore_with_password_path(
+            keystore_path,
+            password_path.clone(),
+        ),
+        (password, _) => ValidatorDefinition::new_keystore_with_password(keystore_path, password),
+    }
+    .map_err(|e| format!("Unable to create new validator definition: {:?}", e))?;
+    validator_def.enabled = enabled;
+    validator_def.graffiti = graffiti.clone();
+
+    Ok(validator_def)
+}
+
+/// Adds `new_defs` to the definitions file in `validator_dir` and replaces `defs` with the result.
+///
+/// An error is returned, and nothing is saved, if another process has added a definition for the
+/// same validator since `defs` was loaded.
+pub(crate) fn save_new_definitions(
+    defs: &mut ValidatorDefinitions,
+    validator_dir: &Path,
+    new_defs: Vec<ValidatorDefinition>,
+) -> Result<(), String> {
+    update_definitions(defs, validator_dir, |latest| {
+        for def in new_defs {
+            if latest
+                .as_slice()
+                .iter()
+                .any(|existing| existing.voting_public_key == def.voting_public_key)
+            {
+                return Err(format!(
+                    "Validator {} was added to {} by another process",
+                    def.voting_public_key.to_hex_string(),
+                    CONFIG_FILENAME
+                ));
+            }
+            latest.push(def);
+        }
+        Ok(())
+    })
+}
+
+/// Removes the definitions of `pubkeys` from the definitions file in `validator_dir` and replaces
+/// `defs` with the result.
+pub(crate) fn remove_definitions(
+    defs: &mut ValidatorDefinitions,
+    validator_dir: &Path,
+    pubkeys: &[&PublicKey],
+) -> Result<(), String> {
+    update_definitions(defs, validator_dir, |latest| {
+        latest.retain(|def| !pubkeys.contains(&&def.voting_public_key));
+        Ok(())
+    })
+}
+
+/// Applies `update` to the definitions file in `validator_dir` whilst holding a lock on it.
+///
+/// The file is re-read rather than saving `defs` directly, so that definitions saved by a
+/// concurrent import since `defs` was loaded are not overwritten. On success, `defs` is replaced
+/// with the definitions that were saved.
+pub(crate) fn update_definitions<F>(
+    defs: &mut ValidatorDefinitions,
+    validator_dir: &Path,
+    update: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut ValidatorDefinitions) -> Result<(), String>,
+{
+    let _lockfile = lock_definitions(validator_dir)?;
+
+    let mut latest = ValidatorDefinitions::open(validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+    update(&mut latest)?;
+    latest
+        .save(validator_dir)
+        .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+    *defs = latest;
+    Ok(())
+}
+
+/// Obtains the lock on the definitions file in `validator_dir`, waiting for any other import which
+/// is currently updating the file.
+fn lock_definitions(validator_dir: &Path) -> Result<Lockfile, String> {
+    let lockfile_path = validator_dir.join(format!("{}.lock", CONFIG_FILENAME));
+    let mut attempts = 0;
+    loop {
+        match Lockfile::new(lockfile_path.clone()) {
+            Ok(lockfile) => return Ok(lockfile),
+            Err(LockfileError::FileLocked(..)) if attempts < DEFINITIONS_LOCK_ATTEMPTS => {
+                attempts += 1;
+                sleep(DEFINITIONS_LOCK_RETRY_DELAY);
+            }
+            Err(e) => return Err(format!("Unable to lock {}: {:?}", CONFIG_FILENAME, e)),
+        }
+    }
+}
+
+/// Reads the password in the file at `path`, which must be valid UTF-8.
+pub(crate) fn read_password_file(path: &Path) -> Result<ZeroizeString, String> {
+    let password = read_password(path)
+        .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e))?;
+    String::from_utf8(password.as_bytes().to_vec())
+        .map(ZeroizeString::from)
+        .map_err(|_| format!("The password in {:?} is not valid UTF-8", path))
+}
+
+/// Sets the Unix permissions of `path` to `mode`, so that imported keystores and passwords are not
+/// readable by other users.
+///
+/// This is a no-op on other platforms.
+pub(crate) fn restrict_permissions(path: &Path, mode: u32) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))
+            .map_err(|e| format!("Unable to set permissions of {:?}: {:?}", path, e))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (path, mode);
+
+    Ok(())
+}
+
+/// The key derivation function, and its cost, used to re-encrypt keystores.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReencryptionKdf {
+    Scrypt { n: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl ReencryptionKdf {
+    /// Returns the KDF called `function` with the given `cost`, or the default cost if `None`.
+    ///
+    /// Returns an error if `cost` is below the minimum for the KDF.
+    pub fn new(function: &str, cost: Option<u32>) -> Result<Self, String> {
+        match function {
+            "scrypt" => {
+                let n = cost.unwrap_or(Scrypt::default_scrypt(vec![]).n);
+                if n < MIN_SCRYPT_N || !n.is_power_of_two() {
+                    return Err(format!(
+                        "Invalid --{} {} for scrypt, it must be a power of two of at least {}",
+                        KDF_COST_FLAG, n, MIN_SCRYPT_N
+                    ));
+                }
+                Ok(ReencryptionKdf::Scrypt { n })
+            }
+            "pbkdf2" => {
+                let c = cost.unwrap_or(DEFAULT_PBKDF2_C);
+                if c < MIN_PBKDF2_C {
+                    return Err(format!(
+                        "Invalid --{} {} for pbkdf2, it must be at least {}",
+                        KDF_COST_FLAG, c, MIN_PBKDF2_C
+                    ));
+                }
+                Ok(ReencryptionKdf::Pbkdf2 { c })
+            }
+            other => Err(format!("Unsupported --{}: {}", KDF_FLAG, other)),
+        }
+    }
+
+    /// Returns the parameters for a new keystore, with a random salt.
+    fn kdf(self) -> Kdf {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+        match self {
+            ReencryptionKdf::Scrypt { n } => Kdf::Scrypt(Scrypt {
+                n,
+                ..Scrypt::default_scrypt(salt)
+            }),
+            ReencryptionKdf::Pbkdf2 { c } => Kdf::Pbkdf2(Pbkdf2 {
+                c,
+                dklen: DKLEN,
+                prf: Prf::default(),
+                salt: salt.into(),
+            }),
+        }
+    }
+}
+
+/// Returns a new keystore for the keypair in `keystore`, encrypted with `new_password` using the
+/// parameters of `kdf`.
+///
+/// The derivation path and description are retained, but the new keystore has a new UUID.
+pub(crate) fn reencrypt_keystore(
+    keystore: &Keystore,
+    password: &ZeroizeString,
+    new_password: &ZeroizeString,
+    kdf: ReencryptionKdf,
+) -> Result<Keystore, String> {
+    let keypair = keystore.decrypt_keypair(password.as_ref()).map_err(|e| {
+        format!(
+            "Unable to decrypt keystore 0x{} for re-encryption: {:?}",
+            keystore.pubkey(),
+            e
+        )
+    })?;
+
+    let mut builder = KeystoreBuilder::new(
+        &keypair,
+        new_password.as_ref(),
+        keystore.path().unwrap_or_default(),
+    )
+    .map_err(|e| format!("Unable to re-encrypt keystore: {:?}", e))?
+    .kdf(kdf.kdf());
+    if let Some(description) = keystore.description() {
+        builder = builder.description(description.to_string());
+    }
+    builder
+        .build()
+        .map_err(|e| format!("Unable to re-encrypt keystore: {:?}", e))
+}
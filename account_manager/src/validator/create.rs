@@ -9,6 +9,7 @@ use directory::{
     ensure_dir_exists, parse_path_or_default_with_flag, DEFAULT_SECRET_DIR, DEFAULT_WALLET_DIR,
 };
 use environment::Environment;
+use eth2_wallet::PathTemplate;
 use eth2_wallet_manager::WalletManager;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::ffi::OsStr;
@@ -24,6 +25,8 @@ pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
+pub const DERIVATION_PATH_FLAG: &str = "derivation-path";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
 pub const WALLET_PASSWORD_PROMPT: &str = "Enter your wallet's password:";
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
@@ -103,6 +106,30 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .conflicts_with("count")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name(DERIVATION_PATH_FLAG)
+                .long(DERIVATION_PATH_FLAG)
+                .value_name("PATH_TEMPLATE")
+                .help(
+                    "A template for the derivation path of the voting keys, in which {index} is \
+                    replaced by the index of each validator. The withdrawal key is derived from \
+                    the parent of the voting key. Only required to reproduce keys which were \
+                    generated with a non-standard path by other tooling.",
+                )
+                .default_value("m/12381/3600/{index}/0/0")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help(
+                    "The index of the first validator to create. Must not be lower than the \
+                    index of the next validator of the wallet, so that no validator is created \
+                    twice. Defaults to the index of the next validator of the wallet.",
+                )
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name(STDIN_INPUTS_FLAG)
                 .long(STDIN_INPUTS_FLAG)
@@ -136,6 +163,8 @@ pub fn cli_run<T: EthSpec>(
         .unwrap_or(spec.max_effective_balance);
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
+    let template: PathTemplate = clap_utils::parse_required(matches, DERIVATION_PATH_FLAG)?;
+    let first_index: Option<u32> = clap_utils::parse_optional(matches, FIRST_INDEX_FLAG)?;
 
     ensure_dir_exists(&validator_dir)?;
     ensure_dir_exists(&secrets_dir)?;
@@ -197,15 +226,34 @@ pub fn cli_run<T: EthSpec>(
         )
     })?;
 
+    if let Some(first_index) = first_index {
+        let nextaccount = wallet.wallet().nextaccount();
+        if first_index < nextaccount {
+            return Err(format!(
+                "--{} {} is lower than the index of the next validator of the wallet, {}. \
+                Creating a validator twice can lead to slashing.",
+                FIRST_INDEX_FLAG, first_index, nextaccount
+            ));
+        }
+        wallet
+            .set_nextaccount(first_index)
+            .map_err(|e| format!("Unable to update wallet: {:?}", e))?;
+    }
+
+    if template != PathTemplate::default() {
+        eprintln!("Deriving keys with the non-standard path {}", template);
+    }
+
     for i in 0..n {
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
         let keystores = wallet
-            .next_validator(
+            .next_validator_with_template(
                 wallet_password.as_bytes(),
                 voting_password.as_bytes(),
                 withdrawal_password.as_bytes(),
+                &template,
             )
             .map_err(|e| format!("Unable to create validator keys: {:?}", e))?;
 
@@ -252,6 +300,7 @@ fn existing_validator_count<P: AsRef<Path>>(validator_dir: P) -> Result<usize, S
             iter.filter_map(|e| e.ok())
                 .filter(|e| {
                     e.file_name() != OsStr::new(validator_definitions::CONFIG_FILENAME)
+                        && e.file_name() != OsStr::new(validator_definitions::TOML_CONFIG_FILENAME)
                         && e.file_name()
                             != OsStr::new(slashing_protection::SLASHING_PROTECTION_FILENAME)
                 })
@@ -0,0 +1,209 @@
+use super::common::{export_slashing_protection, genesis_validators_root, remove_definitions};
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    read_input_from_user,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME},
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs;
+use std::path::PathBuf;
+use types::{EthSpec, PublicKey};
+
+pub const CMD: &str = "delete";
+pub const PUBKEY_FLAG: &str = "pubkey";
+pub const EXPORT_SLASHING_PROTECTION_FLAG: &str = "export-slashing-protection";
+pub const YES_FLAG: &str = "yes";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Deletes a validator from the validator directory. The history of the validator is \
+            exported from the slashing protection database first, so that it can be safely \
+            imported elsewhere. The validator client must not be running.",
+        )
+        .arg(
+            Arg::with_name(PUBKEY_FLAG)
+                .long(PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("The public key of the validator to delete.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXPORT_SLASHING_PROTECTION_FLAG)
+                .long(EXPORT_SLASHING_PROTECTION_FLAG)
+                .value_name("INTERCHANGE_FILE")
+                .help(
+                    "The path of the EIP-3076 interchange file to which the history of the \
+                    validator is written before it is deleted. An existing file is not \
+                    overwritten.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+        .arg(
+            Arg::with_name(YES_FLAG)
+                .long(YES_FLAG)
+                .help("If present, the validator is deleted without asking for confirmation."),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let pubkey: PublicKey = clap_utils::parse_required(matches, PUBKEY_FLAG)?;
+    let export_path: PathBuf =
+        clap_utils::parse_required(matches, EXPORT_SLASHING_PROTECTION_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let confirm = !matches.is_present(YES_FLAG);
+
+    if export_path.exists() {
+        return Err(format!("Refusing to overwrite existing {:?}", export_path));
+    }
+
+    let mut defs = ValidatorDefinitions::open(&validator_dir).map_err(|e| {
+        format!(
+            "No validator definitions found in {:?}: {:?}",
+            validator_dir, e
+        )
+    })?;
+    let (voting_keystore_path, voting_keystore_password_path) = defs
+        .as_slice()
+        .iter()
+        .find(|def| def.voting_public_key == pubkey)
+        .map(|def| match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                ..
+            } => (
+                voting_keystore_path.clone(),
+                voting_keystore_password_path.clone(),
+            ),
+        })
+        .ok_or_else(|| format!("No validator with public key {} found", pubkey))?;
+
+    // A password file which other validators refer to (e.g. one written by
+    // `--store-password-once`) is still required by them, so it is never removed.
+    let password_file_shared = voting_keystore_password_path
+        .as_ref()
+        .map_or(false, |path| {
+            defs.as_slice().iter().any(|def| {
+                def.voting_public_key != pubkey
+                    && matches!(
+                        &def.signing_definition,
+                        SigningDefinition::LocalKeystore {
+                            voting_keystore_password_path: Some(other_path),
+                            ..
+                        } if other_path == path
+                    )
+            })
+        });
+
+    // Only a directory which was created for the keystore by an import is removed, since a
+    // keystore elsewhere may be in a directory which contains other files.
+    let keystore_dir = voting_keystore_path
+        .parent()
+        .filter(|dir| dir.parent() == Some(validator_dir.as_path()))
+        .map(PathBuf::from);
+
+    // The database is never created, since a validator without any history cannot be safely
+    // imported elsewhere.
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection = SlashingDatabase::open(&slashing_protection_path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection database at {}: {:?}",
+            slashing_protection_path.display(),
+            e
+        )
+    })?;
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot delete validators while the validator client is running: {:?}",
+            e
+        )
+    })?;
+    let genesis_validators_root = genesis_validators_root(&env)?;
+
+    if confirm {
+        eprintln!();
+        eprintln!("Deleting validator {}.", pubkey);
+        match &keystore_dir {
+            Some(keystore_dir) => eprintln!(" - Keystore directory: {:?}", keystore_dir),
+            None => eprintln!(" - Keystore (not deleted): {:?}", voting_keystore_path),
+        }
+        match &voting_keystore_password_path {
+            Some(path) if password_file_shared => {
+                eprintln!(" - Password file (shared, not deleted): {:?}", path)
+            }
+            Some(path) => eprintln!(" - Password file: {:?}", path),
+            None => {}
+        }
+        eprintln!(" - Slashing protection export: {:?}", export_path);
+        eprintln!();
+        eprintln!("Do you want to continue with the deletion? [y/N]");
+
+        let input = read_input_from_user(stdin_inputs)?;
+        if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            eprintln!("Deletion cancelled, no files were modified.");
+            return Ok(());
+        }
+    }
+
+    // The history is exported before anything is removed, so that the validator can never be
+    // deleted without it.
+    export_slashing_protection(
+        &slashing_protection,
+        &export_path,
+        &[pubkey.clone()],
+        genesis_validators_root,
+    )?;
+    eprintln!("Exported slashing protection history to {:?}.", export_path);
+
+    // The definition is removed before the keystore, so that the validator client never attempts
+    // to load a missing keystore.
+    remove_definitions(&mut defs, &validator_dir, &[&pubkey])?;
+    eprintln!("Removed validator from {}.", CONFIG_FILENAME);
+
+    match keystore_dir {
+        Some(keystore_dir) => {
+            fs::remove_dir_all(&keystore_dir)
+                .map_err(|e| format!("Unable to remove {:?}: {:?}", keystore_dir, e))?;
+            eprintln!("Removed keystore directory {:?}.", keystore_dir);
+        }
+        None => eprintln!(
+            "The keystore {:?} is outside of the validator directory and was not removed.",
+            voting_keystore_path
+        ),
+    }
+
+    match voting_keystore_password_path {
+        Some(path) if password_file_shared => eprintln!(
+            "The password file {:?} is used by other validators and was not removed.",
+            path
+        ),
+        Some(path) if path.exists() => {
+            fs::remove_file(&path)
+                .map_err(|e| format!("Unable to remove password file {:?}: {:?}", path, e))?;
+            eprintln!("Removed password file {:?}.", path);
+        }
+        _ => {}
+    }
+
+    // The history is retained, so that the validator is still protected if it is imported again.
+    eprintln!(
+        "Successfully deleted validator {}. Its history remains in {}.",
+        pubkey, SLASHING_PROTECTION_FILENAME
+    );
+
+    Ok(())
+}
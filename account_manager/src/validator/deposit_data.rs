@@ -0,0 +1,315 @@
+use super::common::read_password_file;
+use super::ledger;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{read_password_from_user, ZeroizeString};
+use bls::{get_withdrawal_credentials, Keypair};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_keystore::Keystore;
+use serde_derive::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use tree_hash::TreeHash;
+use types::{ChainSpec, DepositData, EthSpec, Hash256, PublicKey, SignatureBytes, SignedRoot};
+
+pub const CMD: &str = "deposit-data";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const WITHDRAWAL_PUBKEY_FLAG: &str = "withdrawal-pubkey";
+pub const WITHDRAWAL_ADDRESS_FLAG: &str = "withdrawal-address";
+pub const WITHDRAWAL_LEDGER_INDEX_FLAG: &str = "withdrawal-ledger-index";
+pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
+pub const OUTPUT_FLAG: &str = "output";
+
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password";
+
+/// The prefix of withdrawal credentials which withdraw to an execution address.
+pub const ETH1_ADDRESS_WITHDRAWAL_PREFIX: u8 = 0x01;
+/// The version of the `eth2.0-deposit-cli` whose deposit data format is produced. The launchpad
+/// refuses deposit data without a version.
+pub const DEPOSIT_CLI_VERSION: &str = "1.2.0";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Regenerates the deposit data of existing voting keystores, in the deposit_data-*.json \
+            format of the eth2.0-deposit-cli. The withdrawal credentials must be the same as in \
+            the original deposit, otherwise the deposit will not match the validator.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to an EIP-2335 voting keystore. May be given more than once.")
+                .required(true)
+                .multiple(true)
+                .number_of_values(1)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help(
+                    "The path to a file containing the password of every keystore. If omitted, \
+                    the password of each keystore is prompted for.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WITHDRAWAL_PUBKEY_FLAG)
+                .long(WITHDRAWAL_PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("The BLS withdrawal public key, for 0x00 withdrawal credentials.")
+                .conflicts_with_all(&[WITHDRAWAL_ADDRESS_FLAG, WITHDRAWAL_LEDGER_INDEX_FLAG])
+                .required_unless_one(&[WITHDRAWAL_ADDRESS_FLAG, WITHDRAWAL_LEDGER_INDEX_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WITHDRAWAL_ADDRESS_FLAG)
+                .long(WITHDRAWAL_ADDRESS_FLAG)
+                .value_name("ADDRESS")
+                .help("The 0x-prefixed execution address, for 0x01 withdrawal credentials.")
+                .conflicts_with_all(&[WITHDRAWAL_PUBKEY_FLAG, WITHDRAWAL_LEDGER_INDEX_FLAG])
+                .required_unless_one(&[WITHDRAWAL_PUBKEY_FLAG, WITHDRAWAL_LEDGER_INDEX_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WITHDRAWAL_LEDGER_INDEX_FLAG)
+                .long(WITHDRAWAL_LEDGER_INDEX_FLAG)
+                .value_name("INDEX")
+                .help(
+                    "The index of a withdrawal key held by a Ledger device, for 0x00 withdrawal \
+                    credentials. The public key of m/12381/3600/INDEX/0 is read from the device \
+                    and must be approved there. Requires the ledger feature.",
+                )
+                .conflicts_with_all(&[WITHDRAWAL_PUBKEY_FLAG, WITHDRAWAL_ADDRESS_FLAG])
+                .required_unless_one(&[WITHDRAWAL_PUBKEY_FLAG, WITHDRAWAL_ADDRESS_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DEPOSIT_GWEI_FLAG)
+                .long(DEPOSIT_GWEI_FLAG)
+                .value_name("DEPOSIT_GWEI")
+                .help(
+                    "The GWEI value of the deposit amount. Defaults to the minimum amount \
+                    required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_FLAG)
+                .long(OUTPUT_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path of the deposit data file to create. An existing file is not \
+                    overwritten.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+/// A single entry of a deposit data file, as produced by the `eth2.0-deposit-cli`.
+///
+/// All byte values are hex encoded without a `0x` prefix.
+#[derive(Serialize)]
+pub struct DepositDataEntry {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_name: Option<String>,
+    pub deposit_cli_version: String,
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let keystore_paths = matches
+        .values_of(KEYSTORE_FLAG)
+        .ok_or_else(|| format!("--{} is required", KEYSTORE_FLAG))?
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    let password_file: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let withdrawal_pubkey: Option<PublicKey> =
+        clap_utils::parse_optional(matches, WITHDRAWAL_PUBKEY_FLAG)?;
+    let withdrawal_address = matches
+        .value_of(WITHDRAWAL_ADDRESS_FLAG)
+        .map(parse_withdrawal_address)
+        .transpose()?;
+    let withdrawal_ledger_index: Option<u32> =
+        clap_utils::parse_optional(matches, WITHDRAWAL_LEDGER_INDEX_FLAG)?;
+    let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let spec = &env.eth2_config.spec;
+    let deposit_gwei = clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
+        .unwrap_or(spec.max_effective_balance);
+
+    if output_path.exists() {
+        return Err(format!("Refusing to overwrite existing {:?}", output_path));
+    }
+
+    let withdrawal_pubkey = match (withdrawal_pubkey, withdrawal_ledger_index) {
+        (Some(pubkey), _) => Some(pubkey),
+        (None, Some(index)) => Some(ledger::withdrawal_public_key(index)?),
+        (None, None) => None,
+    };
+    let withdrawal_credentials = match (withdrawal_pubkey, withdrawal_address) {
+        (Some(pubkey), _) => Hash256::from_slice(&get_withdrawal_credentials(
+            &pubkey,
+            spec.bls_withdrawal_prefix_byte,
+        )),
+        (None, Some(address)) => {
+            let mut credentials = [0; 32];
+            credentials[0] = ETH1_ADDRESS_WITHDRAWAL_PREFIX;
+            credentials[12..].copy_from_slice(&address);
+            Hash256::from(credentials)
+        }
+        (None, None) => {
+            return Err(format!(
+                "One of --{}, --{} or --{} is required",
+                WITHDRAWAL_PUBKEY_FLAG, WITHDRAWAL_ADDRESS_FLAG, WITHDRAWAL_LEDGER_INDEX_FLAG
+            ))
+        }
+    };
+
+    let password = password_file
+        .map(|path| read_password_file(&path))
+        .transpose()?;
+    let network_name = env
+        .testnet
+        .as_ref()
+        .and_then(|config| config.yaml_config.as_ref())
+        .map(|config| config.config_name.clone());
+
+    let mut entries = Vec::with_capacity(keystore_paths.len());
+    for keystore_path in &keystore_paths {
+        let keypair = decrypt_keystore(keystore_path, password.as_ref(), stdin_inputs)?;
+        let entry = deposit_data_entry(
+            &keypair,
+            withdrawal_credentials,
+            deposit_gwei,
+            network_name.clone(),
+            spec,
+        )?;
+        eprintln!(
+            "Created deposit data for {} from {:?}.",
+            keypair.pk, keystore_path
+        );
+        entries.push(entry);
+    }
+
+    let json = serde_json::to_vec(&entries)
+        .map_err(|e| format!("Unable to encode deposit data: {:?}", e))?;
+    fs::write(&output_path, &json)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", output_path, e))?;
+
+    eprintln!(
+        "Wrote the deposit data of {} validators to {:?}.",
+        entries.len(),
+        output_path
+    );
+    eprintln!(
+        "Withdrawal credentials: {:?}. Please check that they match the original deposits.",
+        withdrawal_credentials
+    );
+
+    Ok(())
+}
+
+/// Signs the deposit data for `keypair`, checking the signature against the deposit domain.
+fn deposit_data_entry(
+    keypair: &Keypair,
+    withdrawal_credentials: Hash256,
+    amount: u64,
+    network_name: Option<String>,
+    spec: &ChainSpec,
+) -> Result<DepositDataEntry, String> {
+    let mut deposit_data = DepositData {
+        pubkey: keypair.pk.clone().into(),
+        withdrawal_credentials,
+        amount,
+        signature: SignatureBytes::empty(),
+    };
+    deposit_data.signature = deposit_data.create_signature(&keypair.sk, spec);
+
+    let deposit_message = deposit_data.as_deposit_message();
+    let signature = deposit_data
+        .signature
+        .decompress()
+        .map_err(|e| format!("Invalid deposit signature: {:?}", e))?;
+    if !signature.verify(
+        &keypair.pk,
+        deposit_message.signing_root(spec.get_deposit_domain()),
+    ) {
+        return Err(format!("Invalid deposit signature for {}", keypair.pk));
+    }
+
+    Ok(DepositDataEntry {
+        pubkey: hex::encode(deposit_data.pubkey.as_serialized()),
+        withdrawal_credentials: hex::encode(withdrawal_credentials.as_bytes()),
+        amount,
+        signature: hex::encode(deposit_data.signature.serialize()),
+        deposit_message_root: hex::encode(deposit_message.tree_hash_root().as_bytes()),
+        deposit_data_root: hex::encode(deposit_data.tree_hash_root().as_bytes()),
+        fork_version: hex::encode(spec.genesis_fork_version),
+        network_name,
+        deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+    })
+}
+
+/// Decrypts the keystore at `keystore_path` with `password`, or with a password read from the
+/// user if there is none.
+fn decrypt_keystore(
+    keystore_path: &PathBuf,
+    password: Option<&ZeroizeString>,
+    stdin_inputs: bool,
+) -> Result<Keypair, String> {
+    let keystore = Keystore::from_json_file(keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+
+    let password = match password {
+        Some(password) => password.clone(),
+        None => {
+            eprintln!();
+            eprintln!("{} for {:?}: ", PASSWORD_PROMPT, keystore_path);
+            read_password_from_user(stdin_inputs)?
+        }
+    };
+
+    keystore
+        .decrypt_keypair(password.as_ref())
+        .map_err(|e| match e {
+            eth2_keystore::Error::InvalidPassword => {
+                format!("Invalid password for {:?}", keystore_path)
+            }
+            e => format!("Error while decrypting {:?}: {:?}", keystore_path, e),
+        })
+}
+
+/// Parses a 0x-prefixed, 20 byte execution address.
+fn parse_withdrawal_address(address: &str) -> Result<[u8; 20], String> {
+    let bytes = address
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Withdrawal address {} must start with 0x", address))
+        .and_then(|hex_address| {
+            hex::decode(hex_address)
+                .map_err(|e| format!("Invalid withdrawal address {}: {:?}", address, e))
+        })?;
+
+    let mut result = [0; 20];
+    if bytes.len() != result.len() {
+        return Err(format!("Withdrawal address {} must be 20 bytes", address));
+    }
+    result.copy_from_slice(&bytes);
+    Ok(result)
+}
@@ -1,4 +1,5 @@
 use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::create_with_600_perms;
 use bls::{Keypair, PublicKey};
 use clap::{App, Arg, ArgMatches};
 use environment::Environment;
@@ -10,15 +11,26 @@ use eth2_keystore::Keystore;
 use eth2_network_config::Eth2NetworkConfig;
 use safe_arith::SafeArith;
 use slot_clock::{SlotClock, SystemTimeSlotClock};
+use ssz::{Decode, Encode};
+use std::fs;
 use std::path::PathBuf;
 use std::time::Duration;
 use tokio_compat_02::FutureExt;
-use types::{ChainSpec, Epoch, EthSpec, Fork, VoluntaryExit};
+use types::{
+    ChainSpec, Domain, Epoch, EthSpec, Fork, SignedRoot, SignedVoluntaryExit, VoluntaryExit,
+};
 
 pub const CMD: &str = "exit";
 pub const KEYSTORE_FLAG: &str = "keystore";
 pub const PASSWORD_FILE_FLAG: &str = "password-file";
 pub const BEACON_SERVER_FLAG: &str = "beacon-node";
+pub const OFFLINE_FLAG: &str = "offline";
+pub const VALIDATOR_INDEX_FLAG: &str = "validator-index";
+pub const EPOCH_FLAG: &str = "epoch";
+pub const FORK_VERSION_FLAG: &str = "fork-version";
+pub const OUTPUT_FLAG: &str = "output";
+pub const OUTPUT_FORMAT_FLAG: &str = "output-format";
+pub const BROADCAST_FLAG: &str = "broadcast";
 pub const PASSWORD_PROMPT: &str = "Enter the keystore password";
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
@@ -28,14 +40,18 @@ pub const PROMPT: &str = "WARNING: WITHDRAWING STAKED ETH IS NOT CURRENTLY POSSI
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
     App::new("exit")
-        .about("Submits a VoluntaryExit to the beacon chain for a given validator keystore.")
+        .about(
+            "Submits a VoluntaryExit to the beacon chain for a given validator keystore. With \
+            --offline, the exit is signed without a beacon node and written to a file, which can \
+            later be submitted with --broadcast.",
+        )
         .arg(
             Arg::with_name(KEYSTORE_FLAG)
                 .long(KEYSTORE_FLAG)
                 .value_name("KEYSTORE_PATH")
                 .help("The path to the EIP-2335 voting keystore for the validator")
                 .takes_value(true)
-                .required(true),
+                .required_unless(BROADCAST_FLAG),
         )
         .arg(
             Arg::with_name(PASSWORD_FILE_FLAG)
@@ -57,26 +73,121 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .long(STDIN_INPUTS_FLAG)
                 .help("If present, read all user inputs from stdin instead of tty."),
         )
+        .arg(
+            Arg::with_name(OFFLINE_FLAG)
+                .long(OFFLINE_FLAG)
+                .help(
+                    "If present, the exit is signed without contacting a beacon node and written \
+                    to --output. The exit is not published.",
+                )
+                .requires_all(&[VALIDATOR_INDEX_FLAG, OUTPUT_FLAG])
+                .conflicts_with(BROADCAST_FLAG),
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_INDEX_FLAG)
+                .long(VALIDATOR_INDEX_FLAG)
+                .value_name("INDEX")
+                .help("The index of the validator in the beacon chain.")
+                .requires(OFFLINE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EPOCH_FLAG)
+                .long(EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The earliest epoch at which the exit can be included. Defaults to the \
+                    current epoch, according to the system clock.",
+                )
+                .requires(OFFLINE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FORK_VERSION_FLAG)
+                .long(FORK_VERSION_FLAG)
+                .value_name("FORK_VERSION")
+                .help(
+                    "The 0x-prefixed fork version of the epoch of the exit, which is used in the \
+                    signature. Defaults to the genesis fork version of the network.",
+                )
+                .requires(OFFLINE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_FLAG)
+                .long(OUTPUT_FLAG)
+                .value_name("PATH")
+                .help("The path of the file to which the signed exit is written.")
+                .requires(OFFLINE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OUTPUT_FORMAT_FLAG)
+                .long(OUTPUT_FORMAT_FLAG)
+                .value_name("FORMAT")
+                .help("The encoding of the signed exit written to --output. Defaults to json.")
+                .possible_values(&["json", "ssz"])
+                .requires(OFFLINE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BROADCAST_FLAG)
+                .long(BROADCAST_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The path of a signed exit created with --offline, in either JSON or SSZ. The \
+                    exit is checked against the validator in the beacon state, then published.",
+                )
+                .conflicts_with_all(&[KEYSTORE_FLAG, PASSWORD_FILE_FLAG])
+                .takes_value(true),
+        )
 }
 
 pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
-    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
     let password_file_path: Option<PathBuf> =
         clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
     let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
 
     let spec = env.eth2_config().spec.clone();
+
+    let testnet_config = env
+        .testnet
+        .clone()
+        .expect("network should have a valid config");
+
+    if matches.is_present(OFFLINE_FLAG) {
+        let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+        return sign_voluntary_exit_offline::<E>(
+            matches,
+            &keystore_path,
+            password_file_path.as_ref(),
+            &spec,
+            stdin_inputs,
+            &testnet_config,
+        );
+    }
+
     let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
     let client = BeaconNodeHttpClient::new(
         Url::parse(&server_url)
             .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
     );
 
-    let testnet_config = env
-        .testnet
-        .clone()
-        .expect("network should have a valid config");
+    let signed_exit_path: Option<PathBuf> = clap_utils::parse_optional(matches, BROADCAST_FLAG)?;
+    if let Some(signed_exit_path) = signed_exit_path {
+        return env.runtime().block_on(
+            broadcast_voluntary_exit::<E>(
+                &signed_exit_path,
+                &client,
+                &spec,
+                stdin_inputs,
+                &testnet_config,
+            )
+            .compat(),
+        );
+    }
 
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
     env.runtime().block_on(
         publish_voluntary_exit::<E>(
             &keystore_path,
@@ -101,26 +212,7 @@ async fn publish_voluntary_exit<E: EthSpec>(
     stdin_inputs: bool,
     testnet_config: &Eth2NetworkConfig,
 ) -> Result<(), String> {
-    let genesis_data = get_geneisis_data(client).await?;
-    let testnet_genesis_root = testnet_config
-        .beacon_state::<E>()
-        .as_ref()
-        .expect("network should have valid genesis state")
-        .genesis_validators_root;
-
-    // Verify that the beacon node and validator being exited are on the same network.
-    if genesis_data.genesis_validators_root != testnet_genesis_root {
-        return Err(
-            "Invalid genesis state. Please ensure that your beacon node is on the same network \
-                 as the validator you are publishing an exit for"
-                .to_string(),
-        );
-    }
-
-    // Return immediately if beacon node is not synced
-    if is_syncing(client).await? {
-        return Err("Beacon node is still syncing".to_string());
-    }
+    let genesis_data = get_checked_genesis_data::<E>(client, testnet_config).await?;
 
     let keypair = load_voting_keypair(keystore_path, password_file_path, stdin_inputs)?;
 
@@ -133,10 +225,172 @@ async fn publish_voluntary_exit<E: EthSpec>(
         epoch,
         validator_index,
     };
+    let signed_voluntary_exit = voluntary_exit.sign(
+        &keypair.sk,
+        &fork,
+        genesis_data.genesis_validators_root,
+        spec,
+    );
+
+    confirm_and_publish(client, &signed_voluntary_exit, &keypair.pk, stdin_inputs).await
+}
+
+/// Signs a voluntary exit without a beacon node, using the validator index, epoch and fork version
+/// given in `matches`, and writes it to the `--output` file.
+fn sign_voluntary_exit_offline<E: EthSpec>(
+    matches: &ArgMatches,
+    keystore_path: &PathBuf,
+    password_file_path: Option<&PathBuf>,
+    spec: &ChainSpec,
+    stdin_inputs: bool,
+    testnet_config: &Eth2NetworkConfig,
+) -> Result<(), String> {
+    let validator_index: u64 = clap_utils::parse_required(matches, VALIDATOR_INDEX_FLAG)?;
+    let epoch: Option<Epoch> = clap_utils::parse_optional(matches, EPOCH_FLAG)?;
+    let fork_version = matches
+        .value_of(FORK_VERSION_FLAG)
+        .map(parse_fork_version)
+        .transpose()?
+        .unwrap_or(spec.genesis_fork_version);
+    let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FLAG)?;
+    let output_format: Option<String> = clap_utils::parse_optional(matches, OUTPUT_FORMAT_FLAG)?;
+
+    if output_path.exists() {
+        return Err(format!("Refusing to overwrite existing {:?}", output_path));
+    }
+
+    let genesis_state = testnet_config
+        .beacon_state::<E>()
+        .map_err(|e| format!("Unable to get genesis state of the network: {:?}", e))?;
+    let epoch = match epoch {
+        Some(epoch) => epoch,
+        None => get_current_epoch::<E>(genesis_state.genesis_time, spec)
+            .ok_or("Failed to get current epoch. Please check your system time or use --epoch")?,
+    };
 
+    let keypair = load_voting_keypair(keystore_path, password_file_path, stdin_inputs)?;
+
+    // The fork only needs to give `fork_version` for `epoch`.
+    let fork = Fork {
+        previous_version: fork_version,
+        current_version: fork_version,
+        epoch,
+    };
+    let signed_voluntary_exit = VoluntaryExit {
+        epoch,
+        validator_index,
+    }
+    .sign(
+        &keypair.sk,
+        &fork,
+        genesis_state.genesis_validators_root,
+        spec,
+    );
+
+    let bytes = match output_format.as_deref() {
+        Some("ssz") => signed_voluntary_exit.as_ssz_bytes(),
+        _ => serde_json::to_vec_pretty(&signed_voluntary_exit)
+            .map_err(|e| format!("Unable to encode voluntary exit: {:?}", e))?,
+    };
+    // The signed exit can be published by anyone who obtains it, so it is only readable by the user.
+    create_with_600_perms(&output_path, &bytes)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", output_path, e))?;
+
+    eprintln!(
+        "Signed a voluntary exit for validator {} (index {}) at epoch {}.",
+        keypair.pk, validator_index, epoch
+    );
+    eprintln!(
+        "The exit was written to {:?}. It can be published with --{}.",
+        output_path, BROADCAST_FLAG
+    );
+    eprintln!("WARNING: ONCE PUBLISHED, THE EXIT IS IRREVERSIBLE. STORE THE FILE SECURELY.");
+
+    Ok(())
+}
+
+/// Reads a voluntary exit signed with `--offline`, checks it against the beacon state and
+/// publishes it.
+async fn broadcast_voluntary_exit<E: EthSpec>(
+    signed_exit_path: &PathBuf,
+    client: &BeaconNodeHttpClient,
+    spec: &ChainSpec,
+    stdin_inputs: bool,
+    testnet_config: &Eth2NetworkConfig,
+) -> Result<(), String> {
+    let bytes = fs::read(signed_exit_path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", signed_exit_path, e))?;
+    let signed_voluntary_exit = serde_json::from_slice::<SignedVoluntaryExit>(&bytes)
+        .or_else(|_| SignedVoluntaryExit::from_ssz_bytes(&bytes))
+        .map_err(|e| {
+            format!(
+                "{:?} is neither a JSON nor an SSZ voluntary exit: {:?}",
+                signed_exit_path, e
+            )
+        })?;
+
+    let genesis_data = get_checked_genesis_data::<E>(client, testnet_config).await?;
+
+    let validator_index = signed_voluntary_exit.message.validator_index;
+    let validator_pubkey = client
+        .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(validator_index))
+        .await
+        .map_err(|e| format!("Failed to get validator details: {:?}", e))?
+        .ok_or_else(|| {
+            format!(
+                "Validator {} is not present in the beacon state",
+                validator_index
+            )
+        })?
+        .data
+        .validator
+        .pubkey
+        .decompress()
+        .map_err(|e| format!("Invalid validator public key: {:?}", e))?;
+
+    let epoch = get_current_epoch::<E>(genesis_data.genesis_time, spec)
+        .ok_or("Failed to get current epoch. Please check your system time")?;
+    get_validator_index_for_exit(client, &validator_pubkey, epoch, spec).await?;
+
+    // An exit signed with the wrong fork version, network or key would be rejected.
+    let fork = get_beacon_state_fork(client).await?;
+    let domain = spec.get_domain(
+        signed_voluntary_exit.message.epoch,
+        Domain::VoluntaryExit,
+        &fork,
+        genesis_data.genesis_validators_root,
+    );
+    let message = signed_voluntary_exit.message.signing_root(domain);
+    if !signed_voluntary_exit
+        .signature
+        .verify(&validator_pubkey, message)
+    {
+        return Err(format!(
+            "The signature of the voluntary exit for validator {} is invalid. Please check the \
+            key, network and --{} used to sign it.",
+            validator_index, FORK_VERSION_FLAG
+        ));
+    }
+
+    confirm_and_publish(
+        client,
+        &signed_voluntary_exit,
+        &validator_pubkey,
+        stdin_inputs,
+    )
+    .await
+}
+
+/// Asks the user for the exit phrase, then publishes `signed_voluntary_exit` if it was entered.
+async fn confirm_and_publish(
+    client: &BeaconNodeHttpClient,
+    signed_voluntary_exit: &SignedVoluntaryExit,
+    validator_pubkey: &PublicKey,
+    stdin_inputs: bool,
+) -> Result<(), String> {
     eprintln!(
         "Publishing a voluntary exit for validator: {} \n",
-        keypair.pk
+        validator_pubkey
     );
     eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
     eprintln!("{}\n", PROMPT);
@@ -148,32 +402,44 @@ async fn publish_voluntary_exit<E: EthSpec>(
 
     let confirmation = account_utils::read_input_from_user(stdin_inputs)?;
     if confirmation == CONFIRMATION_PHRASE {
-        // Sign and publish the voluntary exit to network
-        let signed_voluntary_exit = voluntary_exit.sign(
-            &keypair.sk,
-            &fork,
-            genesis_data.genesis_validators_root,
-            spec,
-        );
+        // Publish the voluntary exit to network
         client
-            .post_beacon_pool_voluntary_exits(&signed_voluntary_exit)
+            .post_beacon_pool_voluntary_exits(signed_voluntary_exit)
             .await
             .map_err(|e| format!("Failed to publish voluntary exit: {}", e))?;
         tokio::time::sleep(std::time::Duration::from_secs(1)).await; // Provides nicer UX.
         eprintln!(
             "Successfully validated and published voluntary exit for validator {}",
-            keypair.pk
+            validator_pubkey
         );
     } else {
         eprintln!(
             "Did not publish voluntary exit for validator {}. Please check that you entered the correct exit phrase.",
-            keypair.pk
+            validator_pubkey
         );
     }
 
     Ok(())
 }
 
+/// Parses a 0x-prefixed, 4 byte fork version.
+fn parse_fork_version(fork_version: &str) -> Result<[u8; 4], String> {
+    let bytes = fork_version
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Fork version {} must start with 0x", fork_version))
+        .and_then(|hex_version| {
+            hex::decode(hex_version)
+                .map_err(|e| format!("Invalid fork version {}: {:?}", fork_version, e))
+        })?;
+
+    let mut version = [0; 4];
+    if bytes.len() != version.len() {
+        return Err(format!("Fork version {} must be 4 bytes", fork_version));
+    }
+    version.copy_from_slice(&bytes);
+    Ok(version)
+}
+
 /// Get the validator index of a given the validator public key by querying the beacon node endpoint.
 ///
 /// Returns an error if the beacon endpoint returns an error or given validator is not eligible for an exit.
@@ -223,6 +489,36 @@ async fn get_validator_index_for_exit(
     }
 }
 
+/// Get genesis data from the beacon node client, checking that it is on the same network as
+/// `testnet_config` and that it is synced.
+async fn get_checked_genesis_data<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    testnet_config: &Eth2NetworkConfig,
+) -> Result<GenesisData, String> {
+    let genesis_data = get_geneisis_data(client).await?;
+    let testnet_genesis_root = testnet_config
+        .beacon_state::<E>()
+        .as_ref()
+        .expect("network should have valid genesis state")
+        .genesis_validators_root;
+
+    // Verify that the beacon node and validator being exited are on the same network.
+    if genesis_data.genesis_validators_root != testnet_genesis_root {
+        return Err(
+            "Invalid genesis state. Please ensure that your beacon node is on the same network \
+                 as the validator you are publishing an exit for"
+                .to_string(),
+        );
+    }
+
+    // Return immediately if beacon node is not synced
+    if is_syncing(client).await? {
+        return Err("Beacon node is still syncing".to_string());
+    }
+
+    Ok(genesis_data)
+}
+
 /// Get genesis data by querying the beacon node client.
 async fn get_geneisis_data(client: &BeaconNodeHttpClient) -> Result<GenesisData, String> {
     Ok(client
@@ -0,0 +1,269 @@
+use super::archive::create_archive;
+use super::common::{genesis_validators_root, slashing_protection_interchange};
+use account_utils::{
+    create_with_600_perms,
+    eth2_keystore::{
+        encrypt,
+        json_keystore::{
+            Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, Kdf,
+            KdfModule, Scrypt, Sha256Checksum,
+        },
+        IV_SIZE, SALT_SIZE,
+    },
+    read_password,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions},
+};
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use rand::Rng;
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use types::{EthSpec, PublicKey};
+
+pub const CMD: &str = "export";
+pub const OUTPUT_FLAG: &str = "output";
+pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const ALL_FLAG: &str = "all";
+pub const INCLUDE_PASSWORDS_FLAG: &str = "include-passwords";
+
+/// The name of the EIP-3076 interchange file within an exported archive.
+pub const INTERCHANGE_FILENAME: &str = "slashing_protection.json";
+/// The name of the file of encrypted keystore passwords within an exported archive.
+pub const PASSWORDS_FILENAME: &str = "passwords.json";
+
+pub const EXPORT_WARNING: &str = "DO NOT START THE EXPORTED VALIDATORS ON ANOTHER MACHINE WHILST \
+                                  THEY ARE ENABLED HERE, OR YOU WILL GET SLASHED.";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Exports validators into a .zip archive containing their EIP-2335 keystores and an \
+            EIP-3076 slashing protection interchange file. The archive can be imported on another \
+            machine with `validator import --archive`, which also applies the slashing protection \
+            history it contains.",
+        )
+        .arg(
+            Arg::with_name(OUTPUT_FLAG)
+                .long(OUTPUT_FLAG)
+                .value_name("ARCHIVE_PATH")
+                .help(
+                    "The path of the .zip archive to create. An existing file is not overwritten.",
+                )
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PUBKEYS_FLAG)
+                .long(PUBKEYS_FLAG)
+                .value_name("PUBKEYS")
+                .help("A comma-separated list of the public keys of the validators to export.")
+                .conflicts_with(ALL_FLAG)
+                .required_unless(ALL_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help("If present, all validators in the validator directory are exported.")
+                .conflicts_with(PUBKEYS_FLAG)
+                .required_unless(PUBKEYS_FLAG),
+        )
+        .arg(
+            Arg::with_name(INCLUDE_PASSWORDS_FLAG)
+                .long(INCLUDE_PASSWORDS_FLAG)
+                .value_name("PASSWORD_FILE")
+                .help(
+                    "If present, the stored password of each keystore is included in the archive, \
+                    encrypted with the password in this file. The passwords are written to \
+                    passwords.json as EIP-2335 crypto objects, keyed by public key.",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let output_path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FLAG)?;
+    let include_passwords: Option<PathBuf> =
+        clap_utils::parse_optional(matches, INCLUDE_PASSWORDS_FLAG)?;
+    let pubkeys: Option<Vec<PublicKey>> = matches
+        .value_of(PUBKEYS_FLAG)
+        .map(|pubkeys| {
+            pubkeys
+                .split(',')
+                .map(|pubkey| {
+                    pubkey
+                        .trim()
+                        .parse()
+                        .map_err(|e| format!("Invalid public key {}: {:?}", pubkey, e))
+                })
+                .collect::<Result<_, _>>()
+        })
+        .transpose()?;
+
+    if output_path.exists() {
+        return Err(format!("Refusing to overwrite existing {:?}", output_path));
+    }
+
+    let export_password = include_passwords
+        .map(|path| {
+            let password = read_password(&path)
+                .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e))?;
+            if password.as_bytes().is_empty() {
+                return Err(format!("The password in {:?} is empty", path));
+            }
+            Ok(password)
+        })
+        .transpose()?;
+
+    let defs = ValidatorDefinitions::open(&validator_dir).map_err(|e| {
+        format!(
+            "No validator definitions found in {:?}: {:?}",
+            validator_dir, e
+        )
+    })?;
+
+    let selected = match &pubkeys {
+        Some(pubkeys) => pubkeys
+            .iter()
+            .map(|pubkey| {
+                defs.as_slice()
+                    .iter()
+                    .find(|def| &def.voting_public_key == pubkey)
+                    .ok_or_else(|| format!("No validator with public key {} found", pubkey))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        None => defs.as_slice().iter().collect(),
+    };
+    if selected.is_empty() {
+        return Err(format!("No validators found in {:?}", validator_dir));
+    }
+
+    // The history is exported for the selected validators only, so the database must not be
+    // modified by a running validator client whilst it is read.
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection = SlashingDatabase::open(&slashing_protection_path).map_err(|e| {
+        format!(
+            "Unable to open slashing protection database at {}: {:?}",
+            slashing_protection_path.display(),
+            e
+        )
+    })?;
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot export validators while the validator client is running: {:?}",
+            e
+        )
+    })?;
+
+    let mut files = vec![];
+    let mut passwords = BTreeMap::new();
+    for def in &selected {
+        let pubkey = def.voting_public_key.to_hex_string();
+        let voting_keystore_path = match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => voting_keystore_path,
+        };
+
+        // Each keystore is placed in a directory named after its public key, in the same way as
+        // the validator directory, so that keystores with the same file name do not collide.
+        let file_name = voting_keystore_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| format!("Badly formatted file name: {:?}", voting_keystore_path))?;
+        let keystore = fs::read(voting_keystore_path).map_err(|e| {
+            format!(
+                "Unable to read keystore {:?}: {:?}",
+                voting_keystore_path, e
+            )
+        })?;
+        files.push((format!("{}/{}", pubkey, file_name), keystore));
+
+        if let Some(export_password) = &export_password {
+            let password = def
+                .signing_definition
+                .voting_keystore_password()
+                .map_err(|e| format!("Unable to read password for {}: {:?}", pubkey, e))?;
+            match password {
+                Some(password) => {
+                    passwords.insert(
+                        pubkey,
+                        encrypt_password(password.as_bytes(), export_password.as_bytes())?,
+                    );
+                }
+                None => eprintln!("WARNING: No password is stored for {}", pubkey),
+            }
+        }
+    }
+
+    let pubkeys = selected
+        .iter()
+        .map(|def| def.voting_public_key.clone())
+        .collect::<Vec<_>>();
+    let interchange = slashing_protection_interchange(
+        &slashing_protection,
+        &pubkeys,
+        genesis_validators_root(&env)?,
+    )?;
+    let mut interchange_bytes = vec![];
+    interchange
+        .write_to(&mut interchange_bytes)
+        .map_err(|e| format!("Error writing interchange file: {:?}", e))?;
+    files.push((INTERCHANGE_FILENAME.to_string(), interchange_bytes));
+
+    if export_password.is_some() {
+        let passwords_bytes = serde_json::to_vec_pretty(&passwords)
+            .map_err(|e| format!("Unable to encode passwords: {:?}", e))?;
+        files.push((PASSWORDS_FILENAME.to_string(), passwords_bytes));
+    }
+
+    let archive = create_archive(&files)?;
+    create_with_600_perms(&output_path, &archive)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", output_path, e))?;
+
+    eprintln!(
+        "Exported {} validators to {:?}.",
+        selected.len(),
+        output_path
+    );
+    eprintln!("WARNING: {}", EXPORT_WARNING);
+
+    Ok(())
+}
+
+/// Encrypts `password` with `export_password`, returning an EIP-2335 crypto object which can be
+/// decrypted with `eth2_keystore::decrypt`.
+fn encrypt_password(password: &[u8], export_password: &[u8]) -> Result<Crypto, String> {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+    let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>().to_vec().into();
+    let kdf = Kdf::Scrypt(Scrypt::default_scrypt(salt));
+    let cipher = Cipher::Aes128Ctr(Aes128Ctr { iv });
+
+    let (cipher_text, checksum) = encrypt(password, export_password, &kdf, &cipher)
+        .map_err(|e| format!("Unable to encrypt password: {:?}", e))?;
+
+    Ok(Crypto {
+        kdf: KdfModule {
+            function: kdf.function(),
+            params: kdf,
+            message: EmptyString,
+        },
+        checksum: ChecksumModule {
+            function: Sha256Checksum::function(),
+            params: EmptyMap,
+            message: checksum.to_vec().into(),
+        },
+        cipher: CipherModule {
+            function: cipher.function(),
+            params: cipher,
+            message: cipher_text.into(),
+        },
+    })
+}
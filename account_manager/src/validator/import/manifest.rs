@@ -0,0 +1,102 @@
+use super::report::{KeystoreReport, KeystoreStatus};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// An append-only record of the keystores which have been completed by an import, used to resume
+/// an interrupted import.
+///
+/// Each line of the file is a JSON `KeystoreReport`.
+pub(super) struct ImportManifest {
+    path: PathBuf,
+    file: File,
+    /// The public key of each completed keystore, by source path.
+    completed: HashMap<PathBuf, Option<String>>,
+}
+
+impl ImportManifest {
+    /// Opens the manifest at `path`, creating it if it does not exist.
+    ///
+    /// A partially written final line, left by an import which crashed whilst writing it, is
+    /// removed.
+    pub(super) fn open(path: PathBuf) -> Result<Self, String> {
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Unable to read manifest {:?}: {:?}", path, e)),
+        };
+
+        let mut completed = HashMap::new();
+        let lines = contents.lines().collect::<Vec<_>>();
+        for (i, line) in lines.iter().enumerate() {
+            match serde_json::from_str::<KeystoreReport>(line) {
+                Ok(entry) => {
+                    completed.insert(entry.path, entry.pubkey);
+                }
+                Err(_) if i + 1 == lines.len() && !contents.ends_with('\n') => {}
+                Err(e) => {
+                    return Err(format!(
+                        "Invalid entry on line {} of manifest {:?}: {:?}",
+                        i + 1,
+                        path,
+                        e
+                    ))
+                }
+            }
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("Unable to open manifest {:?}: {:?}", path, e))?;
+
+        // Remove any partially written line, so that it does not corrupt the next entry.
+        if !contents.ends_with('\n') {
+            let complete_len = contents.rfind('\n').map_or(0, |i| i + 1);
+            file.set_len(complete_len as u64)
+                .map_err(|e| format!("Unable to truncate manifest {:?}: {:?}", path, e))?;
+        }
+
+        Ok(Self {
+            path,
+            file,
+            completed,
+        })
+    }
+
+    /// Appends `keystore_report` to the manifest, syncing it to disk before returning.
+    pub(super) fn record(&mut self, keystore_report: &KeystoreReport) -> Result<(), String> {
+        let mut line = serde_json::to_string(keystore_report)
+            .map_err(|e| format!("Unable to encode manifest entry: {:?}", e))?;
+        line.push('\n');
+
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|()| self.file.sync_data())
+            .map_err(|e| format!("Unable to write manifest {:?}: {:?}", self.path, e))?;
+
+        self.completed
+            .insert(keystore_report.path.clone(), keystore_report.pubkey.clone());
+
+        Ok(())
+    }
+
+    /// Removes the keystores which have already been completed from `keystore_paths`, returning
+    /// their reports.
+    pub(super) fn skip_completed(&self, keystore_paths: &mut Vec<PathBuf>) -> Vec<KeystoreReport> {
+        let (completed, remaining): (Vec<_>, Vec<_>) = keystore_paths
+            .drain(..)
+            .partition(|path| self.completed.contains_key(path));
+        *keystore_paths = remaining;
+        completed
+            .into_iter()
+            .map(|path| KeystoreReport {
+                pubkey: self.completed[&path].clone(),
+                path,
+                status: KeystoreStatus::Skipped,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,2444 @@
+mod manifest;
+mod network;
+mod passwords;
+mod report;
+mod staging;
+
+use super::archive::ExtractedArchive;
+use super::common::{
+    export_slashing_protection, genesis_validators_root, new_validator_definition,
+    read_password_file, reencrypt_keystore, remove_definitions, restrict_permissions,
+    save_new_definitions, update_definitions, ReencryptionKdf, KDF_COST_FLAG, KDF_FLAG,
+};
+use super::export;
+use super::other_client::{client_layout, ClientLayout, SUPPORTED_CLIENTS};
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use crate::SECRETS_DIR_FLAG;
+use account_utils::{
+    eth2_keystore::Keystore,
+    eth2_wallet::{KeyType, ValidatorPath},
+    read_input_from_user, read_password_from_user,
+    validator_definitions::{
+        is_voting_keystore, recursively_find_keystores_matching, DefinitionsFormat,
+        SigningDefinition, ValidatorDefinitions, CONFIG_FILENAME, TOML_CONFIG_FILENAME,
+    },
+    ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
+use environment::Environment;
+use glob::Pattern;
+use manifest::ImportManifest;
+use network::{check_keystore_networks, check_slashing_protection_network};
+use passwords::{
+    check_passwords, read_env_password, read_password_map, read_reencrypt_password, PasswordPrompt,
+    PasswordSources,
+};
+use report::{import_logger, open_log_file, report_dry_run, Progress};
+use slashing_protection::{
+    interchange::Interchange, InterchangeImportOutcome, SlashingDatabase,
+    SLASHING_PROTECTION_FILENAME,
+};
+use slog::{debug, error, info, warn, Logger};
+use staging::{commit_staged_imports, StagedImport, StagingDir};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{EthSpec, GraffitiString, Hash256, PublicKey};
+
+pub use report::{ImportReport, KeystoreReport, KeystoreStatus};
+
+pub const CMD: &str = "import";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const DIR_FLAG: &str = "directory";
+pub const REUSE_PASSWORD_FLAG: &str = "reuse-password";
+pub const NO_WAIT_FLAG: &str = "no-wait";
+pub const SLASHING_PROTECTION_FLAG: &str = "slashing-protection";
+pub const COUNT_FLAG: &str = "count";
+pub const OFFSET_FLAG: &str = "offset";
+pub const MOVE_FLAG: &str = "move";
+pub const ENABLED_FLAG: &str = "enabled";
+pub const GRAFFITI_FLAG: &str = "graffiti";
+pub const ALLOW_DUPLICATES_FLAG: &str = "allow-duplicates";
+pub const ALL_OR_NOTHING_FLAG: &str = "all-or-nothing";
+pub const SKIP_VALIDATION_FLAG: &str = "skip-validation";
+pub const STORE_PASSWORD_ONCE_FLAG: &str = "store-password-once";
+pub const JSON_OUTPUT_FILE_FLAG: &str = "json-output-file";
+pub const BACKUP_DIR_FLAG: &str = "backup-dir";
+pub const FILENAME_PATTERN_FLAG: &str = "filename-pattern";
+pub const CONTINUE_ON_ERROR_FLAG: &str = "continue-on-error";
+pub const I_UNDERSTAND_SLASHING_RISK_FLAG: &str = "i-understand-slashing-risk";
+pub const PASSWORD_ENV_FLAG: &str = "password-env";
+pub const STRICT_FLAG: &str = "strict";
+pub const DIR_NAMING_FLAG: &str = "dir-naming";
+pub const REENCRYPT_PASSWORD_FILE_FLAG: &str = "reencrypt-password-file";
+pub const QUIET_FLAG: &str = "quiet";
+pub const MIN_PASSWORD_ENTROPY_FLAG: &str = "min-password-entropy";
+pub const MANIFEST_FLAG: &str = "manifest";
+pub const YES_FLAG: &str = "yes";
+pub const IMPORT_FROM_OTHER_CLIENT_FLAG: &str = "import-from-other-client";
+pub const NO_CREATE_FLAG: &str = "no-create";
+pub const LOG_FILE_FLAG: &str = "log-file";
+pub const MAX_PASSWORD_ATTEMPTS_FLAG: &str = "max-password-attempts";
+pub const ARCHIVE_FLAG: &str = "archive";
+pub const EXPORT_SLASHING_PROTECTION_FLAG: &str = "export-slashing-protection";
+pub const REGISTER_ONLY_FLAG: &str = "register-only";
+pub const VERIFY_AFTER_FLAG: &str = "verify-after";
+pub const DEFINITIONS_FORMAT_FLAG: &str = "definitions-format";
+pub const PASSWORD_FILE_FLAG: &str = "password-file";
+pub const PASSWORD_MAP_FLAG: &str = "password-map";
+pub const WALLET_PASSWORD_FILE_FLAG: &str = "wallet-password-file";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+pub const THREADS_FLAG: &str = "threads";
+pub const PROGRESS_FLAG: &str = "progress";
+pub const SHARED_PASSWORD_DIR_FLAG: &str = "shared-password-dir";
+
+/// The prefix of the deposit data files that the `eth2.0-deposit-cli` writes alongside keystores.
+pub const DEPOSIT_DATA_PREFIX: &str = "deposit_data-";
+
+/// The directory within the `validator_dir` where keystores are staged during an
+/// `--all-or-nothing` import.
+pub const STAGING_DIR_NAME: &str = ".import-staging";
+
+/// The prefix of the files in the secrets directory written by `--store-password-once`.
+pub const SHARED_PASSWORD_FILE_PREFIX: &str = "shared-password-";
+
+/// The most threads used to check passwords when `--threads` is not supplied. Decrypting a scrypt
+/// keystore requires 256 MiB of memory, so a thread per CPU could exhaust the memory of a machine
+/// with many cores.
+const DEFAULT_MAX_PASSWORD_CHECK_THREADS: usize = 4;
+
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password, or press enter to omit it:";
+pub const WALLET_PASSWORD_PROMPT: &str = "Enter the wallet password:";
+pub const KEYSTORE_REUSE_WARNING: &str = "DO NOT USE THE ORIGINAL KEYSTORES TO VALIDATE WITH \
+                                          ANOTHER CLIENT, OR YOU WILL GET SLASHED.";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Imports one or more EIP-2335 passwords into a Lighthouse VC directory, \
+            requesting passwords interactively. The directory flag provides a convenient \
+            method for importing a directory of keys generated by the eth2-deposit-cli \
+            Python utility.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("Path to a single keystore to be imported.")
+                .conflicts_with_all(&[DIR_FLAG, ARCHIVE_FLAG])
+                .required_unless_one(&[DIR_FLAG, ARCHIVE_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DIR_FLAG)
+                .long(DIR_FLAG)
+                .value_name("KEYSTORES_DIRECTORY")
+                .help(
+                    "Path to a directory which contains zero or more keystores \
+                    for import. This directory and all sub-directories will be \
+                    searched and any file name which contains 'keystore' and \
+                    has the '.json' extension will be attempted to be imported.",
+                )
+                .conflicts_with_all(&[KEYSTORE_FLAG, ARCHIVE_FLAG])
+                .required_unless_one(&[KEYSTORE_FLAG, ARCHIVE_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+        .arg(
+            Arg::with_name(REUSE_PASSWORD_FLAG)
+                .long(REUSE_PASSWORD_FLAG)
+                .help("If present, the same password will be used for all imported keystores."),
+        )
+        .arg(Arg::with_name(NO_WAIT_FLAG).long(NO_WAIT_FLAG).help(
+            "If present, do not pause after each password prompt. Pauses are always \
+            skipped when reading inputs from stdin.",
+        ))
+        .arg(
+            Arg::with_name(SLASHING_PROTECTION_FLAG)
+                .long(SLASHING_PROTECTION_FLAG)
+                .value_name("INTERCHANGE_FILE")
+                .help(
+                    "Path to an EIP-3076 slashing protection interchange file. The history for \
+                    each of the imported keystores will be applied to the slashing protection \
+                    database before any keystores are imported.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("COUNT")
+                .help(
+                    "The maximum number of keystores to import from the directory. Keystores \
+                    are sorted by path before being selected.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(OFFSET_FLAG)
+                .long(OFFSET_FLAG)
+                .value_name("OFFSET")
+                .help(
+                    "The number of keystores to skip from the start of the directory. Keystores \
+                    are sorted by path before being selected.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(Arg::with_name(MOVE_FLAG).long(MOVE_FLAG).help(
+            "If present, each source keystore is deleted once it has been successfully \
+            imported, rather than leaving a copy of it behind.",
+        ))
+        .arg(
+            Arg::with_name(ENABLED_FLAG)
+                .long(ENABLED_FLAG)
+                .value_name("ENABLED")
+                .help(
+                    "Whether the imported validators should be enabled in the validator \
+                    definitions file. Disabled validators are still registered with slashing \
+                    protection, but will not be started by the validator client.",
+                )
+                .possible_values(&["true", "false"])
+                .default_value("true")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GRAFFITI_FLAG)
+                .long(GRAFFITI_FLAG)
+                .value_name("GRAFFITI")
+                .help(
+                    "A graffiti string of at most 32 bytes to store in the validator definition \
+                    of each imported validator.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ALLOW_DUPLICATES_FLAG)
+                .long(ALLOW_DUPLICATES_FLAG)
+                .help(
+                    "If present, keystores which share a public key with an earlier keystore in \
+                    the same import are skipped. Otherwise, the import is refused.",
+                ),
+        )
+        .arg(
+            Arg::with_name(ALL_OR_NOTHING_FLAG)
+                .long(ALL_OR_NOTHING_FLAG)
+                .help(
+                    "If present, the keystores are only imported if every one of them can be \
+                    imported. An invalid password aborts the import rather than prompting \
+                    again, and the validator directory is left unchanged.",
+                ),
+        )
+        .arg(
+            Arg::with_name(SKIP_VALIDATION_FLAG)
+                .long(SKIP_VALIDATION_FLAG)
+                .help(
+                    "If present, keystores are imported even if their derivation path is not a \
+                    valid EIP-2334 path.",
+                ),
+        )
+        .arg(
+            Arg::with_name(STORE_PASSWORD_ONCE_FLAG)
+                .long(STORE_PASSWORD_ONCE_FLAG)
+                .help(
+                    "If present, the reused password is written once to a new \
+                    shared-password-<n> file in the --shared-password-dir directory and each \
+                    validator definition refers to that file, rather than storing a copy of the \
+                    password for every validator.",
+                )
+                .requires(REUSE_PASSWORD_FLAG),
+        )
+        .arg(
+            Arg::with_name(SHARED_PASSWORD_DIR_FLAG)
+                .long(SHARED_PASSWORD_DIR_FLAG)
+                .value_name("DIR")
+                .help(
+                    "The path where the password is stored when using --store-password-once. \
+                    Defaults to ~/.lighthouse/{network}/secrets",
+                )
+                .conflicts_with("datadir")
+                .requires(STORE_PASSWORD_ONCE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(JSON_OUTPUT_FILE_FLAG)
+                .long(JSON_OUTPUT_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, a JSON report of the outcome for each keystore is written to \
+                    this path once the import finishes, even if the import fails.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BACKUP_DIR_FLAG)
+                .long(BACKUP_DIR_FLAG)
+                .value_name("BACKUP_DIR")
+                .help(
+                    "If present, the slashing protection database and validator definitions \
+                    are copied into a timestamped directory within this directory before they \
+                    are modified. The import is refused if the backup fails.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FILENAME_PATTERN_FLAG)
+                .long(FILENAME_PATTERN_FLAG)
+                .value_name("GLOB")
+                .help(
+                    "A glob pattern which overrides the default rules for deciding which file \
+                    names in the directory are keystores, e.g. 'validator-*.json'.",
+                )
+                .requires(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(CONTINUE_ON_ERROR_FLAG)
+                .long(CONTINUE_ON_ERROR_FLAG)
+                .help(
+                    "If present, a keystore which fails to import is reported and the import \
+                    continues with the remaining keystores. The command still exits with an \
+                    error if any keystore failed.",
+                )
+                .conflicts_with(ALL_OR_NOTHING_FLAG),
+        )
+        .arg(
+            Arg::with_name(I_UNDERSTAND_SLASHING_RISK_FLAG)
+                .long(I_UNDERSTAND_SLASHING_RISK_FLAG)
+                .help(
+                    "If present, keystores are imported even if the slashing protection database \
+                    already records blocks or attestations signed by them. Such keys may still \
+                    be in use by another validator client.",
+                ),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_ENV_FLAG)
+                .long(PASSWORD_ENV_FLAG)
+                .value_name("VAR")
+                .help(
+                    "The name of an environment variable containing the password for all \
+                    keystores, e.g. KEYSTORE_PASSWORD. If present, the password is not prompted \
+                    for, and the import fails if it does not decrypt a keystore.",
+                )
+                .takes_value(true),
+        )
+        .arg(Arg::with_name(STRICT_FLAG).long(STRICT_FLAG).help(
+            "If present, the import fails if the derivation path of a keystore is not an \
+            EIP-2334 voting key path, or a deposit data file alongside the keystores was created \
+            for a different network than the one selected with --network, rather than only \
+            printing a warning.",
+        ))
+        .arg(
+            Arg::with_name(DIR_NAMING_FLAG)
+                .long(DIR_NAMING_FLAG)
+                .value_name("SCHEME")
+                .help(
+                    "The name of the directory that each keystore is imported into: the public \
+                    key, the index of its EIP-2334 voting key path, or its UUID.",
+                )
+                .possible_values(&["pubkey", "index", "uuid"])
+                .default_value("pubkey")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(REENCRYPT_PASSWORD_FILE_FLAG)
+                .long(REENCRYPT_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, each keystore is decrypted with its original password and \
+                    re-encrypted with the password in this file before it is written to the \
+                    validator directory. The source keystores are not modified.",
+                )
+                .conflicts_with_all(&[MOVE_FLAG, STORE_PASSWORD_ONCE_FLAG])
+                .takes_value(true),
+        )
+        .arg(Arg::with_name(QUIET_FLAG).long(QUIET_FLAG).help(
+            "If present, only warnings, errors and password prompts are printed. The warning \
+            against reusing the original keystores is also omitted if --i-understand-slashing-risk \
+            is present.",
+        ))
+        .arg(
+            Arg::with_name(MIN_PASSWORD_ENTROPY_FLAG)
+                .long(MIN_PASSWORD_ENTROPY_FLAG)
+                .value_name("BITS")
+                .help(
+                    "If present, the import is refused if the estimated entropy of the password \
+                    in --reencrypt-password-file is less than this many bits. The estimate is \
+                    based upon the length of the password and the classes of characters it uses.",
+                )
+                .requires(REENCRYPT_PASSWORD_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MANIFEST_FLAG)
+                .long(MANIFEST_FLAG)
+                .value_name("PATH")
+                .help(
+                    "Path to a file which records each keystore once it has been imported or \
+                    skipped. If the import is interrupted, re-running it with the same manifest \
+                    skips those keystores without reading them or prompting for passwords. The \
+                    file is created if it does not exist.",
+                )
+                .takes_value(true),
+        )
+        .arg(Arg::with_name(YES_FLAG).long(YES_FLAG).help(
+            "If present, a directory import starts without asking for confirmation. Imports are \
+            only confirmed when stdin is a terminal and --stdin-inputs is not used, so scripted \
+            imports never wait for confirmation.",
+        ))
+        .arg(
+            Arg::with_name(IMPORT_FROM_OTHER_CLIENT_FLAG)
+                .long(IMPORT_FROM_OTHER_CLIENT_FLAG)
+                .value_name("CLIENT")
+                .help(
+                    "The name of the client which created the directory, if it is not laid out \
+                    like the output of the eth2-deposit-cli. Passwords stored alongside the \
+                    keystores by that client are used rather than prompting for them. A Prysm \
+                    wallet directory is decrypted with its wallet password. The slashing \
+                    protection database of Prysm cannot be read, so its history must be exported \
+                    by Prysm and provided with --slashing-protection.",
+                )
+                .possible_values(SUPPORTED_CLIENTS)
+                .requires(DIR_FLAG)
+                .conflicts_with_all(&[FILENAME_PATTERN_FLAG, PASSWORD_ENV_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WALLET_PASSWORD_FILE_FLAG)
+                .long(WALLET_PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "Path to a file containing the wallet password of a client which encrypts \
+                    all keys with a single password, such as Prysm. If omitted, the password is \
+                    prompted for.",
+                )
+                .requires(IMPORT_FROM_OTHER_CLIENT_FLAG)
+                .takes_value(true),
+        )
+        .arg(Arg::with_name(NO_CREATE_FLAG).long(NO_CREATE_FLAG).help(
+            "If present, the import fails if the validator directory does not already exist, \
+            rather than creating it. Useful for catching a mistyped --validator-dir.",
+        ))
+        .arg(
+            Arg::with_name(LOG_FILE_FLAG)
+                .long(LOG_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "If present, a transcript of the import is written to this file, including \
+                    output omitted by --quiet. Passwords are never written to the file. An \
+                    existing file is overwritten.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(MAX_PASSWORD_ATTEMPTS_FLAG)
+                .long(MAX_PASSWORD_ATTEMPTS_FLAG)
+                .value_name("ATTEMPTS")
+                .help(
+                    "The number of times an invalid password may be entered for a keystore \
+                    before it fails to import. With --continue-on-error the import then moves on \
+                    to the next keystore, otherwise it stops. When reading inputs from stdin, an \
+                    invalid password always fails the keystore immediately.",
+                )
+                .default_value("3")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ARCHIVE_FLAG)
+                .long(ARCHIVE_FLAG)
+                .value_name("ARCHIVE_PATH")
+                .help(
+                    "Path to a .zip archive of keystores to be imported. The archive is extracted \
+                    into a temporary directory which is searched like --directory, and which is \
+                    removed once the import finishes. The slashing protection history in an \
+                    archive created by `validator export` is applied unless --slashing-protection \
+                    is present.",
+                )
+                .conflicts_with_all(&[KEYSTORE_FLAG, DIR_FLAG])
+                .required_unless_one(&[KEYSTORE_FLAG, DIR_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help(
+                    "The key derivation function used to re-encrypt keystores. Defaults to \
+                    scrypt.",
+                )
+                .possible_values(&["scrypt", "pbkdf2"])
+                .requires(REENCRYPT_PASSWORD_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("N")
+                .help(
+                    "The cost parameter of the --kdf used to re-encrypt keystores, i.e. the \
+                    scrypt \"n\" or the pbkdf2 iteration count \"c\". Defaults to 262144. \
+                    Values below 16384 are refused, and scrypt requires a power of two.",
+                )
+                .requires(REENCRYPT_PASSWORD_FILE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(EXPORT_SLASHING_PROTECTION_FLAG)
+                .long(EXPORT_SLASHING_PROTECTION_FLAG)
+                .value_name("INTERCHANGE_FILE")
+                .help(
+                    "If present, an EIP-3076 slashing protection interchange file containing the \
+                    history of each imported validator is written to this path once all of them \
+                    have been registered. Validators without any history are included with \
+                    empty records.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(REGISTER_ONLY_FLAG)
+                .long(REGISTER_ONLY_FLAG)
+                .help(
+                    "If present, the public key of each keystore is registered with the slashing \
+                    protection database, but the keystores are not decrypted, copied or added to \
+                    validator_definitions.yml. This is intended for validators which sign using \
+                    a remote signer.",
+                )
+                .conflicts_with_all(&[
+                    MOVE_FLAG,
+                    REUSE_PASSWORD_FLAG,
+                    PASSWORD_ENV_FLAG,
+                    PASSWORD_FILE_FLAG,
+                    PASSWORD_MAP_FLAG,
+                    REENCRYPT_PASSWORD_FILE_FLAG,
+                    STORE_PASSWORD_ONCE_FLAG,
+                    ALL_OR_NOTHING_FLAG,
+                    MANIFEST_FLAG,
+                    EXPORT_SLASHING_PROTECTION_FLAG,
+                    DEFINITIONS_FORMAT_FLAG,
+                ]),
+        )
+        .arg(Arg::with_name(VERIFY_AFTER_FLAG).long(VERIFY_AFTER_FLAG).help(
+            "If present, each imported validator is loaded from validator_definitions.yml once \
+            the import has finished, in the same way as the validator client. Its keystore is \
+            read and, if a password is stored, decrypted. Any validator which fails to load is \
+            reported and the import exits with an error.",
+        ))
+        .arg(
+            Arg::with_name(DEFINITIONS_FORMAT_FLAG)
+                .long(DEFINITIONS_FORMAT_FLAG)
+                .value_name("FORMAT")
+                .help(
+                    "The format in which the validator definitions are written. YAML is written \
+                    to validator_definitions.yml and TOML to validator_definitions.toml, and \
+                    the file of the other format is removed. The validator client reads either \
+                    file. If omitted, an existing file keeps its format and a new file is \
+                    written as YAML.",
+                )
+                .possible_values(&["yaml", "toml"])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "Path to a file containing the password for all keystores. If present, the \
+                    password is not prompted for, and the import fails if it does not decrypt a \
+                    keystore.",
+                )
+                .conflicts_with_all(&[PASSWORD_ENV_FLAG, IMPORT_FROM_OTHER_CLIENT_FLAG])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_MAP_FLAG)
+                .long(PASSWORD_MAP_FLAG)
+                .value_name("PATH")
+                .help(
+                    "Path to a JSON file which maps the public key of each keystore to its \
+                    password, e.g. {\"0xa1b2...\": \"password\"}. If present, passwords are not \
+                    prompted for, and the import fails if a keystore has no password in the file \
+                    or its password is invalid.",
+                )
+                .conflicts_with_all(&[
+                    PASSWORD_ENV_FLAG,
+                    PASSWORD_FILE_FLAG,
+                    IMPORT_FROM_OTHER_CLIENT_FLAG,
+                ])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "If present, the password of each keystore is read from the file in this \
+                    directory named after its public key (e.g., 0x1234...), as in the secrets \
+                    directory of the validator client, rather than being prompted for. The \
+                    import fails if a keystore has no password file, or its password is \
+                    invalid.",
+                )
+                .conflicts_with_all(&[
+                    PASSWORD_ENV_FLAG,
+                    PASSWORD_FILE_FLAG,
+                    PASSWORD_MAP_FLAG,
+                    IMPORT_FROM_OTHER_CLIENT_FLAG,
+                ])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(THREADS_FLAG)
+                .long(THREADS_FLAG)
+                .value_name("THREADS")
+                .help(
+                    "The number of threads used to check the passwords of keystores before they \
+                    are imported, when the passwords are not prompted for. Decrypting a keystore \
+                    which uses scrypt requires about 256 MiB of memory per thread, so each \
+                    additional thread adds 256 MiB to the peak memory use of the import. Defaults \
+                    to the number of CPUs, up to a maximum of 4.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PROGRESS_FLAG)
+                .long(PROGRESS_FLAG)
+                .help(
+                    "If present, the output for each keystore is replaced by a single line \
+                    showing the number of keystores processed and the estimated time remaining. \
+                    Intended for large imports whose passwords are not prompted for, which may \
+                    be resumed using --manifest if they are interrupted.",
+                )
+                .conflicts_with(QUIET_FLAG),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "If present, every keystore is read and its password is checked, and any \
+                    keystores which are already imported, share a public key or have signing \
+                    history in the slashing protection database are reported. Nothing is \
+                    copied, and neither validator_definitions.yml nor the slashing protection \
+                    database is modified. Exits with an error if the import would fail.",
+                )
+                .conflicts_with_all(&[
+                    MOVE_FLAG,
+                    REGISTER_ONLY_FLAG,
+                    ALL_OR_NOTHING_FLAG,
+                    STORE_PASSWORD_ONCE_FLAG,
+                    MANIFEST_FLAG,
+                    BACKUP_DIR_FLAG,
+                    EXPORT_SLASHING_PROTECTION_FLAG,
+                    DEFINITIONS_FORMAT_FLAG,
+                    VERIFY_AFTER_FLAG,
+                ]),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    mut env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let json_output_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, JSON_OUTPUT_FILE_FLAG)?;
+    let backup_dir: Option<PathBuf> = clap_utils::parse_optional(matches, BACKUP_DIR_FLAG)?;
+    let quiet = matches.is_present(QUIET_FLAG);
+    let show_progress = matches.is_present(PROGRESS_FLAG);
+    let log_file: Option<PathBuf> = clap_utils::parse_optional(matches, LOG_FILE_FLAG)?;
+
+    let file_log = log_file.as_deref().map(open_log_file).transpose()?;
+    let env_log = env.core_context().log().clone();
+    let log = import_logger(&env_log, file_log.as_ref(), quiet);
+    // The output for each keystore is replaced by the progress line, if there is one.
+    let keystore_log = import_logger(&env_log, file_log.as_ref(), quiet || show_progress);
+
+    if matches.is_present(NO_CREATE_FLAG) && !validator_dir.is_dir() {
+        return Err(format!(
+            "The validator directory {:?} does not exist and --{} is present",
+            validator_dir, NO_CREATE_FLAG
+        ));
+    }
+
+    let backup = if let Some(backup_dir) = backup_dir {
+        let backup = backup_validator_dir(&validator_dir, &backup_dir)?;
+        info!(log, "Backed up the validator directory"; "backup" => ?backup);
+        Some(backup)
+    } else {
+        None
+    };
+
+    let mut report = ImportReport::default();
+    let result = import(
+        matches,
+        env,
+        validator_dir,
+        &log,
+        &keystore_log,
+        &mut report,
+    );
+
+    if let Err(e) = &result {
+        debug!(log, "Import failed: {}", e);
+    }
+
+    if let (Err(_), Some(backup)) = (&result, &backup) {
+        error!(
+            log,
+            "The import failed. The {} and {} files from before the import can be restored \
+             from the backup",
+            SLASHING_PROTECTION_FILENAME,
+            CONFIG_FILENAME;
+            "backup" => ?backup,
+        );
+    }
+
+    if let Some(json_output_file) = json_output_file {
+        report.finalize(result.as_ref().err())?;
+        report.write_to_file(&json_output_file)?;
+        info!(log, "Wrote import report"; "path" => ?json_output_file);
+    }
+
+    result
+}
+
+fn import<T: EthSpec>(
+    matches: &ArgMatches,
+    env: Environment<T>,
+    validator_dir: PathBuf,
+    log: &Logger,
+    keystore_log: &Logger,
+    report: &mut ImportReport,
+) -> Result<(), String> {
+    let keystore: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORE_FLAG)?;
+    let keystores_dir: Option<PathBuf> = clap_utils::parse_optional(matches, DIR_FLAG)?;
+    let archive_path: Option<PathBuf> = clap_utils::parse_optional(matches, ARCHIVE_FLAG)?;
+    // The extracted keystores are removed when this is dropped, after the import.
+    let archive = archive_path
+        .map(|path| ExtractedArchive::extract(&path))
+        .transpose()?;
+    let keystores_dir = keystores_dir.or_else(|| archive.as_ref().map(|a| a.path().to_path_buf()));
+    // An archive created by the `export` command carries the history of its keystores.
+    let interchange_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, SLASHING_PROTECTION_FLAG)?.or_else(|| {
+            archive
+                .as_ref()
+                .map(|archive| archive.path().join(export::INTERCHANGE_FILENAME))
+                .filter(|path| path.is_file())
+        });
+    // The genesis validators root is obtained before importing anything, so that the export
+    // cannot fail for this reason after the validators have been imported.
+    let export_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, EXPORT_SLASHING_PROTECTION_FLAG)?;
+    let export = export_path
+        .map(|path| Ok::<_, String>((path, genesis_validators_root(&env)?)))
+        .transpose()?;
+    let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
+    let offset: usize = clap_utils::parse_optional(matches, OFFSET_FLAG)?.unwrap_or(0);
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let reuse_password = matches.is_present(REUSE_PASSWORD_FLAG);
+    let move_keystores = matches.is_present(MOVE_FLAG);
+    let register_only = matches.is_present(REGISTER_ONLY_FLAG);
+    let dry_run = matches.is_present(DRY_RUN_FLAG);
+    let show_progress = matches.is_present(PROGRESS_FLAG);
+    let verify_after = matches.is_present(VERIFY_AFTER_FLAG);
+    let definitions_format: Option<DefinitionsFormat> =
+        clap_utils::parse_optional(matches, DEFINITIONS_FORMAT_FLAG)?;
+    let enabled: bool = clap_utils::parse_required(matches, ENABLED_FLAG)?;
+    let graffiti: Option<GraffitiString> = clap_utils::parse_optional(matches, GRAFFITI_FLAG)?;
+    let allow_duplicates = matches.is_present(ALLOW_DUPLICATES_FLAG);
+    let all_or_nothing = matches.is_present(ALL_OR_NOTHING_FLAG);
+    let skip_validation = matches.is_present(SKIP_VALIDATION_FLAG);
+    let continue_on_error = matches.is_present(CONTINUE_ON_ERROR_FLAG);
+    let understand_slashing_risk = matches.is_present(I_UNDERSTAND_SLASHING_RISK_FLAG);
+    let password_env: Option<String> = clap_utils::parse_optional(matches, PASSWORD_ENV_FLAG)?;
+    let password_file: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let password_map_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, PASSWORD_MAP_FLAG)?;
+    let strict = matches.is_present(STRICT_FLAG);
+    let dir_naming: DirNaming = clap_utils::parse_required(matches, DIR_NAMING_FLAG)?;
+    let reencrypt_password_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, REENCRYPT_PASSWORD_FILE_FLAG)?;
+    let min_password_entropy: Option<u32> =
+        clap_utils::parse_optional(matches, MIN_PASSWORD_ENTROPY_FLAG)?;
+    let reencryption_kdf = ReencryptionKdf::new(
+        matches.value_of(KDF_FLAG).unwrap_or("scrypt"),
+        clap_utils::parse_optional(matches, KDF_COST_FLAG)?,
+    )?;
+    let manifest_path: Option<PathBuf> = clap_utils::parse_optional(matches, MANIFEST_FLAG)?;
+    let max_password_attempts: usize =
+        clap_utils::parse_required(matches, MAX_PASSWORD_ATTEMPTS_FLAG)?;
+    if max_password_attempts == 0 {
+        return Err(format!(
+            "--{} must be at least 1",
+            MAX_PASSWORD_ATTEMPTS_FLAG
+        ));
+    }
+    let threads: Option<usize> = clap_utils::parse_optional(matches, THREADS_FLAG)?;
+    if threads == Some(0) {
+        return Err(format!("--{} must be at least 1", THREADS_FLAG));
+    }
+    let wallet_password_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, WALLET_PASSWORD_FILE_FLAG)?;
+    let other_client =
+        clap_utils::parse_optional::<String>(matches, IMPORT_FROM_OTHER_CLIENT_FLAG)?
+            .map(|name| {
+                client_layout(&name, || match &wallet_password_file {
+                    Some(path) => read_password_file(path),
+                    None => {
+                        eprintln!("{}", WALLET_PASSWORD_PROMPT);
+                        read_password_from_user(stdin_inputs)
+                    }
+                })
+            })
+            .transpose()?;
+    // Only imports of a whole directory are confirmed, since they may find unexpected keystores.
+    // Scripted imports are never confirmed, as there may be nobody to answer.
+    let confirm = keystores_dir.is_some()
+        && !matches.is_present(YES_FLAG)
+        && !dry_run
+        && !stdin_inputs
+        && stdin_is_terminal();
+    let filename_pattern = clap_utils::parse_optional::<String>(matches, FILENAME_PATTERN_FLAG)?
+        .map(|pattern| {
+            Pattern::new(&pattern)
+                .map_err(|e| format!("Invalid --{}: {:?}", FILENAME_PATTERN_FLAG, e))
+        })
+        .transpose()?;
+    let secrets_dir: Option<PathBuf> = clap_utils::parse_optional(matches, SECRETS_DIR_FLAG)?;
+    let shared_password_dir = if !matches.is_present(STORE_PASSWORD_ONCE_FLAG) {
+        None
+    } else if matches.value_of("datadir").is_some() {
+        let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
+        Some(path.join(DEFAULT_SECRET_DIR))
+    } else {
+        Some(parse_path_or_default_with_flag(
+            matches,
+            SHARED_PASSWORD_DIR_FLAG,
+            DEFAULT_SECRET_DIR,
+        )?)
+    };
+    let env_password = password_env.as_deref().map(read_env_password).transpose()?;
+    // An empty file would otherwise silently import every keystore without a password.
+    if let Some(path) = &password_file {
+        if read_password_file(path)?.as_ref().is_empty() {
+            return Err(format!("The password in {:?} is empty", path));
+        }
+    }
+    let password_map = password_map_path
+        .as_ref()
+        .map(|path| read_password_map(path))
+        .transpose()?;
+    let reencrypt_password = reencrypt_password_file
+        .as_ref()
+        .map(|path| read_reencrypt_password(path, min_password_entropy))
+        .transpose()?;
+    // The pauses only exist to provide nicer UX for a human at a terminal.
+    let ux_delay = if stdin_inputs
+        || env_password.is_some()
+        || password_file.is_some()
+        || password_map.is_some()
+        || secrets_dir.is_some()
+        || matches.is_present(NO_WAIT_FLAG)
+    {
+        None
+    } else {
+        Some(Duration::from_secs(1))
+    };
+
+    let interrupted = handle_interrupts()?;
+
+    let mut defs = open_definitions(&validator_dir, register_only, dry_run, definitions_format)?;
+
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    // A dry run never creates the database. Without one no keystore has any signing history, so
+    // an empty database is created in a temporary directory instead, which is removed when it is
+    // dropped.
+    let empty_slashing_protection_dir = if dry_run && !slashing_protection_path.exists() {
+        Some(
+            tempfile::Builder::new()
+                .prefix("lighthouse-import-dry-run-")
+                .tempdir()
+                .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?,
+        )
+    } else {
+        None
+    };
+    let slashing_protection_path = empty_slashing_protection_dir
+        .as_ref()
+        .map_or(slashing_protection_path, |dir| {
+            dir.path().join(SLASHING_PROTECTION_FILENAME)
+        });
+    let slashing_protection =
+        open_slashing_protection(&slashing_protection_path, &env, dry_run, log)?;
+
+    // The password file of each keystore, for clients which store them alongside the keystores.
+    let mut password_files = HashMap::new();
+
+    let mut keystore_paths = find_keystore_paths(
+        keystore,
+        keystores_dir,
+        other_client.as_deref(),
+        filename_pattern.as_ref(),
+        offset,
+        count,
+        &mut password_files,
+        log,
+    )?;
+    if keystore_paths.is_empty() {
+        return Ok(());
+    }
+
+    // Keystores completed by a previous run with the same manifest are not read again.
+    let mut manifest = manifest_path.map(ImportManifest::open).transpose()?;
+    let completed_keystores = match &manifest {
+        Some(manifest) => manifest.skip_completed(&mut keystore_paths),
+        None => vec![],
+    };
+    if !completed_keystores.is_empty() {
+        info!(
+            log,
+            "Skipping {} keystores which were completed according to the manifest",
+            completed_keystores.len()
+        );
+    }
+
+    // A dry run checks every keystore, as if `--continue-on-error` were present.
+    let (mut keystores, unreadable_keystores) =
+        read_keystores(keystore_paths, continue_on_error || dry_run, log)?;
+
+    // A dry run records the reasons that the import would be refused, rather than stopping at the
+    // first of them.
+    let mut refusals = Refusals::new(dry_run, log);
+    check_duplicate_keystores(&mut keystores, allow_duplicates, &mut refusals, log)?;
+    check_keystore_networks(
+        &keystores,
+        env.eth2_config.spec.genesis_fork_version,
+        strict,
+        &mut refusals,
+        log,
+    )?;
+    check_signing_history(
+        &slashing_protection,
+        &keystores,
+        &validator_dir,
+        &defs,
+        understand_slashing_risk,
+        &mut refusals,
+        log,
+    )?;
+
+    // Give the user a chance to check the import before anything is modified.
+    if confirm && !keystores.is_empty() && !confirm_import(&keystores, &validator_dir, &defs)? {
+        info!(log, "Import cancelled, no keystores were imported");
+        return Ok(());
+    }
+
+    report.keystores = keystores
+        .iter()
+        .map(|(path, _)| KeystoreReport {
+            path: path.clone(),
+            pubkey: None,
+            status: KeystoreStatus::NotImported,
+        })
+        .collect();
+    report.keystores.extend(completed_keystores.iter().cloned());
+    report
+        .keystores
+        .extend(unreadable_keystores.iter().cloned());
+
+    // Import the slashing protection history before any of the keystores, so that an imported
+    // keystore is never usable without its history.
+    //
+    // An `--all-or-nothing` import defers this until all keystores have been staged.
+    if let (Some(interchange_path), false, false) = (&interchange_path, all_or_nothing, dry_run) {
+        import_slashing_protection(
+            &slashing_protection,
+            interchange_path,
+            &keystores,
+            &env,
+            log,
+        )?;
+    }
+
+    if register_only {
+        register_keystores(&slashing_protection, &keystores, &mut report.keystores, log)?;
+        if !unreadable_keystores.is_empty() {
+            return Err(format!(
+                "Failed to register {} of {} keystores",
+                unreadable_keystores.len(),
+                keystores.len() + unreadable_keystores.len()
+            ));
+        }
+        return Ok(());
+    }
+
+    // Keystores are copied into the staging directory, then moved into place once all of them
+    // have been processed. The staging directory is removed when it is dropped, including
+    // when returning early with an error.
+    let staging = if all_or_nothing {
+        Some(StagingDir::create(
+            validator_dir.join(STAGING_DIR_NAME),
+            log,
+        )?)
+    } else {
+        None
+    };
+
+    if !dry_run {
+        log_reuse_warning(understand_slashing_risk, log);
+    }
+
+    let password_sources = PasswordSources {
+        env_password: env_password.as_ref(),
+        password_map: password_map.as_ref(),
+        secrets_dir: secrets_dir.as_deref(),
+        password_files: &password_files,
+        password_file: password_file.as_deref(),
+    };
+
+    // Decrypting a keystore is deliberately slow, so the passwords which are not prompted for are
+    // checked in parallel up-front. Everything else happens one keystore at a time below.
+    let checked_passwords = check_passwords(
+        &keystores,
+        &password_sources,
+        &validator_dir,
+        &defs,
+        threads,
+    )?;
+    if !checked_passwords.is_empty() {
+        info!(
+            log,
+            "Checked the passwords of {} keystores",
+            checked_passwords.len()
+        );
+    }
+
+    let mut importer = KeystoreImporter {
+        validator_dir: &validator_dir,
+        defs: &mut defs,
+        slashing_protection: &slashing_protection,
+        slashing_protection_path: &slashing_protection_path,
+        log: keystore_log,
+        passwords: PasswordPrompt {
+            sources: password_sources,
+            checked_passwords,
+            previous_password: None,
+            shared_password_path: None,
+            reuse_password,
+            shared_password_dir: shared_password_dir.as_deref(),
+            staging: staging.as_ref(),
+            stdin_inputs,
+            all_or_nothing,
+            max_password_attempts,
+            ux_delay,
+            log: keystore_log,
+        },
+        staging: staging.as_ref(),
+        staged_imports: vec![],
+        dir_naming,
+        skip_validation,
+        dry_run,
+        reencrypt_password: reencrypt_password.as_ref(),
+        reencryption_kdf,
+        enabled,
+        graffiti: &graffiti,
+        move_keystores,
+        num_keystores: keystores.len(),
+        num_imported: 0,
+    };
+
+    let mut progress = if show_progress {
+        Some(Progress::new(keystores.len()))
+    } else {
+        None
+    };
+    let mut num_failed_keystores = unreadable_keystores.len();
+    for (i, (src_keystore, keystore)) in keystores.iter().enumerate() {
+        // Only stop between keystores, so that each keystore is either fully imported or not at
+        // all.
+        if interrupted.load(Ordering::SeqCst) {
+            if let Some(progress) = &mut progress {
+                progress.end_line();
+            }
+            return Err(format!(
+                "Import interrupted after {} of {} keystores. The remaining keystores can be \
+                 imported by running the import again",
+                i,
+                keystores.len()
+            ));
+        }
+
+        if let Err(e) =
+            importer.import_keystore(i, src_keystore, keystore, &mut report.keystores[i])
+        {
+            if let Some(progress) = &mut progress {
+                progress.end_line();
+            }
+            // A dry run checks every keystore, as if `--continue-on-error` were present.
+            if !continue_on_error && !dry_run {
+                return Err(e);
+            }
+            error!(
+                log,
+                "Failed to import keystore {:?}", src_keystore;
+                "error" => e,
+            );
+            num_failed_keystores += 1;
+        }
+
+        // Staged keystores are recorded once they have been committed.
+        if let Some(manifest) = &mut manifest {
+            let keystore_report = &report.keystores[i];
+            if matches!(
+                keystore_report.status,
+                KeystoreStatus::Imported | KeystoreStatus::Skipped
+            ) {
+                manifest.record(keystore_report)?;
+            }
+        }
+
+        if let Some(progress) = &mut progress {
+            progress.increment();
+        }
+    }
+
+    if dry_run {
+        return report_dry_run(
+            importer.num_imported,
+            keystores.len() + completed_keystores.len() + unreadable_keystores.len()
+                - importer.num_imported
+                - num_failed_keystores,
+            num_failed_keystores,
+            &refusals.reasons,
+            log,
+        );
+    }
+
+    if staging.is_some() {
+        if let Some(interchange_path) = &interchange_path {
+            import_slashing_protection(
+                &slashing_protection,
+                interchange_path,
+                &keystores,
+                &env,
+                log,
+            )?;
+        }
+
+        importer.commit_staged(&mut report.keystores, manifest.as_mut())?;
+    }
+    let num_imported_keystores = importer.num_imported;
+
+    if verify_after {
+        check_imported_validators(&validator_dir, &report.keystores, log)?;
+    }
+
+    if let Some((export_path, genesis_validators_root)) = &export {
+        export_imported_history(
+            &slashing_protection,
+            export_path,
+            &report.keystores,
+            *genesis_validators_root,
+            log,
+        )?;
+    }
+
+    info!(
+        log,
+        "Successfully imported {} validators ({} skipped, {} failed)",
+        num_imported_keystores,
+        keystores.len() + completed_keystores.len() + unreadable_keystores.len()
+            - num_imported_keystores
+            - num_failed_keystores,
+        num_failed_keystores
+    );
+    log_reuse_warning(understand_slashing_risk, log);
+
+    if num_failed_keystores > 0 {
+        return Err(format!(
+            "Failed to import {} of {} keystores",
+            num_failed_keystores,
+            keystores.len() + unreadable_keystores.len()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Opens the validator definitions in `validator_dir`, creating the file if the import may modify
+/// it.
+fn open_definitions(
+    validator_dir: &Path,
+    register_only: bool,
+    dry_run: bool,
+    definitions_format: Option<DefinitionsFormat>,
+) -> Result<ValidatorDefinitions, String> {
+    // A `--register-only` import never modifies the definitions file, so it is not created.
+    if register_only {
+        return Ok(ValidatorDefinitions::open(validator_dir).unwrap_or_default());
+    }
+
+    if dry_run {
+        // A dry run must compare against the same definitions as the import, so only a missing
+        // file is ignored.
+        let defs_exist = DefinitionsFormat::detect(validator_dir)
+            .map_err(|e| format!("Unable to find {}: {:?}", CONFIG_FILENAME, e))?
+            .is_some();
+        return if defs_exist {
+            ValidatorDefinitions::open(validator_dir)
+                .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))
+        } else {
+            Ok(ValidatorDefinitions::default())
+        };
+    }
+
+    let mut defs = ValidatorDefinitions::open_or_create(validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+    // The definitions file is only created with restrictive permissions, so an existing file
+    // is restricted before any plain-text passwords are written to it.
+    restrict_permissions(&validator_dir.join(defs.format().filename()), 0o600)?;
+    // The file is converted up-front, since later saves keep the format of the file.
+    if let Some(format) = definitions_format.filter(|format| *format != defs.format()) {
+        update_definitions(&mut defs, validator_dir, |latest| {
+            latest.set_format(format);
+            Ok(())
+        })?;
+    }
+    Ok(defs)
+}
+
+/// Opens the slashing protection database at `path`, checking that the validator client is not
+/// running and that the database is for the network of `env`.
+fn open_slashing_protection<T: EthSpec>(
+    path: &Path,
+    env: &Environment<T>,
+    dry_run: bool,
+    log: &Logger,
+) -> Result<SlashingDatabase, String> {
+    let slashing_protection = SlashingDatabase::open_or_create(path).map_err(|e| {
+        format!(
+            "Unable to open or create slashing protection database at {}: {:?}",
+            path.display(),
+            e
+        )
+    })?;
+
+    // Create an empty transaction and drop it. Used to test if the database is locked.
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot import keys while the validator client is running: {:?}",
+            e
+        )
+    })?;
+
+    // A database which is used for another network offers no protection on this one. Networks
+    // without a genesis state cannot be checked, in which case the validator client records the
+    // network when it first starts.
+    match genesis_validators_root(env) {
+        Ok(genesis_validators_root) => check_slashing_protection_network(
+            &slashing_protection,
+            genesis_validators_root,
+            dry_run,
+        )?,
+        Err(e) => {
+            warn!(
+                log,
+                "Unable to check that the slashing protection database is for the selected \
+                 network";
+                "error" => e,
+            );
+        }
+    }
+
+    Ok(slashing_protection)
+}
+
+/// Returns the paths of the keystores to import, which are either the single `keystore` or those
+/// found in `keystores_dir`, sorted and selected by `offset` and `count`.
+///
+/// The password files found alongside the keystores of `other_client` are added to
+/// `password_files`. An empty list is returned, after explaining why, if there is nothing to
+/// import.
+#[allow(clippy::too_many_arguments)]
+fn find_keystore_paths(
+    keystore: Option<PathBuf>,
+    keystores_dir: Option<PathBuf>,
+    other_client: Option<&dyn ClientLayout>,
+    filename_pattern: Option<&Pattern>,
+    offset: usize,
+    count: Option<usize>,
+    password_files: &mut HashMap<PathBuf, PathBuf>,
+    log: &Logger,
+) -> Result<Vec<PathBuf>, String> {
+    let keystores_dir = match (keystore, keystores_dir) {
+        (Some(keystore), None) => return Ok(vec![keystore]),
+        (None, Some(keystores_dir)) => keystores_dir,
+        _ => {
+            return Err(format!(
+                "Must supply one of --{}, --{} or --{}",
+                KEYSTORE_FLAG, DIR_FLAG, ARCHIVE_FLAG
+            ))
+        }
+    };
+
+    let mut keystores = vec![];
+    let mut rejected = vec![];
+
+    if let Some(other_client) = other_client {
+        for found in other_client.find_keystores(&keystores_dir)? {
+            if let Some(password_file) = found.password_file {
+                password_files.insert(found.keystore.clone(), password_file);
+            }
+            keystores.push(found.keystore);
+        }
+        Ok(())
+    } else if let Some(pattern) = filename_pattern {
+        recursively_find_keystores_matching(
+            &keystores_dir,
+            &|file_name| pattern.matches(file_name),
+            &mut keystores,
+            &mut rejected,
+        )
+    } else {
+        recursively_find_keystores_matching(
+            &keystores_dir,
+            &is_voting_keystore,
+            &mut keystores,
+            &mut rejected,
+        )
+    }
+    .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+
+    if keystores.is_empty() {
+        warn!(log, "No keystores found in {:?}", keystores_dir);
+        rejected.sort();
+        for path in &rejected {
+            warn!(
+                log,
+                "File found, but its name does not match those of keystores (see --{})",
+                FILENAME_PATTERN_FLAG;
+                "path" => ?path,
+            );
+        }
+        return Ok(vec![]);
+    }
+
+    // Sort the keystores so that the import order, the `--offset` and `--count` selection and the
+    // report are the same on all machines and for every run.
+    keystores.sort();
+
+    let keystores = keystores
+        .into_iter()
+        .skip(offset)
+        .take(count.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    if keystores.is_empty() {
+        warn!(
+            log,
+            "No keystores selected from {:?} with --{} {}", keystores_dir, OFFSET_FLAG, offset
+        );
+    }
+
+    Ok(keystores)
+}
+
+/// Reads each of the keystores at `keystore_paths`, once, for the rest of the import.
+///
+/// Keystores which cannot be read are returned as failed reports if `continue_on_error` is set,
+/// otherwise the first of them is returned as an error.
+#[allow(clippy::type_complexity)]
+fn read_keystores(
+    keystore_paths: Vec<PathBuf>,
+    continue_on_error: bool,
+    log: &Logger,
+) -> Result<(Vec<(PathBuf, Keystore)>, Vec<KeystoreReport>), String> {
+    let mut keystores = vec![];
+    let mut unreadable_keystores = vec![];
+    for path in keystore_paths {
+        let result = Keystore::from_json_file(&path)
+            .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", path, e))
+            .and_then(|keystore| match keystore.public_key() {
+                Some(_) => Ok(keystore),
+                None => Err(format!(
+                    "Keystore public key is invalid: {}",
+                    keystore.pubkey()
+                )),
+            });
+        match result {
+            Ok(keystore) => keystores.push((path, keystore)),
+            Err(e) if continue_on_error => {
+                error!(log, "Failed to import keystore {:?}", path; "error" => e);
+                unreadable_keystores.push(KeystoreReport {
+                    path,
+                    pubkey: None,
+                    status: KeystoreStatus::Failed,
+                });
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((keystores, unreadable_keystores))
+}
+
+/// The reasons that an import is refused.
+///
+/// A dry run records each of them, rather than stopping at the first.
+struct Refusals<'a> {
+    dry_run: bool,
+    reasons: Vec<String>,
+    log: &'a Logger,
+}
+
+impl<'a> Refusals<'a> {
+    fn new(dry_run: bool, log: &'a Logger) -> Self {
+        Self {
+            dry_run,
+            reasons: vec![],
+            log,
+        }
+    }
+
+    /// Refuses the import for `reason`, which is an error unless this is a dry run.
+    fn refuse(&mut self, reason: String) -> Result<(), String> {
+        if self.dry_run {
+            warn!(self.log, "{}", reason);
+            self.reasons.push(reason);
+            Ok(())
+        } else {
+            Err(reason)
+        }
+    }
+}
+
+/// Detects keystores in this import which share a public key, rather than relying upon the
+/// destination directory of the first to cause the others to be skipped.
+///
+/// The duplicates are removed from `keystores` if `allow_duplicates` is set.
+fn check_duplicate_keystores(
+    keystores: &mut Vec<(PathBuf, Keystore)>,
+    allow_duplicates: bool,
+    refusals: &mut Refusals,
+    log: &Logger,
+) -> Result<(), String> {
+    let duplicates = find_duplicate_keystores(keystores);
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+
+    for (duplicate, original) in &duplicates {
+        warn!(
+            log,
+            "Keystore {:?} has the same public key as {:?}", duplicate, original
+        );
+    }
+
+    if !allow_duplicates {
+        refusals.refuse(format!(
+            "Refusing to import {} keystores with duplicate public keys, use --{} to skip them",
+            duplicates.len(),
+            ALLOW_DUPLICATES_FLAG
+        ))?;
+    }
+
+    keystores.retain(|(path, _)| !duplicates.iter().any(|(duplicate, _)| duplicate == path));
+    warn!(log, "Skipping {} duplicate keystores", duplicates.len());
+    Ok(())
+}
+
+/// Refuses to import keystores which are not yet in the `validator_dir` but have signed messages,
+/// since they have been used elsewhere and may still be in use by another validator client.
+///
+/// This is checked before any slashing protection is imported, since the history in an
+/// interchange file is expected.
+fn check_signing_history(
+    slashing_protection: &SlashingDatabase,
+    keystores: &[(PathBuf, Keystore)],
+    validator_dir: &Path,
+    defs: &ValidatorDefinitions,
+    understand_slashing_risk: bool,
+    refusals: &mut Refusals,
+    log: &Logger,
+) -> Result<(), String> {
+    let used_keystores =
+        find_keystores_with_signing_history(slashing_protection, keystores, validator_dir, defs)?;
+    if used_keystores.is_empty() {
+        return Ok(());
+    }
+
+    for (path, pubkey) in &used_keystores {
+        warn!(
+            log,
+            "Keystore {:?} has signing history for public key {} in the slashing protection \
+             database",
+            path,
+            pubkey.to_hex_string()
+        );
+    }
+
+    if understand_slashing_risk {
+        warn!(log, "{}", KEYSTORE_REUSE_WARNING);
+    } else {
+        refusals.refuse(format!(
+            "Refusing to import {} keystores which have previously been used to sign messages. \
+             Ensure they are not in use by any other validator client, then use --{} to import \
+             them",
+            used_keystores.len(),
+            I_UNDERSTAND_SLASHING_RISK_FLAG
+        ))?;
+    }
+    Ok(())
+}
+
+/// Imports keystores one at a time, holding the state which is shared between them.
+struct KeystoreImporter<'a> {
+    validator_dir: &'a Path,
+    defs: &'a mut ValidatorDefinitions,
+    slashing_protection: &'a SlashingDatabase,
+    slashing_protection_path: &'a Path,
+    log: &'a Logger,
+    passwords: PasswordPrompt<'a>,
+    /// The staging directory of an `--all-or-nothing` import.
+    staging: Option<&'a StagingDir>,
+    staged_imports: Vec<StagedImport>,
+    dir_naming: DirNaming,
+    skip_validation: bool,
+    dry_run: bool,
+    reencrypt_password: Option<&'a ZeroizeString>,
+    reencryption_kdf: ReencryptionKdf,
+    enabled: bool,
+    graffiti: &'a Option<GraffitiString>,
+    move_keystores: bool,
+    num_keystores: usize,
+    /// The number of keystores imported, or which would be imported by a dry run.
+    num_imported: usize,
+}
+
+impl<'a> KeystoreImporter<'a> {
+    /// Imports the `i`th `keystore`, which was read from `src_keystore`, recording the outcome in
+    /// `keystore_report`.
+    ///
+    /// - Obtain the keystore password, if the user desires.
+    /// - Copy the keystore into the `validator_dir`.
+    /// - Add the keystore to the validator definitions file.
+    /// - Register the voting key with the slashing protection database.
+    ///
+    /// Keystores that already exist are skipped. If the same password is reused for all
+    /// keystores, it is only obtained once.
+    fn import_keystore(
+        &mut self,
+        i: usize,
+        src_keystore: &Path,
+        keystore: &Keystore,
+        keystore_report: &mut KeystoreReport,
+    ) -> Result<(), String> {
+        let log = self.log;
+
+        // The keystore is reported as failed unless it reaches the end of this function.
+        keystore_report.status = KeystoreStatus::Failed;
+        keystore_report.pubkey = Some(format!("0x{}", keystore.pubkey()));
+
+        info!(
+            log,
+            "[{}/{}] Keystore found at {:?}",
+            i + 1,
+            self.num_keystores,
+            src_keystore;
+            "pubkey" => format!("0x{}", keystore.pubkey()),
+            "uuid" => %keystore.uuid(),
+        );
+
+        // Check this before prompting for the password, so that re-running an import does not
+        // require the passwords of keystores which have already been imported.
+        if is_imported(keystore, self.validator_dir, self.defs) {
+            info!(
+                log,
+                "Skipping import of keystore for existing public key: {:?}", src_keystore
+            );
+            keystore_report.status = KeystoreStatus::Skipped;
+            return Ok(());
+        }
+
+        // Keystores are always skipped based upon their public key, so another keystore which
+        // has the same directory name under the chosen scheme must not be overwritten.
+        let dest_dir = self.validator_dir.join(self.dir_naming.dir_name(keystore)?);
+        if dest_dir.exists() {
+            return Err(format!(
+                "Unable to import keystore {:?}, {:?} already exists",
+                src_keystore, dest_dir
+            ));
+        }
+        // A staged keystore's directory is not created until the import is committed.
+        if let Some(staged) = self
+            .staged_imports
+            .iter()
+            .find(|staged| staged.dest_dir == dest_dir)
+        {
+            return Err(format!(
+                "Unable to import keystore {:?}, {:?} is also being imported into {:?}",
+                src_keystore, staged.src_keystore, dest_dir
+            ));
+        }
+
+        if let Err(e) = validate_keystore(keystore) {
+            if self.skip_validation {
+                warn!(log, "Importing invalid keystore {:?}", src_keystore; "error" => e);
+            } else {
+                return Err(format!(
+                    "Invalid keystore {:?}: {}. Use --{} to import it anyway",
+                    src_keystore, e, SKIP_VALIDATION_FLAG
+                ));
+            }
+        }
+
+        info!(
+            log,
+            "If you enter the password it will be stored as plain-text in {} so that it is not \
+             required each time the validator client starts",
+            CONFIG_FILENAME
+        );
+
+        let (password_opt, password_checked) = self.passwords.password(src_keystore, keystore)?;
+
+        // A dry run stops once nothing further can prevent the keystore from being imported.
+        if self.dry_run {
+            info!(log, "The keystore would be imported");
+            self.num_imported += 1;
+            keystore_report.status = KeystoreStatus::NotImported;
+            return Ok(());
+        }
+
+        // A re-encrypted keystore is written to the destination instead of a copy of the source,
+        // and its new password is the one stored in the definition.
+        let (reencrypted, password_opt) = match self.reencrypt_password {
+            Some(new_password) => {
+                let password = password_opt.ok_or_else(|| {
+                    format!(
+                        "The password for keystore {:?} is required to re-encrypt it",
+                        src_keystore
+                    )
+                })?;
+                let reencrypted =
+                    reencrypt_keystore(keystore, &password, new_password, self.reencryption_kdf)?;
+                (Some(reencrypted), Some(new_password.clone()))
+            }
+            None => (None, password_opt),
+        };
+        let expected_keystore = reencrypted.as_ref().unwrap_or(keystore);
+        // A copy of a keystore whose password was checked up-front is identical to the keystore
+        // that was decrypted, so decrypting the copy would only repeat that check serially.
+        let copy_password = password_opt
+            .as_ref()
+            .filter(|_| !password_checked || reencrypted.is_some());
+
+        // Retain the keystore file name, but place it in the new directory. The file name never
+        // contains any directories, so the keystore cannot be written elsewhere.
+        let file_name = src_keystore
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .ok_or_else(|| format!("Badly formatted file name: {:?}", src_keystore))?;
+        let dest_keystore = dest_dir.join(file_name);
+
+        let voting_pubkey = keystore
+            .public_key()
+            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+
+        if let Some(staging) = self.staging {
+            let staged_dir = staging.path.join(format!("0x{}", keystore.pubkey()));
+            fs::create_dir_all(&staged_dir)
+                .map_err(|e| format!("Unable to create staging directory: {:?}", e))?;
+            restrict_permissions(&staged_dir, 0o700)?;
+            let staged_keystore = staged_dir.join(file_name);
+            write_keystore(src_keystore, reencrypted.as_ref(), &staged_keystore)?;
+            verify_copied_keystore(expected_keystore, &staged_keystore, copy_password)?;
+
+            self.staged_imports.push(StagedImport {
+                src_keystore: src_keystore.to_path_buf(),
+                staged_dir,
+                dest_dir,
+                dest_keystore,
+                voting_pubkey,
+                password: password_opt,
+            });
+
+            info!(log, "Successfully staged keystore");
+            keystore_report.status = KeystoreStatus::NotImported;
+            return Ok(());
+        }
+
+        fs::create_dir_all(&dest_dir)
+            .map_err(|e| format!("Unable to create import directory: {:?}", e))?;
+        restrict_permissions(&dest_dir, 0o700).map_err(|e| {
+            remove_failed_import(&dest_dir, log);
+            e
+        })?;
+
+        // Copy the keystore to the new location.
+        write_keystore(src_keystore, reencrypted.as_ref(), &dest_keystore).map_err(|e| {
+            remove_failed_import(&dest_dir, log);
+            e
+        })?;
+
+        // Check the copy before it is referenced by slashing protection or the definitions.
+        verify_copied_keystore(expected_keystore, &dest_keystore, copy_password).map_err(|e| {
+            remove_failed_import(&dest_dir, log);
+            e
+        })?;
+
+        // Save the definition before registering with slashing protection, so that a failure to
+        // save the definition never leaves an orphaned registration. If either step fails, the
+        // copied keystore is removed so that the import can be retried.
+        let validator_def = new_validator_definition(
+            &dest_keystore,
+            password_opt,
+            self.passwords.shared_password_path.as_ref(),
+            self.enabled,
+            self.graffiti,
+        )
+        .map_err(|e| {
+            remove_failed_import(&dest_dir, log);
+            e
+        })?;
+
+        if let Err(e) = save_new_definitions(self.defs, self.validator_dir, vec![validator_def]) {
+            remove_failed_import(&dest_dir, log);
+            return Err(e);
+        }
+
+        info!(log, "Successfully updated {}", CONFIG_FILENAME);
+
+        if let Err(e) = self.slashing_protection.register_validator(&voting_pubkey) {
+            if let Err(e) = remove_definitions(self.defs, self.validator_dir, &[&voting_pubkey]) {
+                error!(log, "Unable to remove definition"; "error" => e);
+            }
+            remove_failed_import(&dest_dir, log);
+            return Err(format!(
+                "Error registering validator {}: {:?}",
+                voting_pubkey.to_hex_string(),
+                e
+            ));
+        }
+        self.log_registration(&voting_pubkey);
+
+        info!(log, "Successfully imported keystore");
+        self.num_imported += 1;
+
+        // Only remove the source once all other side-effects have succeeded, so that a failed
+        // import never loses the keystore.
+        if self.move_keystores {
+            remove_source_keystore(src_keystore, &dest_keystore)?;
+            info!(log, "Removed source keystore {:?}", src_keystore);
+        }
+
+        keystore_report.status = KeystoreStatus::Imported;
+        Ok(())
+    }
+
+    /// Commits the keystores staged by an `--all-or-nothing` import, marking them as imported in
+    /// `keystore_reports` and the `manifest`.
+    fn commit_staged(
+        &mut self,
+        keystore_reports: &mut [KeystoreReport],
+        mut manifest: Option<&mut ImportManifest>,
+    ) -> Result<(), String> {
+        let log = self.log;
+        commit_staged_imports(
+            &self.staged_imports,
+            self.slashing_protection,
+            self.defs,
+            self.validator_dir,
+            self.passwords.shared_password_path.as_ref(),
+            self.enabled,
+            self.graffiti,
+            log,
+        )?;
+        if let Some(staging) = self.staging {
+            staging.keep_files();
+        }
+        self.num_imported = self.staged_imports.len();
+        for staged in &self.staged_imports {
+            self.log_registration(&staged.voting_pubkey);
+        }
+        for keystore_report in keystore_reports {
+            if keystore_report.status == KeystoreStatus::NotImported {
+                keystore_report.status = KeystoreStatus::Imported;
+                if let Some(manifest) = manifest.as_mut() {
+                    manifest.record(keystore_report)?;
+                }
+            }
+        }
+
+        info!(
+            log,
+            "Successfully imported {} staged keystores and updated {}",
+            self.num_imported,
+            CONFIG_FILENAME
+        );
+
+        if self.move_keystores {
+            for staged in &self.staged_imports {
+                remove_source_keystore(&staged.src_keystore, &staged.dest_keystore)?;
+                info!(log, "Removed source keystore {:?}", staged.src_keystore);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Logs that `voting_pubkey` has been registered with slashing protection.
+    fn log_registration(&self, voting_pubkey: &PublicKey) {
+        info!(
+            self.log,
+            "Registered validator with slashing protection";
+            "pubkey" => voting_pubkey.to_hex_string(),
+            "slashing_protection" => ?self.slashing_protection_path,
+        );
+    }
+}
+
+/// Writes the history of each of the imported validators in `keystore_reports` to an EIP-3076
+/// interchange file at `export_path`.
+fn export_imported_history(
+    slashing_protection: &SlashingDatabase,
+    export_path: &Path,
+    keystore_reports: &[KeystoreReport],
+    genesis_validators_root: Hash256,
+    log: &Logger,
+) -> Result<(), String> {
+    let pubkeys = keystore_reports
+        .iter()
+        .filter(|keystore_report| keystore_report.status == KeystoreStatus::Imported)
+        .filter_map(|keystore_report| keystore_report.pubkey.as_ref())
+        .map(|pubkey| {
+            pubkey
+                .parse()
+                .map_err(|e| format!("Invalid public key {}: {:?}", pubkey, e))
+        })
+        .collect::<Result<Vec<PublicKey>, _>>()?;
+    export_slashing_protection(
+        slashing_protection,
+        export_path,
+        &pubkeys,
+        genesis_validators_root,
+    )?;
+    info!(
+        log,
+        "Exported slashing protection history for {} validators to {:?}",
+        pubkeys.len(),
+        export_path
+    );
+    Ok(())
+}
+
+/// Checks that the fields of `keystore` are those expected of an EIP-2335 voting keystore.
+///
+/// The version and KDF are already enforced when the keystore is parsed and decrypted, so only the
+/// derivation path is checked here. An empty path is permitted by EIP-2335. A valid EIP-2334 path
+/// which is not a voting key path is reported by `find_network_mismatches`.
+fn validate_keystore(keystore: &Keystore) -> Result<(), String> {
+    let path = match keystore.path() {
+        Some(path) if !path.is_empty() => path,
+        _ => return Ok(()),
+    };
+
+    let is_eip_2334_path = path.strip_prefix("m/").map_or(false, |nodes| {
+        nodes.split('/').all(|node| node.parse::<u32>().is_ok())
+    });
+    if !is_eip_2334_path {
+        return Err(format!("path {:?} is not an EIP-2334 path", path));
+    }
+
+    Ok(())
+}
+
+/// Returns the validator index of the keystore's EIP-2334 voting key path, if it has one.
+fn voting_path_index(keystore: &Keystore) -> Option<u32> {
+    let nodes = keystore
+        .path()?
+        .strip_prefix("m/")?
+        .split('/')
+        .map(|node| node.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    match nodes.as_slice() {
+        [_, _, index, _, _]
+            if ValidatorPath::new(*index, KeyType::Voting)
+                .iter_nodes()
+                .eq(nodes.iter()) =>
+        {
+            Some(*index)
+        }
+        _ => None,
+    }
+}
+
+/// The scheme used to name the directory within the `validator_dir` that a keystore is imported
+/// into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DirNaming {
+    /// `0x` followed by the public key, as used by all other Lighthouse commands.
+    Pubkey,
+    /// `index-` followed by the index of the EIP-2334 voting key path.
+    Index,
+    /// The UUID of the keystore.
+    Uuid,
+}
+
+impl FromStr for DirNaming {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pubkey" => Ok(DirNaming::Pubkey),
+            "index" => Ok(DirNaming::Index),
+            "uuid" => Ok(DirNaming::Uuid),
+            other => Err(format!("Unknown directory naming scheme: {}", other)),
+        }
+    }
+}
+
+impl DirNaming {
+    /// Returns the name of the directory that `keystore` should be imported into.
+    pub fn dir_name(self, keystore: &Keystore) -> Result<String, String> {
+        match self {
+            DirNaming::Pubkey => Ok(format!("0x{}", keystore.pubkey())),
+            DirNaming::Index => voting_path_index(keystore)
+                .map(|index| format!("index-{}", index))
+                .ok_or_else(|| {
+                    format!(
+                        "Unable to name the directory for keystore 0x{} by index, its path \
+                         {:?} is not a voting key path",
+                        keystore.pubkey(),
+                        keystore.path().unwrap_or_default()
+                    )
+                }),
+            DirNaming::Uuid => Ok(keystore.uuid().to_string()),
+        }
+    }
+}
+
+/// Returns `true` if `keystore` has already been imported into `validator_dir`, either into a
+/// directory named after its public key or with a definition for its public key.
+fn is_imported(keystore: &Keystore, validator_dir: &Path, defs: &ValidatorDefinitions) -> bool {
+    if validator_dir
+        .join(format!("0x{}", keystore.pubkey()))
+        .exists()
+    {
+        return true;
+    }
+
+    keystore.public_key().map_or(false, |pubkey| {
+        defs.as_slice()
+            .iter()
+            .any(|def| def.voting_public_key == pubkey)
+    })
+}
+
+/// Copies the slashing protection database and validator definitions in `validator_dir` into a new
+/// timestamped directory within `backup_dir`, returning the path to that directory.
+///
+/// The files are copied into a temporary directory which is then renamed, so that the returned
+/// directory always contains a complete backup. Files which do not exist yet are not backed up.
+fn backup_validator_dir(validator_dir: &Path, backup_dir: &Path) -> Result<PathBuf, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to read system time: {:?}", e))?
+        .as_secs();
+    let backup = backup_dir.join(format!("import-{}", timestamp));
+    let temp_backup = backup_dir.join(format!(".import-{}.tmp", timestamp));
+
+    if backup.exists() {
+        return Err(format!("Backup directory {:?} already exists", backup));
+    }
+
+    fs::create_dir_all(&temp_backup).map_err(|e| {
+        format!(
+            "Unable to create backup directory {:?}: {:?}",
+            temp_backup, e
+        )
+    })?;
+
+    for file_name in &[
+        SLASHING_PROTECTION_FILENAME,
+        CONFIG_FILENAME,
+        TOML_CONFIG_FILENAME,
+    ] {
+        let path = validator_dir.join(file_name);
+        if path.exists() {
+            fs::copy(&path, temp_backup.join(file_name))
+                .map_err(|e| format!("Unable to back up {:?}: {:?}", path, e))?;
+        }
+    }
+
+    fs::rename(&temp_backup, &backup).map_err(|e| {
+        format!(
+            "Unable to rename {:?} to {:?}: {:?}",
+            temp_backup, backup, e
+        )
+    })?;
+
+    Ok(backup)
+}
+
+/// Writes the keystore at `src_keystore` to `dest_keystore`, or `reencrypted` in its place if it
+/// has been re-encrypted.
+///
+/// An existing file at `dest_keystore` is never overwritten.
+fn write_keystore(
+    src_keystore: &Path,
+    reencrypted: Option<&Keystore>,
+    dest_keystore: &Path,
+) -> Result<(), String> {
+    if dest_keystore.exists() {
+        return Err(format!(
+            "Refusing to overwrite existing keystore {:?}",
+            dest_keystore
+        ));
+    }
+
+    let result = match reencrypted {
+        Some(keystore) => File::create(dest_keystore)
+            .map_err(|e| format!("Unable to create keystore file: {:?}", e))
+            .and_then(|file| {
+                keystore
+                    .to_json_writer(file)
+                    .map_err(|e| format!("Unable to write keystore: {:?}", e))
+            }),
+        None => fs::copy(src_keystore, dest_keystore)
+            .map(|_| ())
+            .map_err(|e| format!("Unable to copy keystore: {:?}", e)),
+    };
+
+    // A copy retains the permissions of the source, which may be readable by other users.
+    result.and_then(|()| restrict_permissions(dest_keystore, 0o600))
+}
+
+/// Returns `true` if stdin is a terminal, rather than e.g. a pipe from a script.
+fn stdin_is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 1 }
+}
+
+/// Prints a summary of the import and asks the user to confirm it on the terminal, returning
+/// `true` if they did.
+fn confirm_import(
+    keystores: &[(PathBuf, Keystore)],
+    validator_dir: &Path,
+    defs: &ValidatorDefinitions,
+) -> Result<bool, String> {
+    let num_existing = keystores
+        .iter()
+        .filter(|(_, keystore)| is_imported(keystore, validator_dir, defs))
+        .count();
+
+    eprintln!();
+    eprintln!("Discovered {} keystores.", keystores.len());
+    eprintln!(
+        " - {} have already been imported and will be skipped.",
+        num_existing
+    );
+    eprintln!(" - Validator directory: {:?}", validator_dir);
+    eprintln!();
+    eprintln!("Do you want to continue with the import? [y/N]");
+
+    let input = read_input_from_user(false)?;
+    Ok(matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Installs a Ctrl-C handler which sets the returned flag, rather than exiting whilst a keystore
+/// is only partially imported. A second Ctrl-C exits immediately.
+fn handle_interrupts() -> Result<Arc<AtomicBool>, String> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let interrupted_c = interrupted.clone();
+
+    ctrlc::set_handler(move || {
+        if interrupted_c.swap(true, Ordering::SeqCst) {
+            eprintln!("Exiting immediately.");
+            process::exit(130);
+        }
+        eprintln!(
+            "Interrupted, the import will stop once the current keystore has been imported. \
+             Press Ctrl-C again to exit immediately."
+        );
+    })
+    .map_err(|e| format!("Could not set ctrlc handler: {:?}", e))?;
+
+    Ok(interrupted)
+}
+
+/// Loads each of the validators in `keystore_reports` which were imported from the definitions
+/// file in `validator_dir`, returning an error if any of them cannot be loaded.
+///
+/// Unlike `verify_copied_keystore`, this only uses what has been written to disk, so it checks
+/// what the validator client will load.
+fn check_imported_validators(
+    validator_dir: &Path,
+    keystore_reports: &[KeystoreReport],
+    log: &Logger,
+) -> Result<(), String> {
+    let defs = ValidatorDefinitions::open(validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let imported_pubkeys = keystore_reports
+        .iter()
+        .filter(|keystore_report| keystore_report.status == KeystoreStatus::Imported)
+        .filter_map(|keystore_report| keystore_report.pubkey.as_ref())
+        .collect::<Vec<_>>();
+
+    let mut num_failed = 0;
+    for pubkey in &imported_pubkeys {
+        if let Err(e) = load_imported_validator(&defs, pubkey) {
+            error!(log, "Validator {} failed to load", pubkey; "error" => e);
+            num_failed += 1;
+        }
+    }
+
+    if num_failed > 0 {
+        return Err(format!(
+            "{} of {} imported validators failed to load",
+            num_failed,
+            imported_pubkeys.len()
+        ));
+    }
+
+    info!(
+        log,
+        "Successfully loaded {} imported validators",
+        imported_pubkeys.len()
+    );
+
+    Ok(())
+}
+
+/// Loads the validator with `pubkey` from `defs`, decrypting its keystore if it has a password.
+fn load_imported_validator(defs: &ValidatorDefinitions, pubkey: &str) -> Result<(), String> {
+    let voting_public_key: PublicKey = pubkey
+        .parse()
+        .map_err(|e| format!("Invalid public key: {:?}", e))?;
+    let def = defs
+        .as_slice()
+        .iter()
+        .find(|def| def.voting_public_key == voting_public_key)
+        .ok_or_else(|| format!("No definition in {}", CONFIG_FILENAME))?;
+
+    let voting_keystore_path = match &def.signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => voting_keystore_path,
+    };
+    let keystore = Keystore::from_json_file(voting_keystore_path).map_err(|e| {
+        format!(
+            "Unable to read keystore {:?}: {:?}",
+            voting_keystore_path, e
+        )
+    })?;
+    if keystore.public_key() != Some(voting_public_key.clone()) {
+        return Err(format!(
+            "Keystore {:?} has public key 0x{}",
+            voting_keystore_path,
+            keystore.pubkey()
+        ));
+    }
+
+    // Without a stored password the validator client prompts for it, so it cannot be checked.
+    let password = def
+        .signing_definition
+        .voting_keystore_password()
+        .map_err(|e| format!("Unable to read password: {:?}", e))?;
+    if let Some(password) = password {
+        let keypair = keystore.decrypt_keypair(password.as_bytes()).map_err(|e| {
+            format!(
+                "Unable to decrypt keystore {:?}: {:?}",
+                voting_keystore_path, e
+            )
+        })?;
+        if keypair.pk != voting_public_key {
+            return Err(format!(
+                "Keystore {:?} does not decrypt to its public key",
+                voting_keystore_path
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the keystore at `copied_keystore` is identical to `keystore` and, if a `password` is
+/// supplied, that it decrypts to the same public key.
+fn verify_copied_keystore(
+    keystore: &Keystore,
+    copied_keystore: &Path,
+    password: Option<&ZeroizeString>,
+) -> Result<(), String> {
+    let copy = Keystore::from_json_file(copied_keystore).map_err(|e| {
+        format!(
+            "Unable to read copied keystore {:?}: {:?}",
+            copied_keystore, e
+        )
+    })?;
+
+    if copy != *keystore {
+        return Err(format!(
+            "Copied keystore {:?} does not match the keystore for 0x{}",
+            copied_keystore,
+            keystore.pubkey()
+        ));
+    }
+
+    if let Some(password) = password {
+        let keypair = copy.decrypt_keypair(password.as_ref()).map_err(|e| {
+            format!(
+                "Unable to decrypt copied keystore {:?}: {:?}",
+                copied_keystore, e
+            )
+        })?;
+        if Some(keypair.pk) != keystore.public_key() {
+            return Err(format!(
+                "Copied keystore {:?} does not decrypt to 0x{}",
+                copied_keystore,
+                keystore.pubkey()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Logs the warning against reusing the original keystores, at the info level if the user has
+/// acknowledged the risk so that it is omitted by `--quiet`.
+fn log_reuse_warning(understand_slashing_risk: bool, log: &Logger) {
+    if understand_slashing_risk {
+        info!(log, "{}", KEYSTORE_REUSE_WARNING);
+    } else {
+        warn!(log, "{}", KEYSTORE_REUSE_WARNING);
+    }
+}
+
+/// Removes the directory of a keystore which could not be imported, so that the import can be
+/// retried.
+fn remove_failed_import(dest_dir: &Path, log: &Logger) {
+    if let Err(e) = fs::remove_dir_all(dest_dir) {
+        error!(log, "Unable to remove {:?}", dest_dir; "error" => ?e);
+    }
+}
+
+/// Returns a `(duplicate, original)` pair for each of `keystores` which has the same public key as
+/// an earlier keystore in `keystores`.
+fn find_duplicate_keystores(keystores: &[(PathBuf, Keystore)]) -> Vec<(PathBuf, PathBuf)> {
+    let mut originals: HashMap<String, &PathBuf> = HashMap::new();
+    let mut duplicates = vec![];
+
+    for (path, keystore) in keystores {
+        match originals.entry(keystore.pubkey().to_string()) {
+            Entry::Occupied(original) => {
+                duplicates.push((path.clone(), original.get().to_path_buf()))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(path);
+            }
+        }
+    }
+
+    duplicates
+}
+
+/// Returns the path and public key of each keystore that has not already been imported into
+/// `validator_dir`, but has signed blocks or attestations recorded in `slashing_protection`.
+fn find_keystores_with_signing_history(
+    slashing_protection: &SlashingDatabase,
+    keystores: &[(PathBuf, Keystore)],
+    validator_dir: &Path,
+    defs: &ValidatorDefinitions,
+) -> Result<Vec<(PathBuf, PublicKey)>, String> {
+    let mut used = vec![];
+
+    for (path, keystore) in keystores {
+        // Keystores which are already imported are skipped, so their history is expected.
+        if is_imported(keystore, validator_dir, defs) {
+            continue;
+        }
+
+        let pubkey = keystore
+            .public_key()
+            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+        if slashing_protection
+            .has_signing_history(&pubkey)
+            .map_err(|e| format!("Unable to read slashing protection database: {:?}", e))?
+        {
+            used.push((path.clone(), pubkey));
+        }
+    }
+
+    Ok(used)
+}
+
+/// Deletes the keystore at `src_keystore`, after checking that it has been faithfully copied to
+/// `dest_keystore`.
+fn remove_source_keystore(src_keystore: &Path, dest_keystore: &Path) -> Result<(), String> {
+    let src_bytes = fs::read(src_keystore)
+        .map_err(|e| format!("Unable to read keystore {:?}: {:?}", src_keystore, e))?;
+    let dest_bytes = fs::read(dest_keystore)
+        .map_err(|e| format!("Unable to read keystore {:?}: {:?}", dest_keystore, e))?;
+
+    if src_bytes != dest_bytes {
+        return Err(format!(
+            "Refusing to remove {:?} since it does not match the imported copy at {:?}",
+            src_keystore, dest_keystore
+        ));
+    }
+
+    fs::remove_file(src_keystore)
+        .map_err(|e| format!("Unable to remove keystore {:?}: {:?}", src_keystore, e))
+}
+
+/// Registers the public key of each of `keystores` with `slashing_protection`, without decrypting
+/// or copying the keystores. The first `keystores.len()` entries of `keystore_reports` are those
+/// of `keystores`.
+///
+/// Keystores whose public keys are already registered are skipped.
+fn register_keystores(
+    slashing_protection: &SlashingDatabase,
+    keystores: &[(PathBuf, Keystore)],
+    keystore_reports: &mut [KeystoreReport],
+    log: &Logger,
+) -> Result<(), String> {
+    let mut pubkeys = vec![];
+    for ((_, keystore), keystore_report) in keystores.iter().zip(keystore_reports.iter_mut()) {
+        let pubkey = keystore
+            .public_key()
+            .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+        keystore_report.pubkey = Some(pubkey.to_hex_string());
+
+        if slashing_protection.get_validator_id(&pubkey).is_ok() {
+            info!(
+                log,
+                "Skipping registration of existing public key";
+                "pubkey" => pubkey.to_hex_string(),
+            );
+            keystore_report.status = KeystoreStatus::Skipped;
+        } else {
+            pubkeys.push(pubkey);
+        }
+    }
+
+    slashing_protection
+        .register_validators(pubkeys.iter())
+        .map_err(|e| format!("Error registering validators: {:?}", e))?;
+    for keystore_report in keystore_reports.iter_mut() {
+        if keystore_report.status == KeystoreStatus::NotImported {
+            keystore_report.status = KeystoreStatus::Registered;
+        }
+    }
+
+    info!(
+        log,
+        "Successfully registered {} validators with slashing protection, no keystores were \
+         imported",
+        pubkeys.len()
+    );
+
+    Ok(())
+}
+
+/// Applies the records from the EIP-3076 interchange file at `interchange_path` to the
+/// `slashing_protection` database, for only the public keys of `keystores`.
+///
+/// Logs a warning for any of the keystores which do not have a record in the interchange file.
+fn import_slashing_protection<T: EthSpec>(
+    slashing_protection: &SlashingDatabase,
+    interchange_path: &Path,
+    keystores: &[(PathBuf, Keystore)],
+    env: &Environment<T>,
+    log: &Logger,
+) -> Result<(), String> {
+    let genesis_validators_root = genesis_validators_root(env)?;
+
+    let interchange_file = File::open(interchange_path).map_err(|e| {
+        format!(
+            "Unable to open interchange file at {}: {:?}",
+            interchange_path.display(),
+            e
+        )
+    })?;
+    let mut interchange = Interchange::from_json_reader(&interchange_file)
+        .map_err(|e| format!("Error parsing interchange file: {:?}", e))?;
+
+    let pubkeys = keystores
+        .iter()
+        .map(|(_, keystore)| {
+            keystore
+                .public_key()
+                .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))
+        })
+        .collect::<Result<HashSet<PublicKey>, _>>()?;
+
+    interchange
+        .data
+        .retain(|record| pubkeys.contains(&record.pubkey));
+
+    for pubkey in &pubkeys {
+        if !interchange
+            .data
+            .iter()
+            .any(|record| &record.pubkey == pubkey)
+        {
+            warn!(
+                log,
+                "NO SLASHING PROTECTION HISTORY FOR {} IN {}",
+                pubkey.to_hex_string(),
+                interchange_path.display()
+            );
+        }
+    }
+
+    let outcomes = slashing_protection
+        .import_interchange_info(interchange, genesis_validators_root)
+        .map_err(|e| {
+            format!(
+                "Error importing slashing protection: {:?}\n\
+                 IT IS NOT SAFE TO START VALIDATING",
+                e
+            )
+        })?;
+
+    for outcome in &outcomes {
+        if let InterchangeImportOutcome::Failure { pubkey, error } = outcome {
+            error!(
+                log,
+                "Error importing slashing protection";
+                "pubkey" => pubkey.to_hex_string(),
+                "error" => ?error,
+            );
+        }
+    }
+
+    let num_failed = outcomes.iter().filter(|outcome| outcome.failed()).count();
+    if num_failed > 0 {
+        return Err(format!(
+            "Slashing protection was NOT imported for {} of {} records, no keystores have been \
+             imported",
+            num_failed,
+            outcomes.len()
+        ));
+    }
+
+    info!(
+        log,
+        "Imported slashing protection history for {} validators",
+        outcomes.len()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use super::{voting_path_index, Refusals, DEPOSIT_DATA_PREFIX};
+use account_utils::{
+    eth2_keystore::Keystore,
+    eth2_wallet::{COIN_TYPE, PURPOSE},
+};
+use serde_derive::Deserialize;
+use slashing_protection::SlashingDatabase;
+use slog::{warn, Logger};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use types::Hash256;
+
+/// Warns about keystores which may have been created for another network, refusing to import them
+/// if `strict` is set.
+///
+/// Keystores carry little network information, but their derivation paths and the deposit data
+/// produced alongside them do. Importing a keystore for another network would leave the validator
+/// silently inactive.
+pub(super) fn check_keystore_networks(
+    keystores: &[(PathBuf, Keystore)],
+    genesis_fork_version: [u8; 4],
+    strict: bool,
+    refusals: &mut Refusals,
+    log: &Logger,
+) -> Result<(), String> {
+    let mismatches = find_network_mismatches(keystores, genesis_fork_version, log);
+    if mismatches.is_empty() {
+        return Ok(());
+    }
+
+    for (path, reason) in &mismatches {
+        warn!(log, "Keystore {:?} {}", path, reason);
+    }
+
+    if strict {
+        refusals.refuse(format!(
+            "Refusing to import {} keystores which may have been created for a different \
+             network, check --network",
+            mismatches.len()
+        ))?;
+    }
+    Ok(())
+}
+
+/// A single entry of a deposit data file produced by the `eth2.0-deposit-cli`.
+#[derive(Deserialize)]
+struct DepositDataEntry {
+    pubkey: String,
+    fork_version: String,
+    #[serde(default)]
+    network_name: Option<String>,
+}
+
+/// Returns the path of each of `keystores` which appears to have been created for a network other
+/// than the one with `genesis_fork_version`, along with the reason.
+///
+/// The derivation path of a keystore must be an EIP-2334 voting key path, whose purpose and coin
+/// type are the same on every network. The deposit data files in the same directory as the
+/// keystores record the fork version of the network that each keystore was created for. Keystores
+/// without a derivation path or deposit data are not checked.
+fn find_network_mismatches(
+    keystores: &[(PathBuf, Keystore)],
+    genesis_fork_version: [u8; 4],
+    log: &Logger,
+) -> Vec<(PathBuf, String)> {
+    let deposit_data = read_deposit_data(keystores, log);
+    let expected_fork_version = hex::encode(genesis_fork_version);
+
+    let mut mismatches = vec![];
+    for (path, keystore) in keystores {
+        if let Some(reason) = derivation_path_mismatch(keystore) {
+            mismatches.push((path.clone(), reason));
+        } else if let Some(entry) = deposit_data.get(keystore.pubkey()) {
+            let fork_version = entry.fork_version.trim_start_matches("0x");
+            if !fork_version.eq_ignore_ascii_case(&expected_fork_version) {
+                let network = match &entry.network_name {
+                    Some(name) => format!("{} (fork version 0x{})", name, fork_version),
+                    None => format!("fork version 0x{}", fork_version),
+                };
+                mismatches.push((
+                    path.clone(),
+                    format!(
+                        "has deposit data for {}, but the selected network has fork version 0x{}",
+                        network, expected_fork_version
+                    ),
+                ));
+            }
+        }
+    }
+
+    mismatches
+}
+
+/// Returns the reason that the derivation path of `keystore` is not an EIP-2334 voting key path,
+/// or `None` if it is, or it is not an EIP-2334 path at all.
+fn derivation_path_mismatch(keystore: &Keystore) -> Option<String> {
+    let path = keystore.path()?;
+    let nodes = path
+        .strip_prefix("m/")?
+        .split('/')
+        .map(|node| node.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    if !nodes.starts_with(&[PURPOSE, COIN_TYPE]) {
+        Some(format!(
+            "has derivation path {:?}, but keys for Ethereum 2.0 networks are derived from \
+             m/{}/{}",
+            path, PURPOSE, COIN_TYPE
+        ))
+    } else if voting_path_index(keystore).is_none() {
+        Some(format!(
+            "has derivation path {:?}, which is not a voting key path of the form m/{}/{}/i/0/0",
+            path, PURPOSE, COIN_TYPE
+        ))
+    } else {
+        None
+    }
+}
+
+/// Returns the entries of the deposit data files in the directories of `keystores`, by public key.
+///
+/// Deposit data is only used to check the network of the keystores, so files which cannot be read
+/// are skipped with a warning.
+fn read_deposit_data(
+    keystores: &[(PathBuf, Keystore)],
+    log: &Logger,
+) -> HashMap<String, DepositDataEntry> {
+    let mut deposit_data = HashMap::new();
+
+    let deposit_data_paths = keystores
+        .iter()
+        .filter_map(|(path, _)| path.parent())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter_map(|dir| match fs::read_dir(dir) {
+            Ok(entries) => Some(entries),
+            Err(e) => {
+                warn!(log, "Unable to read {:?}", dir; "error" => ?e);
+                None
+            }
+        })
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|file_name| file_name.to_str())
+                .map_or(false, |file_name| {
+                    file_name.starts_with(DEPOSIT_DATA_PREFIX) && file_name.ends_with(".json")
+                })
+        })
+        .collect::<Vec<_>>();
+
+    for path in deposit_data_paths {
+        let entries: Vec<DepositDataEntry> = match File::open(&path)
+            .map_err(|e| format!("Unable to open {:?}: {:?}", path, e))
+            .and_then(|file| {
+                serde_json::from_reader(file)
+                    .map_err(|e| format!("Unable to parse deposit data {:?}: {:?}", path, e))
+            }) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!(log, "{}", e);
+                continue;
+            }
+        };
+        for entry in entries {
+            let pubkey = entry.pubkey.trim_start_matches("0x").to_lowercase();
+            deposit_data.insert(pubkey, entry);
+        }
+    }
+
+    deposit_data
+}
+
+/// Checks that the `slashing_protection` database was used with the network which has the given
+/// `genesis_validators_root`, recording the root if the database has not recorded one yet.
+///
+/// The database is never modified if `dry_run` is set.
+pub(super) fn check_slashing_protection_network(
+    slashing_protection: &SlashingDatabase,
+    genesis_validators_root: Hash256,
+    dry_run: bool,
+) -> Result<(), String> {
+    let recorded_root = slashing_protection
+        .genesis_validators_root()
+        .map_err(|e| format!("Unable to read slashing protection database: {:?}", e))?;
+
+    match recorded_root {
+        Some(recorded_root) if recorded_root != genesis_validators_root => Err(format!(
+            "The slashing protection database is for the network with genesis validators root \
+             {:?}, but the selected network has {:?}. Check --network",
+            recorded_root, genesis_validators_root
+        )),
+        Some(_) => Ok(()),
+        None if dry_run => Ok(()),
+        None => slashing_protection
+            .set_genesis_validators_root(genesis_validators_root)
+            .map_err(|e| format!("Unable to update slashing protection database: {:?}", e)),
+    }
+}
@@ -0,0 +1,368 @@
+use super::staging::StagingDir;
+use super::{
+    is_imported, DEFAULT_MAX_PASSWORD_CHECK_THREADS, MIN_PASSWORD_ENTROPY_FLAG, PASSWORD_ENV_FLAG,
+    PASSWORD_MAP_FLAG, PASSWORD_PROMPT, SHARED_PASSWORD_FILE_PREFIX,
+};
+use crate::validator::common::read_password_file;
+use crate::SECRETS_DIR_FLAG;
+use account_utils::{
+    create_with_600_perms, default_keystore_password_path, estimate_password_entropy,
+    eth2_keystore::Keystore, read_password_from_user, validator_definitions::ValidatorDefinitions,
+    ZeroizeString,
+};
+use directory::ensure_dir_exists;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use slog::{info, Logger};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Reads the password from the environment variable `var`, which is then removed so that the
+/// password is not inherited by any other process.
+pub(super) fn read_env_password(var: &str) -> Result<ZeroizeString, String> {
+    let password = env::var(var)
+        .map(ZeroizeString::from)
+        .map_err(|e| format!("Unable to read password from ${}: {:?}", var, e));
+    env::remove_var(var);
+    let password = password?;
+    if password.as_ref().is_empty() {
+        return Err(format!("The password in ${} is empty", var));
+    }
+    Ok(password)
+}
+
+/// Reads the `--reencrypt-password-file` at `path`, checking it against the
+/// `--min-password-entropy`, if any.
+pub(super) fn read_reencrypt_password(
+    path: &Path,
+    min_password_entropy: Option<u32>,
+) -> Result<ZeroizeString, String> {
+    let password = read_password_file(path)?;
+    if password.as_ref().is_empty() {
+        return Err(format!("The password in {:?} is empty", path));
+    }
+    // Only the password being set is checked, since the existing keystore passwords are not
+    // chosen by this import.
+    if let Some(min_entropy) = min_password_entropy {
+        let entropy = estimate_password_entropy(password.as_str());
+        if entropy < f64::from(min_entropy) {
+            return Err(format!(
+                "The password in {:?} has an estimated entropy of {:.0} bits, which is less \
+                 than the --{} of {} bits",
+                path, entropy, MIN_PASSWORD_ENTROPY_FLAG, min_entropy
+            ));
+        }
+    }
+    Ok(password)
+}
+
+/// Obtains the password of each keystore, from the `PasswordSources` or by prompting the user.
+pub(super) struct PasswordPrompt<'a> {
+    pub(super) sources: PasswordSources<'a>,
+    /// The passwords which were checked up-front by `check_passwords`.
+    pub(super) checked_passwords: HashMap<PathBuf, CheckedPassword>,
+    /// The password of the previous keystore, when it is reused for every keystore.
+    pub(super) previous_password: Option<ZeroizeString>,
+    /// The file to which a reused password was written by `--store-password-once`.
+    pub(super) shared_password_path: Option<PathBuf>,
+    pub(super) reuse_password: bool,
+    pub(super) shared_password_dir: Option<&'a Path>,
+    /// The staging directory of an `--all-or-nothing` import, which removes the shared password
+    /// file if the import is not committed.
+    pub(super) staging: Option<&'a StagingDir>,
+    pub(super) stdin_inputs: bool,
+    pub(super) all_or_nothing: bool,
+    pub(super) max_password_attempts: usize,
+    pub(super) ux_delay: Option<Duration>,
+    pub(super) log: &'a Logger,
+}
+
+impl<'a> PasswordPrompt<'a> {
+    /// Returns the password of `keystore`, which was read from `src_keystore`, or `None` if the
+    /// user chose to omit it.
+    ///
+    /// The returned flag is `true` if the password was checked up-front by `check_passwords`.
+    pub(super) fn password(
+        &mut self,
+        src_keystore: &Path,
+        keystore: &Keystore,
+    ) -> Result<(Option<ZeroizeString>, bool), String> {
+        let log = self.log;
+        let sources = &self.sources;
+        let mut password_attempts = 0;
+        loop {
+            if let Some(password) = self.previous_password.clone() {
+                info!(log, "Reusing the previous password");
+                return Ok((Some(password), false));
+            }
+            let password_file = sources
+                .password_files
+                .get(src_keystore)
+                .map(PathBuf::as_path)
+                .or(sources.password_file);
+            let password = match sources.password(keystore, src_keystore)? {
+                Some(password) => password,
+                None => {
+                    eprintln!();
+                    eprintln!("{}", PASSWORD_PROMPT);
+
+                    read_password_from_user(self.stdin_inputs)?
+                }
+            };
+
+            if password.as_ref().is_empty() {
+                info!(log, "Continuing without password");
+                if let Some(delay) = self.ux_delay {
+                    sleep(delay);
+                }
+                return Ok((None, false));
+            }
+
+            // A password which was checked up-front is only trusted if it has not changed since.
+            let checked = self
+                .checked_passwords
+                .remove(src_keystore)
+                .filter(|checked| checked.password == password)
+                .map(|checked| checked.result);
+            let password_checked = checked.is_some();
+            let decrypted = match checked {
+                Some(result) => result,
+                None => keystore.decrypt_keypair(password.as_ref()).map(|_| ()),
+            };
+
+            match decrypted {
+                Ok(()) => {
+                    info!(log, "Password is correct");
+                    if let Some(delay) = self.ux_delay {
+                        sleep(delay);
+                    }
+                    if self.reuse_password {
+                        self.previous_password = Some(password.clone());
+                    }
+                    if let Some(shared_password_dir) = self.shared_password_dir {
+                        ensure_dir_exists(shared_password_dir)?;
+                        let password_path = unused_shared_password_path(shared_password_dir);
+                        create_with_600_perms(&password_path, password.as_ref()).map_err(|e| {
+                            format!("Unable to write password to {:?}: {:?}", password_path, e)
+                        })?;
+                        info!(log, "Stored password in {:?}", password_path);
+                        if let Some(staging) = self.staging {
+                            staging.add_file(password_path.clone());
+                        }
+                        self.shared_password_path = Some(password_path);
+                    }
+                    return Ok((Some(password), password_checked));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) if self.all_or_nothing => {
+                    return Err(format!(
+                        "Invalid password for keystore {:?}, no keystores were imported",
+                        src_keystore
+                    ));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) if sources.env_password.is_some() => {
+                    return Err(format!(
+                        "The password from --{} is invalid for keystore {:?}",
+                        PASSWORD_ENV_FLAG, src_keystore
+                    ));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) if sources.password_map.is_some() => {
+                    return Err(format!(
+                        "The password from --{} is invalid for keystore {:?}",
+                        PASSWORD_MAP_FLAG, src_keystore
+                    ));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) if sources.secrets_dir.is_some() => {
+                    return Err(format!(
+                        "The password in {:?} is invalid for keystore {:?}",
+                        sources
+                            .secrets_dir
+                            .map(|dir| default_keystore_password_path(keystore, dir))
+                            .unwrap_or_default(),
+                        src_keystore
+                    ));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) if password_file.is_some() => {
+                    return Err(format!(
+                        "The password in {:?} is invalid for keystore {:?}",
+                        password_file, src_keystore
+                    ));
+                }
+                // Retrying is only useful to a human, a script would supply the same password.
+                Err(eth2_keystore::Error::InvalidPassword) if self.stdin_inputs => {
+                    return Err(format!("Invalid password for keystore {:?}", src_keystore));
+                }
+                Err(eth2_keystore::Error::InvalidPassword) => {
+                    // Printed rather than logged, so that it precedes the next prompt.
+                    eprintln!("Invalid password");
+                    password_attempts += 1;
+                    if password_attempts >= self.max_password_attempts {
+                        return Err(format!(
+                            "Invalid password for keystore {:?} after {} attempts",
+                            src_keystore, password_attempts
+                        ));
+                    }
+                }
+                Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+            }
+        }
+    }
+}
+
+/// Returns a path in `shared_password_dir` for a password shared by several validators.
+///
+/// The file is not named after any one validator, and an existing file (e.g. the shared password
+/// of an earlier import) is never reused.
+fn unused_shared_password_path(shared_password_dir: &Path) -> PathBuf {
+    (0..)
+        .map(|i| shared_password_dir.join(format!("{}{}", SHARED_PASSWORD_FILE_PREFIX, i)))
+        .find(|path| !path.exists())
+        .expect("an unused path exists")
+}
+
+/// Reads the `--password-map` at `path`, a JSON object which maps the public key of each keystore
+/// to its password.
+///
+/// The public keys are returned in lower-case without the `0x` prefix, as per `Keystore::pubkey`.
+pub(super) fn read_password_map(path: &Path) -> Result<HashMap<String, ZeroizeString>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Unable to open --{} {:?}: {:?}", PASSWORD_MAP_FLAG, path, e))?;
+    let map: HashMap<String, ZeroizeString> = serde_json::from_reader(file).map_err(|e| {
+        format!(
+            "Unable to parse --{} {:?}: {:?}",
+            PASSWORD_MAP_FLAG, path, e
+        )
+    })?;
+
+    map.into_iter()
+        .map(|(pubkey, password)| {
+            let pubkey = pubkey.trim_start_matches("0x").to_lowercase();
+            if password.as_ref().is_empty() {
+                return Err(format!(
+                    "The password for 0x{} in --{} is empty",
+                    pubkey, PASSWORD_MAP_FLAG
+                ));
+            }
+            Ok((pubkey, password))
+        })
+        .collect()
+}
+
+/// The sources of keystore passwords which do not require prompting the user.
+pub(super) struct PasswordSources<'a> {
+    pub(super) env_password: Option<&'a ZeroizeString>,
+    pub(super) password_map: Option<&'a HashMap<String, ZeroizeString>>,
+    pub(super) secrets_dir: Option<&'a Path>,
+    /// The password file of each keystore, for clients which store them alongside the keystores.
+    pub(super) password_files: &'a HashMap<PathBuf, PathBuf>,
+    pub(super) password_file: Option<&'a Path>,
+}
+
+impl<'a> PasswordSources<'a> {
+    /// Returns the password of `keystore`, which was read from `src_keystore`, or `None` if it
+    /// must be prompted for.
+    fn password(
+        &self,
+        keystore: &Keystore,
+        src_keystore: &Path,
+    ) -> Result<Option<ZeroizeString>, String> {
+        if let Some(password) = self.env_password {
+            Ok(Some(password.clone()))
+        } else if let Some(password_map) = self.password_map {
+            password_map
+                .get(&keystore.pubkey().to_lowercase())
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| {
+                    format!(
+                        "No password for keystore {:?} in --{}",
+                        src_keystore, PASSWORD_MAP_FLAG
+                    )
+                })
+        } else if let Some(secrets_dir) = self.secrets_dir {
+            read_password_from_dir(keystore, secrets_dir).map(Some)
+        } else if let Some(password_file) = self
+            .password_files
+            .get(src_keystore)
+            .map(PathBuf::as_path)
+            .or(self.password_file)
+        {
+            read_password_file(password_file).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// The outcome of decrypting a keystore with a password by `check_passwords`.
+pub(super) struct CheckedPassword {
+    password: ZeroizeString,
+    result: Result<(), eth2_keystore::Error>,
+}
+
+/// Decrypts each of `keystores` whose password is available from `password_sources`, on a pool of
+/// `threads` threads (by default, one per CPU up to `DEFAULT_MAX_PASSWORD_CHECK_THREADS`),
+/// returning the outcome by path.
+///
+/// Keystores which have already been imported, or whose password cannot be read, are omitted, so
+/// that they are handled by the import itself.
+pub(super) fn check_passwords(
+    keystores: &[(PathBuf, Keystore)],
+    password_sources: &PasswordSources,
+    validator_dir: &Path,
+    defs: &ValidatorDefinitions,
+    threads: Option<usize>,
+) -> Result<HashMap<PathBuf, CheckedPassword>, String> {
+    let threads = threads
+        .unwrap_or_else(|| rayon::current_num_threads().min(DEFAULT_MAX_PASSWORD_CHECK_THREADS));
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| format!("Unable to create thread pool: {:?}", e))?;
+
+    Ok(pool.install(|| {
+        keystores
+            .par_iter()
+            .filter_map(|(path, keystore)| {
+                if is_imported(keystore, validator_dir, defs) {
+                    return None;
+                }
+                let password = password_sources
+                    .password(keystore, path)
+                    .ok()
+                    .flatten()
+                    .filter(|password| !password.as_ref().is_empty())?;
+
+                let result = keystore.decrypt_keypair(password.as_ref()).map(|_| ());
+                Some((path.clone(), CheckedPassword { password, result }))
+            })
+            .collect()
+    }))
+}
+
+/// Reads the password of `keystore` from the file in `secrets_dir` which is named after its public
+/// key.
+fn read_password_from_dir(
+    keystore: &Keystore,
+    secrets_dir: &Path,
+) -> Result<ZeroizeString, String> {
+    let path = default_keystore_password_path(keystore, secrets_dir);
+    if !path.exists() {
+        return Err(format!(
+            "No password for keystore 0x{} in --{} {:?}",
+            keystore.pubkey(),
+            SECRETS_DIR_FLAG,
+            secrets_dir
+        ));
+    }
+
+    // An empty file would otherwise silently import the keystore without a password.
+    let password = read_password_file(&path)?;
+    if password.as_ref().is_empty() {
+        return Err(format!("The password in {:?} is empty", path));
+    }
+    Ok(password)
+}
@@ -0,0 +1,211 @@
+use super::LOG_FILE_FLAG;
+use account_utils::create_with_600_perms;
+use serde_derive::{Deserialize, Serialize};
+use slog::{info, o, warn, Drain, Duplicate, Level, Logger};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The outcome of importing a single keystore.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeystoreStatus {
+    Imported,
+    Skipped,
+    Failed,
+    /// The public key of the keystore was registered with slashing protection by a
+    /// `--register-only` import, without importing the keystore.
+    Registered,
+    /// The keystore was not imported because the import stopped, or was rolled back, before it
+    /// could be committed. A `--dry-run` import reports each keystore which would have been
+    /// imported with this status.
+    NotImported,
+}
+
+/// The report of a single keystore in an `ImportReport`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeystoreReport {
+    pub path: PathBuf,
+    pub pubkey: Option<String>,
+    pub status: KeystoreStatus,
+}
+
+/// A summary of an import which is written to the `--json-output-file`.
+///
+/// Must never contain any passwords or secret keys.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportReport {
+    /// Seconds since the UNIX epoch at which the import finished.
+    pub timestamp: u64,
+    pub discovered: usize,
+    pub imported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub error: Option<String>,
+    pub keystores: Vec<KeystoreReport>,
+}
+
+impl ImportReport {
+    /// Sets the summary fields of `self` from the statuses of the keystores.
+    pub(super) fn finalize(&mut self, error: Option<&String>) -> Result<(), String> {
+        let count = |keystores: &[KeystoreReport], status| {
+            keystores
+                .iter()
+                .filter(|keystore| keystore.status == status)
+                .count()
+        };
+
+        self.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs();
+        self.discovered = self.keystores.len();
+        self.imported = count(&self.keystores, KeystoreStatus::Imported);
+        self.skipped = count(&self.keystores, KeystoreStatus::Skipped);
+        self.failed = count(&self.keystores, KeystoreStatus::Failed);
+        self.error = error.cloned();
+
+        Ok(())
+    }
+
+    /// Writes `self` to `path` as JSON.
+    ///
+    /// The report is written to a temporary file which is then renamed, so that `path` never
+    /// contains a partially written report.
+    pub(super) fn write_to_file(&self, path: &Path) -> Result<(), String> {
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+
+        File::create(&temp_path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", temp_path, e))
+            .and_then(|mut file| {
+                serde_json::to_writer_pretty(&mut file, self)
+                    .map_err(|e| format!("Unable to write report: {:?}", e))?;
+                file.sync_all()
+                    .map_err(|e| format!("Unable to sync {:?}: {:?}", temp_path, e))
+            })?;
+
+        fs::rename(&temp_path, path)
+            .map_err(|e| format!("Unable to rename {:?} to {:?}: {:?}", temp_path, path, e))
+    }
+}
+
+/// Logs the summary of a `--dry-run` import, returning an error if the import would fail.
+pub(super) fn report_dry_run(
+    num_importable: usize,
+    num_skipped: usize,
+    num_failed: usize,
+    refusals: &[String],
+    log: &Logger,
+) -> Result<(), String> {
+    info!(log, "Dry run complete, nothing was imported");
+    info!(log, "{} keystores would be imported", num_importable);
+    info!(log, "{} keystores would be skipped", num_skipped);
+    info!(log, "{} keystores would fail to import", num_failed);
+    for reason in refusals {
+        warn!(log, "{}", reason);
+    }
+
+    if num_failed > 0 || !refusals.is_empty() {
+        Err(format!(
+            "The import would fail ({} keystores failed, {} other problems)",
+            num_failed,
+            refusals.len()
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// A line on stderr which shows the progress of an import and the estimated time remaining.
+///
+/// The line is redrawn in place, so it is never written to the `--log-file`.
+pub(super) struct Progress {
+    total: usize,
+    completed: usize,
+    start: Instant,
+    /// Whether the line has been drawn since it was last ended.
+    drawn: bool,
+}
+
+impl Progress {
+    pub(super) fn new(total: usize) -> Self {
+        Self {
+            total,
+            completed: 0,
+            start: Instant::now(),
+            drawn: false,
+        }
+    }
+
+    /// Records that another keystore has been processed and redraws the line, which is ended
+    /// once all keystores have been processed.
+    pub(super) fn increment(&mut self) {
+        self.completed += 1;
+
+        let remaining = self
+            .start
+            .elapsed()
+            .mul_f64((self.total - self.completed) as f64 / self.completed as f64);
+        let line = format!(
+            "[{}/{}] {}% ETA {}",
+            self.completed,
+            self.total,
+            self.completed * 100 / self.total,
+            format_duration(remaining)
+        );
+        // The padding overwrites the end of a longer line drawn previously.
+        eprint!("\r{:<40}", line);
+        self.drawn = true;
+
+        if self.completed == self.total {
+            self.end_line();
+        }
+    }
+
+    /// Ends the line, so that other output is not printed on the same line. The line is drawn
+    /// again by the next `increment`.
+    pub(super) fn end_line(&mut self) {
+        if self.drawn {
+            eprintln!();
+            self.drawn = false;
+        }
+    }
+}
+
+/// Formats `duration` as hours, minutes and seconds, e.g. `1:02:03`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Opens the `--log-file`, returning a logger which writes every record to it, including those at
+/// the debug level.
+///
+/// The file is created with restrictive permissions, since it may contain the paths and public keys
+/// of every keystore.
+pub(super) fn open_log_file(path: &Path) -> Result<Logger, String> {
+    let file = create_with_600_perms(path, &[])
+        .and_then(|()| OpenOptions::new().append(true).open(path))
+        .map_err(|e| format!("Unable to create --{} {:?}: {:?}", LOG_FILE_FLAG, path, e))?;
+    // The import continues without the log file if it cannot be written.
+    let drain = slog_term::FullFormat::new(slog_term::PlainSyncDecorator::new(file))
+        .build()
+        .ignore_res()
+        .filter_level(Level::Debug);
+
+    Ok(Logger::root(drain.fuse(), o!()))
+}
+
+/// Returns a logger for the import, which writes every record to the `file_log`, if any, and only
+/// those at the `Warning` level or above to `log` if `quiet` is `true`.
+pub(super) fn import_logger(log: &Logger, file_log: Option<&Logger>, quiet: bool) -> Logger {
+    let min_level = if quiet { Level::Warning } else { Level::Trace };
+    let terminal = log.clone().filter_level(min_level);
+
+    match file_log {
+        Some(file_log) => Logger::root(Duplicate::new(terminal, file_log.clone()).fuse(), o!()),
+        None => Logger::root(terminal.fuse(), o!()),
+    }
+}
@@ -0,0 +1,156 @@
+use crate::validator::common::{
+    new_validator_definition, remove_definitions, save_new_definitions,
+};
+use account_utils::{validator_definitions::ValidatorDefinitions, ZeroizeString};
+use slashing_protection::SlashingDatabase;
+use slog::{error, Logger};
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use types::{GraffitiString, PublicKey};
+
+/// A directory which is removed, along with its contents, when dropped.
+///
+/// Files which the import writes outside of the directory (e.g. the shared password file) are
+/// added with `add_file`, and are also removed when dropped unless `keep_files` has been called.
+pub(super) struct StagingDir {
+    pub(super) path: PathBuf,
+    files: RefCell<Vec<PathBuf>>,
+    log: Logger,
+}
+
+impl StagingDir {
+    /// Creates the directory at `path`, failing if it already exists.
+    pub(super) fn create(path: PathBuf, log: &Logger) -> Result<Self, String> {
+        if path.exists() {
+            return Err(format!(
+                "Staging directory {:?} already exists, it may be left over from a failed \
+                 import and should be removed manually",
+                path
+            ));
+        }
+
+        fs::create_dir_all(&path)
+            .map_err(|e| format!("Unable to create staging directory {:?}: {:?}", path, e))?;
+
+        Ok(Self {
+            path,
+            files: RefCell::new(vec![]),
+            log: log.clone(),
+        })
+    }
+
+    /// Adds the file at `path` to those which are removed along with the directory.
+    pub(super) fn add_file(&self, path: PathBuf) {
+        self.files.borrow_mut().push(path)
+    }
+
+    /// Keeps the files which were added with `add_file`, once the import has been committed.
+    pub(super) fn keep_files(&self) {
+        self.files.borrow_mut().clear()
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        for path in self.files.borrow().iter() {
+            if let Err(e) = fs::remove_file(path) {
+                error!(self.log, "Unable to remove {:?}", path; "error" => ?e);
+            }
+        }
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            error!(
+                self.log,
+                "Unable to remove staging directory {:?}", self.path;
+                "error" => ?e,
+            );
+        }
+    }
+}
+
+/// A keystore which has been copied into the staging directory, but not yet imported.
+pub(super) struct StagedImport {
+    pub(super) src_keystore: PathBuf,
+    pub(super) staged_dir: PathBuf,
+    pub(super) dest_dir: PathBuf,
+    pub(super) dest_keystore: PathBuf,
+    pub(super) voting_pubkey: PublicKey,
+    pub(super) password: Option<ZeroizeString>,
+}
+
+/// Moves each of the `staged_imports` into the validator directory, registers them with
+/// slashing protection and saves them to the validator definitions file.
+///
+/// If any of the keystores cannot be moved into place, those already moved are moved back into
+/// the staging directory.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn commit_staged_imports(
+    staged_imports: &[StagedImport],
+    slashing_protection: &SlashingDatabase,
+    defs: &mut ValidatorDefinitions,
+    validator_dir: &Path,
+    shared_password_path: Option<&PathBuf>,
+    enabled: bool,
+    graffiti: &Option<GraffitiString>,
+    log: &Logger,
+) -> Result<(), String> {
+    let mut new_defs = Vec::with_capacity(staged_imports.len());
+
+    for (i, staged) in staged_imports.iter().enumerate() {
+        if let Err(e) = fs::rename(&staged.staged_dir, &staged.dest_dir) {
+            for moved in &staged_imports[..i] {
+                let _ = fs::rename(&moved.dest_dir, &moved.staged_dir);
+            }
+            return Err(format!(
+                "Unable to move {:?} to {:?}: {:?}",
+                staged.staged_dir, staged.dest_dir, e
+            ));
+        }
+    }
+
+    let result = staged_imports.iter().try_for_each(|staged| {
+        let validator_def = new_validator_definition(
+            &staged.dest_keystore,
+            staged.password.clone(),
+            shared_password_path,
+            enabled,
+            graffiti,
+        )?;
+        new_defs.push(validator_def);
+        Ok(())
+    });
+
+    let move_back = || {
+        for moved in staged_imports {
+            let _ = fs::rename(&moved.dest_dir, &moved.staged_dir);
+        }
+    };
+
+    if let Err(e) = result {
+        move_back();
+        return Err(e);
+    }
+
+    // As with a regular import, the definitions are saved before the validators are registered
+    // with slashing protection.
+    if let Err(e) = save_new_definitions(defs, validator_dir, new_defs) {
+        move_back();
+        return Err(e);
+    }
+
+    if let Err(e) = slashing_protection
+        .register_validators(staged_imports.iter().map(|staged| &staged.voting_pubkey))
+    {
+        let pubkeys = staged_imports
+            .iter()
+            .map(|staged| &staged.voting_pubkey)
+            .collect::<Vec<_>>();
+        if let Err(e) = remove_definitions(defs, validator_dir, &pubkeys) {
+            error!(log, "Unable to remove definitions"; "error" => e);
+        }
+        move_back();
+        return Err(format!("Error registering validators: {:?}", e));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use super::common::new_validator_definition;
+use crate::common::read_mnemonic_from_cli;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    eth2_keystore::{keypair_from_secret, KeystoreBuilder},
+    eth2_wallet::{bip39::Seed, recover_validator_secret_from_mnemonic, KeyType},
+    is_password_sufficiently_complex, read_password_from_user,
+    validator_definitions::{ValidatorDefinitions, CONFIG_FILENAME},
+};
+use clap::{App, Arg, ArgMatches};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs::{self, File};
+use std::path::PathBuf;
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+pub const CMD: &str = "import-mnemonic";
+pub const MNEMONIC_FILE_FLAG: &str = "mnemonic-file";
+pub const COUNT_FLAG: &str = "count";
+pub const OFFSET_FLAG: &str = "offset";
+
+pub const PASSWORD_PROMPT: &str = "Enter a password to encrypt the derived keystores with:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Derives a range of validator keys from a BIP-39 mnemonic according to EIP-2333 and \
+            EIP-2334, encrypts them with a password and imports them into a Lighthouse VC \
+            directory in the same way as the import command.",
+        )
+        .arg(
+            Arg::with_name(MNEMONIC_FILE_FLAG)
+                .long(MNEMONIC_FILE_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(COUNT_FLAG)
+                .long(COUNT_FLAG)
+                .value_name("COUNT")
+                .help("The number of validator keys to derive and import.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(OFFSET_FLAG)
+                .long(OFFSET_FLAG)
+                .value_name("OFFSET")
+                .help("The EIP-2334 index of the first validator key to derive.")
+                .takes_value(true)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FILE_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let offset: u32 = clap_utils::parse_required(matches, OFFSET_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let last_index = offset
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} plus --{} is too large", OFFSET_FLAG, COUNT_FLAG))?;
+
+    let mut defs = ValidatorDefinitions::open_or_create(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection =
+        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
+            format!(
+                "Unable to open or create slashing protection database at {}: {:?}",
+                slashing_protection_path.display(),
+                e
+            )
+        })?;
+
+    // Create an empty transaction and drop it. Used to test if the database is locked.
+    slashing_protection.test_transaction().map_err(|e| {
+        format!(
+            "Cannot import keys while the validator client is running: {:?}",
+            e
+        )
+    })?;
+
+    eprintln!("");
+    eprintln!("WARNING: KEY RECOVERY CAN LEAD TO DUPLICATING VALIDATORS KEYS, WHICH CAN LEAD TO SLASHING.");
+    eprintln!("");
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let password = loop {
+        eprintln!("{}", PASSWORD_PROMPT);
+
+        let password = read_password_from_user(stdin_inputs)?;
+
+        match is_password_sufficiently_complex(password.as_ref()) {
+            Ok(()) => break password,
+            Err(e) => eprintln!("{}", e),
+        }
+    };
+
+    eprintln!(
+        "The password will be stored as plain-text in {} so that it is not required each time \
+         the validator client starts.",
+        CONFIG_FILENAME
+    );
+
+    let mut num_imported_keystores = 0;
+    for index in offset..last_index {
+        let (secret, path) =
+            recover_validator_secret_from_mnemonic(seed.as_bytes(), index, KeyType::Voting)
+                .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable build keystore: {:?}", e))?;
+
+        // The keystore is placed in a directory that matches the name of the public key, in the
+        // same way as the import command.
+        let dest_dir = validator_dir.join(keypair.pk.to_hex_string());
+        if dest_dir.exists() {
+            eprintln!(
+                "Skipping import of index {} for existing public key: {}",
+                index,
+                keypair.pk.to_hex_string()
+            );
+            continue;
+        }
+
+        let keystore = KeystoreBuilder::new(&keypair, password.as_ref(), format!("{}", path))
+            .map_err(|e| format!("Unable build keystore: {:?}", e))?
+            .build()
+            .map_err(|e| format!("Unable build keystore: {:?}", e))?;
+
+        fs::create_dir_all(&dest_dir)
+            .map_err(|e| format!("Unable to create import directory: {:?}", e))?;
+
+        let dest_keystore = dest_dir.join(VOTING_KEYSTORE_FILE);
+        File::create(&dest_keystore)
+            .map_err(|e| format!("Unable to create keystore file: {:?}", e))
+            .and_then(|file| {
+                keystore
+                    .to_json_writer(file)
+                    .map_err(|e| format!("Unable to write keystore: {:?}", e))
+            })?;
+
+        let validator_def =
+            new_validator_definition(&dest_keystore, Some(password.clone()), None, true, &None)?;
+        defs.push(validator_def);
+        defs.save(&validator_dir)
+            .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+        // As with the import command, only register with slashing protection once the
+        // definition has been saved.
+        slashing_protection
+            .register_validator(&keypair.pk)
+            .map_err(|e| {
+                format!(
+                    "Error registering validator {}: {:?}",
+                    keypair.pk.to_hex_string(),
+                    e
+                )
+            })?;
+
+        num_imported_keystores += 1;
+
+        println!("{}\tIndex: {}\t{}", path, index, keypair.pk.to_hex_string());
+    }
+
+    eprintln!("");
+    eprintln!(
+        "Successfully imported {} validators ({} skipped).",
+        num_imported_keystores,
+        count as usize - num_imported_keystores
+    );
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use bls::get_withdrawal_credentials;
+use clap::{App, Arg, ArgMatches};
+use environment::Environment;
+use eth2_ledger::{Error as LedgerError, Ledger};
+use eth2_wallet::{KeyType, ValidatorPath};
+use types::{EthSpec, Hash256, PublicKey};
+
+pub const CMD: &str = "ledger-withdrawal-key";
+pub const INDEX_FLAG: &str = "index";
+pub const WITHDRAWAL_CREDENTIALS_FLAG: &str = "withdrawal-credentials";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Derives an EIP-2334 withdrawal key on a Ledger device with the Ethereum app open, \
+            and shows its public key and withdrawal credentials. The key never leaves the device. \
+            Requires the ledger feature.",
+        )
+        .arg(
+            Arg::with_name(INDEX_FLAG)
+                .long(INDEX_FLAG)
+                .value_name("INDEX")
+                .help("The index of the withdrawal key, i.e. m/12381/3600/INDEX/0.")
+                .required(true)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(WITHDRAWAL_CREDENTIALS_FLAG)
+                .long(WITHDRAWAL_CREDENTIALS_FLAG)
+                .value_name("WITHDRAWAL_CREDENTIALS")
+                .help(
+                    "The 0x-prefixed withdrawal credentials of a validator. If present, the \
+                    command fails unless they were derived from the withdrawal key.",
+                )
+                .takes_value(true),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
+    let index: u32 = clap_utils::parse_required(matches, INDEX_FLAG)?;
+    let expected_credentials = matches
+        .value_of(WITHDRAWAL_CREDENTIALS_FLAG)
+        .map(parse_withdrawal_credentials)
+        .transpose()?;
+    let spec = &env.eth2_config.spec;
+
+    let pubkey = withdrawal_public_key(index)?;
+    let credentials = Hash256::from_slice(&get_withdrawal_credentials(
+        &pubkey,
+        spec.bls_withdrawal_prefix_byte,
+    ));
+
+    println!("Path: {}", ValidatorPath::new(index, KeyType::Withdrawal));
+    println!("Withdrawal public key: {}", pubkey);
+    println!("Withdrawal credentials: {:?}", credentials);
+
+    match expected_credentials {
+        Some(expected) if expected != credentials => Err(format!(
+            "The withdrawal credentials {:?} were not derived from the withdrawal key at index {}",
+            expected, index
+        )),
+        Some(_) => {
+            eprintln!("The withdrawal credentials match the withdrawal key.");
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Returns the public key of the withdrawal key at `index` on the connected Ledger device.
+///
+/// The public key is shown on the device and must be approved there, so that it cannot be
+/// substituted by this machine.
+pub fn withdrawal_public_key(index: u32) -> Result<PublicKey, String> {
+    let ledger = Ledger::open().map_err(ledger_error)?;
+
+    eprintln!(
+        "Please approve the withdrawal public key for {} on the Ledger device.",
+        ValidatorPath::new(index, KeyType::Withdrawal)
+    );
+    ledger
+        .withdrawal_public_key(index, true)
+        .map_err(ledger_error)
+}
+
+/// Parses 0x-prefixed, 32 byte withdrawal credentials.
+fn parse_withdrawal_credentials(credentials: &str) -> Result<Hash256, String> {
+    let bytes = credentials
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Withdrawal credentials {} must start with 0x", credentials))
+        .and_then(|hex_credentials| {
+            hex::decode(hex_credentials)
+                .map_err(|e| format!("Invalid withdrawal credentials {}: {:?}", credentials, e))
+        })?;
+
+    if bytes.len() == Hash256::len_bytes() {
+        Ok(Hash256::from_slice(&bytes))
+    } else {
+        Err(format!(
+            "Withdrawal credentials {} must be {} bytes",
+            credentials,
+            Hash256::len_bytes()
+        ))
+    }
+}
+
+fn ledger_error(e: LedgerError) -> String {
+    match e {
+        LedgerError::HidUnsupported => {
+            "Ledger devices are not supported, lighthouse must be compiled with the ledger \
+            feature"
+                .to_string()
+        }
+        LedgerError::NoDevice => "No Ledger device is connected".to_string(),
+        LedgerError::UserRejected => "The request was rejected on the Ledger device".to_string(),
+        LedgerError::AppUnavailable => {
+            "Please open the Ethereum app on the Ledger device, and update it if it does not \
+            support Eth2 keys"
+                .to_string()
+        }
+        e => format!("Ledger error: {:?}", e),
+    }
+}
@@ -1,14 +1,54 @@
-use account_utils::validator_definitions::ValidatorDefinitions;
-use clap::App;
+use account_utils::validator_definitions::{SigningDefinition, ValidatorDefinitions};
+use clap::{App, Arg, ArgMatches};
+use serde_derive::{Deserialize, Serialize};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::path::PathBuf;
+use types::{GraffitiString, PublicKey};
 
 pub const CMD: &str = "list";
+pub const FORMAT_FLAG: &str = "format";
+pub const JSON_FLAG: &str = "json";
+
+/// The details of a single validator, as printed by the list command.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorSummary {
+    pub voting_public_key: PublicKey,
+    pub enabled: bool,
+    pub description: String,
+    pub graffiti: Option<GraffitiString>,
+    /// The method used to sign messages, as named in the validator definitions file.
+    pub signing_method: String,
+    /// The path of the keystore, for validators which sign with a local keystore.
+    pub keystore_path: Option<PathBuf>,
+    /// `true` if the keystore password is stored in the definition or in a password file.
+    pub password_stored: bool,
+    /// `None` if the slashing protection database could not be read.
+    pub slashing_protection_registered: Option<bool>,
+}
 
 pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
-    App::new(CMD).about("Lists the public keys of all validators.")
+    App::new(CMD)
+        .about("Lists the public keys of all validators.")
+        .arg(
+            Arg::with_name(FORMAT_FLAG)
+                .long(FORMAT_FLAG)
+                .value_name("FORMAT")
+                .help("The format in which to print the validators.")
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(JSON_FLAG)
+                .long(JSON_FLAG)
+                .help("If present, print the validators as JSON. Equivalent to --format json."),
+        )
 }
 
-pub fn cli_run(validator_dir: PathBuf) -> Result<(), String> {
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let format: String = clap_utils::parse_required(matches, FORMAT_FLAG)?;
+    let json_output = matches.is_present(JSON_FLAG) || format == "json";
+
     let validator_definitions = ValidatorDefinitions::open(&validator_dir).map_err(|e| {
         format!(
             "No validator definitions found in {:?}: {:?}",
@@ -16,11 +56,84 @@ pub fn cli_run(validator_dir: PathBuf) -> Result<(), String> {
         )
     })?;
 
-    for def in validator_definitions.as_slice() {
+    // The database is not created if it does not exist, since listing should not modify the
+    // validator directory.
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection = if slashing_protection_path.exists() {
+        SlashingDatabase::open(&slashing_protection_path)
+            .map_err(|e| eprintln!("Unable to open slashing protection database: {:?}", e))
+            .ok()
+    } else {
+        None
+    };
+
+    let summaries = validator_definitions
+        .as_slice()
+        .iter()
+        .map(|def| {
+            let (signing_method, keystore_path, password_stored) = match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    voting_keystore_password_path,
+                    voting_keystore_password,
+                } => (
+                    "local_keystore",
+                    Some(voting_keystore_path.clone()),
+                    voting_keystore_password_path.is_some() || voting_keystore_password.is_some(),
+                ),
+            };
+            let slashing_protection_registered = slashing_protection
+                .as_ref()
+                .map(|db| db.get_validator_id(&def.voting_public_key).is_ok());
+
+            ValidatorSummary {
+                voting_public_key: def.voting_public_key.clone(),
+                enabled: def.enabled,
+                description: def.description.clone(),
+                graffiti: def.graffiti.clone(),
+                signing_method: signing_method.to_string(),
+                keystore_path,
+                password_stored,
+                slashing_protection_registered,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if json_output {
+        let json = serde_json::to_string_pretty(&summaries)
+            .map_err(|e| format!("Unable to encode validators as JSON: {:?}", e))?;
+        println!("{}", json);
+        return Ok(());
+    }
+
+    for summary in &summaries {
         println!(
             "{} ({})",
-            def.voting_public_key,
-            if def.enabled { "enabled" } else { "disabled" }
+            summary.voting_public_key,
+            if summary.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+        if let Some(graffiti) = &summary.graffiti {
+            println!("  graffiti: {}", graffiti.as_str());
+        }
+        println!("  signing method: {}", summary.signing_method);
+        if let Some(keystore_path) = &summary.keystore_path {
+            println!("  keystore: {}", keystore_path.display());
+        }
+        println!(
+            "  password stored: {}",
+            if summary.password_stored { "yes" } else { "no" }
+        );
+        println!(
+            "  slashing protection: {}",
+            match summary.slashing_protection_registered {
+                Some(true) => "registered",
+                Some(false) => "NOT REGISTERED",
+                None => "unknown",
+            }
         );
     }
 
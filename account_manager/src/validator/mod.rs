@@ -1,9 +1,21 @@
+pub mod archive;
+pub mod bls_to_execution_change;
+pub mod change_password;
+pub mod common;
 pub mod create;
+pub mod delete;
+pub mod deposit_data;
 pub mod exit;
+pub mod export;
 pub mod import;
+pub mod import_mnemonic;
+pub mod ledger;
 pub mod list;
+pub mod other_client;
+pub mod rebuild_definitions;
 pub mod recover;
 pub mod slashing_protection;
+pub mod verify;
 
 use crate::VALIDATOR_DIR_FLAG;
 use clap::{App, Arg, ArgMatches};
@@ -28,12 +40,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .conflicts_with("datadir"),
         )
+        .subcommand(bls_to_execution_change::cli_app())
+        .subcommand(change_password::cli_app())
         .subcommand(create::cli_app())
+        .subcommand(delete::cli_app())
+        .subcommand(deposit_data::cli_app())
         .subcommand(import::cli_app())
+        .subcommand(import_mnemonic::cli_app())
+        .subcommand(ledger::cli_app())
         .subcommand(list::cli_app())
+        .subcommand(rebuild_definitions::cli_app())
         .subcommand(recover::cli_app())
         .subcommand(slashing_protection::cli_app())
+        .subcommand(verify::cli_app())
         .subcommand(exit::cli_app())
+        .subcommand(export::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -46,14 +67,31 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
     eprintln!("validator-dir path: {:?}", validator_base_dir);
 
     match matches.subcommand() {
+        (bls_to_execution_change::CMD, Some(matches)) => {
+            bls_to_execution_change::cli_run(matches, env)
+        }
+        (change_password::CMD, Some(matches)) => {
+            change_password::cli_run(matches, validator_base_dir)
+        }
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, validator_base_dir),
-        (import::CMD, Some(matches)) => import::cli_run(matches, validator_base_dir),
-        (list::CMD, Some(_)) => list::cli_run(validator_base_dir),
+        (delete::CMD, Some(matches)) => delete::cli_run(matches, env, validator_base_dir),
+        (deposit_data::CMD, Some(matches)) => deposit_data::cli_run(matches, env),
+        (import::CMD, Some(matches)) => import::cli_run(matches, env, validator_base_dir),
+        (import_mnemonic::CMD, Some(matches)) => {
+            import_mnemonic::cli_run(matches, validator_base_dir)
+        }
+        (ledger::CMD, Some(matches)) => ledger::cli_run(matches, env),
+        (list::CMD, Some(matches)) => list::cli_run(matches, validator_base_dir),
+        (rebuild_definitions::CMD, Some(matches)) => {
+            rebuild_definitions::cli_run(matches, validator_base_dir)
+        }
         (recover::CMD, Some(matches)) => recover::cli_run(matches, validator_base_dir),
         (slashing_protection::CMD, Some(matches)) => {
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
+        (verify::CMD, Some(matches)) => verify::cli_run(matches, validator_base_dir),
         (exit::CMD, Some(matches)) => exit::cli_run(matches, env),
+        (export::CMD, Some(matches)) => export::cli_run(matches, env, validator_base_dir),
         (unknown, _) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
@@ -0,0 +1,264 @@
+//! Provides support for discovering keystores in directories which are laid out by other
+//! consensus clients, rather than by the `eth2.0-deposit-cli`.
+
+use super::import::DEPOSIT_DATA_PREFIX;
+use account_utils::{
+    create_with_600_perms,
+    eth2_keystore::{decrypt, json_keystore::Crypto, keypair_from_secret, Error, KeystoreBuilder},
+    validator_definitions::recursively_find_keystores_matching,
+    ZeroizeString,
+};
+use serde_derive::Deserialize;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use types::Keypair;
+use validator_dir::VOTING_KEYSTORE_FILE;
+
+pub const TEKU: &str = "teku";
+pub const PRYSM: &str = "prysm";
+
+/// The names of the clients which may be passed to `client_layout`.
+pub const SUPPORTED_CLIENTS: &[&str] = &[TEKU, PRYSM];
+
+/// A keystore found in the directory of another client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKeystore {
+    pub keystore: PathBuf,
+    /// The file containing the password of `keystore`, if the client stores one.
+    pub password_file: Option<PathBuf>,
+}
+
+/// The conventions used by a client for storing keystores and their passwords.
+pub trait ClientLayout {
+    /// Returns the keystores stored in `dir`, sorted by path.
+    fn find_keystores(&self, dir: &Path) -> Result<Vec<ForeignKeystore>, String>;
+}
+
+/// Returns the layout of the client called `name`.
+///
+/// `wallet_password` is only called for clients which encrypt all keys with a single wallet
+/// password.
+pub fn client_layout<F>(name: &str, wallet_password: F) -> Result<Box<dyn ClientLayout>, String>
+where
+    F: FnOnce() -> Result<ZeroizeString, String>,
+{
+    match name {
+        TEKU => Ok(Box::new(Teku)),
+        PRYSM => Ok(Box::new(Prysm::new(wallet_password()?)?)),
+        other => Err(format!(
+            "Unsupported client {:?}, expected one of {:?}",
+            other, SUPPORTED_CLIENTS
+        )),
+    }
+}
+
+/// The layout expected by the `--validator-keys <KEYS_DIR>:<PASSWORDS_DIR>` option of Teku.
+///
+/// Each keystore in `keys` is a `.json` file and its password is stored in the file with the same
+/// relative path in `passwords`, with a `.txt` extension. If there is no `keys` directory, each
+/// `<name>.json` keystore is expected alongside its `<name>.txt` password in the directory itself,
+/// as when Teku is given the same directory for both.
+pub struct Teku;
+
+impl Teku {
+    pub const KEYS_DIR: &'static str = "keys";
+    pub const PASSWORDS_DIR: &'static str = "passwords";
+}
+
+impl ClientLayout for Teku {
+    fn find_keystores(&self, dir: &Path) -> Result<Vec<ForeignKeystore>, String> {
+        let (keys_dir, passwords_dir) = if dir.join(Self::KEYS_DIR).is_dir() {
+            (dir.join(Self::KEYS_DIR), dir.join(Self::PASSWORDS_DIR))
+        } else {
+            (dir.to_path_buf(), dir.to_path_buf())
+        };
+
+        // The deposit data written by the `eth2.0-deposit-cli` is often kept alongside the
+        // keystores, but is not a keystore.
+        let mut keystores = vec![];
+        recursively_find_keystores_matching(
+            &keys_dir,
+            &|file_name| {
+                file_name.ends_with(".json") && !file_name.starts_with(DEPOSIT_DATA_PREFIX)
+            },
+            &mut keystores,
+            &mut vec![],
+        )
+        .map_err(|e| format!("Unable to search {:?}: {:?}", keys_dir, e))?;
+        keystores.sort();
+
+        keystores
+            .into_iter()
+            .map(|keystore| {
+                let relative_path = keystore
+                    .strip_prefix(&keys_dir)
+                    .map_err(|e| format!("Invalid keystore path {:?}: {:?}", keystore, e))?;
+                let password_file = passwords_dir.join(relative_path).with_extension("txt");
+
+                Ok(ForeignKeystore {
+                    keystore,
+                    password_file: Some(password_file).filter(|path| path.is_file()),
+                })
+            })
+            .collect()
+    }
+}
+
+/// The layout of a Prysm wallet directory, as created by `prysm validator accounts`.
+///
+/// All keys of a wallet are stored in a single accounts keystore, which is decrypted with the
+/// wallet password. Each key is converted into an EIP-2335 keystore, encrypted with the same
+/// password, in a temporary directory which is removed when this is dropped.
+pub struct Prysm {
+    wallet_password: ZeroizeString,
+    converted_dir: TempDir,
+}
+
+/// The contents of a Prysm accounts keystore, of which only the `crypto` is required.
+#[derive(Deserialize)]
+struct PrysmAccountsKeystore {
+    crypto: Crypto,
+}
+
+/// The plain-text of a Prysm accounts keystore, in which each key is base64-encoded.
+#[derive(Deserialize)]
+struct PrysmAccounts {
+    private_keys: Vec<String>,
+    public_keys: Vec<String>,
+}
+
+impl Prysm {
+    /// The accounts keystores of non-HD and HD wallets respectively.
+    pub const ACCOUNTS_KEYSTORES: &'static [&'static str] = &[
+        "direct/accounts/all-accounts.keystore.json",
+        "derived/accounts/all-accounts.keystore.json",
+    ];
+    const PASSWORD_FILE: &'static str = "wallet-password";
+
+    pub fn new(wallet_password: ZeroizeString) -> Result<Self, String> {
+        let converted_dir = tempfile::Builder::new()
+            .prefix("lighthouse-prysm-")
+            .tempdir()
+            .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?;
+
+        Ok(Self {
+            wallet_password,
+            converted_dir,
+        })
+    }
+
+    /// Decrypts the accounts keystore at `path`, returning the keypair of each account.
+    fn decrypt_accounts(&self, path: &Path) -> Result<Vec<Keypair>, String> {
+        let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        let keystore: PrysmAccountsKeystore = serde_json::from_reader(file).map_err(|e| {
+            format!(
+                "Unable to parse Prysm accounts keystore {:?}: {:?}",
+                path, e
+            )
+        })?;
+        let plain_text =
+            decrypt(self.wallet_password.as_ref(), &keystore.crypto).map_err(|e| match e {
+                Error::InvalidPassword => format!("Invalid wallet password for {:?}", path),
+                e => format!("Unable to decrypt {:?}: {:?}", path, e),
+            })?;
+        let accounts: PrysmAccounts = serde_json::from_slice(plain_text.as_bytes())
+            .map_err(|e| format!("Unable to parse the accounts in {:?}: {:?}", path, e))?;
+
+        if accounts.private_keys.len() != accounts.public_keys.len() {
+            return Err(format!(
+                "{:?} has {} private keys but {} public keys",
+                path,
+                accounts.private_keys.len(),
+                accounts.public_keys.len()
+            ));
+        }
+
+        accounts
+            .private_keys
+            .iter()
+            .zip(&accounts.public_keys)
+            .map(|(secret, pubkey)| {
+                let secret = base64::decode(secret)
+                    .map_err(|e| format!("Invalid private key in {:?}: {:?}", path, e))?;
+                let pubkey = base64::decode(pubkey)
+                    .map_err(|e| format!("Invalid public key in {:?}: {:?}", path, e))?;
+                let keypair = keypair_from_secret(&secret)
+                    .map_err(|e| format!("Invalid private key in {:?}: {:?}", path, e))?;
+                if keypair.pk.serialize()[..] != pubkey[..] {
+                    return Err(format!(
+                        "Private key for 0x{} in {:?} does not match its public key",
+                        hex::encode(&pubkey),
+                        path
+                    ));
+                }
+                Ok(keypair)
+            })
+            .collect()
+    }
+}
+
+impl ClientLayout for Prysm {
+    fn find_keystores(&self, dir: &Path) -> Result<Vec<ForeignKeystore>, String> {
+        let accounts_keystores = Self::ACCOUNTS_KEYSTORES
+            .iter()
+            .map(|path| dir.join(path))
+            .filter(|path| path.is_file())
+            .collect::<Vec<_>>();
+        if accounts_keystores.is_empty() {
+            return Err(format!(
+                "No Prysm wallet found in {:?}, expected one of {:?}",
+                dir,
+                Self::ACCOUNTS_KEYSTORES
+            ));
+        }
+
+        // Each converted keystore is unlocked with the wallet password, so that it does not need
+        // to be entered again for every keystore.
+        let password_file = self.converted_dir.path().join(Self::PASSWORD_FILE);
+        create_with_600_perms(&password_file, self.wallet_password.as_ref())
+            .map_err(|e| format!("Unable to write {:?}: {:?}", password_file, e))?;
+
+        let mut keystores = vec![];
+        for accounts_keystore in &accounts_keystores {
+            for keypair in self.decrypt_accounts(accounts_keystore)? {
+                let keystore =
+                    KeystoreBuilder::new(&keypair, self.wallet_password.as_ref(), "".into())
+                        .and_then(|builder| builder.build())
+                        .map_err(|e| format!("Unable to build keystore: {:?}", e))?;
+
+                let keystore_dir = self
+                    .converted_dir
+                    .path()
+                    .join(format!("0x{}", keystore.pubkey()));
+                fs::create_dir_all(&keystore_dir).map_err(|e| {
+                    format!("Unable to create directory {:?}: {:?}", keystore_dir, e)
+                })?;
+                let keystore_path = keystore_dir.join(VOTING_KEYSTORE_FILE);
+                let file = File::create(&keystore_path)
+                    .map_err(|e| format!("Unable to create {:?}: {:?}", keystore_path, e))?;
+                keystore
+                    .to_json_writer(file)
+                    .map_err(|e| format!("Unable to write {:?}: {:?}", keystore_path, e))?;
+
+                keystores.push(ForeignKeystore {
+                    keystore: keystore_path,
+                    password_file: Some(password_file.clone()),
+                });
+            }
+        }
+        keystores.sort_by(|a, b| a.keystore.cmp(&b.keystore));
+
+        Ok(keystores)
+    }
+}
+
+impl Drop for Prysm {
+    fn drop(&mut self) {
+        // The converted keystores are encrypted, but the wallet password is not.
+        let password_file = self.converted_dir.path().join(Self::PASSWORD_FILE);
+        if let Ok(metadata) = fs::metadata(&password_file) {
+            let _ = fs::write(&password_file, vec![0; metadata.len() as usize]);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use super::import::STAGING_DIR_NAME;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use crate::SECRETS_DIR_FLAG;
+use account_utils::{
+    default_keystore_password_path,
+    eth2_keystore::Keystore,
+    read_password, read_password_from_user,
+    validator_definitions::{
+        recursively_find_voting_keystores, DefinitionsFormat, ValidatorDefinition,
+        ValidatorDefinitions, CONFIG_FILENAME,
+    },
+    ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use directory::{parse_path_or_default_with_flag, DEFAULT_SECRET_DIR};
+use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "rebuild-definitions";
+
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password, or press enter to omit it:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Re-creates a lost or corrupted validator_definitions.yml from the keystores in the \
+            validator directory. Each keystore is enabled, and its password is either read from \
+            the secrets directory or prompted for.",
+        )
+        .arg(
+            Arg::with_name(SECRETS_DIR_FLAG)
+                .long(SECRETS_DIR_FLAG)
+                .value_name("SECRETS_DIR")
+                .help(
+                    "The path to search for keystore password files, which are named after the \
+                    public key of the keystore. Keystores without a password file are prompted \
+                    for. Defaults to ~/.lighthouse/{network}/secrets",
+                )
+                .conflicts_with("datadir")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let secrets_dir = if matches.value_of("datadir").is_some() {
+        let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
+        path.join(DEFAULT_SECRET_DIR)
+    } else {
+        parse_path_or_default_with_flag(matches, SECRETS_DIR_FLAG, DEFAULT_SECRET_DIR)?
+    };
+
+    // A readable definitions file is never replaced, since it may contain settings (e.g.,
+    // graffiti or disabled validators) which cannot be recovered from the keystores.
+    let format = DefinitionsFormat::detect(&validator_dir)
+        .map_err(|e| format!("Unable to find {}: {:?}", CONFIG_FILENAME, e))?;
+    if let Some(format) = format {
+        let defs_path = validator_dir.join(format.filename());
+        if ValidatorDefinitions::open(&validator_dir).is_ok() {
+            return Err(format!(
+                "{:?} is readable and will not be rebuilt. Remove it first to rebuild it",
+                defs_path
+            ));
+        }
+
+        let backup_path = validator_dir.join(format!("{}.bak", format.filename()));
+        fs::rename(&defs_path, &backup_path).map_err(|e| {
+            format!(
+                "Unable to move {:?} to {:?}: {:?}",
+                defs_path, backup_path, e
+            )
+        })?;
+        eprintln!(
+            "Moved the unreadable {} to {:?}.",
+            format.filename(),
+            backup_path
+        );
+    }
+
+    let mut keystore_paths = vec![];
+    recursively_find_voting_keystores(&validator_dir, &mut keystore_paths)
+        .map_err(|e| format!("Unable to search {:?}: {:?}", validator_dir, e))?;
+    // Keystores left behind by an interrupted `--all-or-nothing` import were never imported.
+    let staging_dir = validator_dir.join(STAGING_DIR_NAME);
+    keystore_paths.retain(|path| !path.starts_with(&staging_dir));
+    keystore_paths.sort();
+
+    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let slashing_protection =
+        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
+            format!(
+                "Unable to open or create slashing protection database at {}: {:?}",
+                slashing_protection_path.display(),
+                e
+            )
+        })?;
+
+    // The rebuilt file keeps the format of the file it replaces.
+    let mut defs = ValidatorDefinitions::default();
+    defs.set_format(format.unwrap_or_default());
+    for keystore_path in &keystore_paths {
+        let def = rebuild_definition(keystore_path, &secrets_dir, stdin_inputs)?;
+
+        // The validator client refuses to sign with a validator which is not registered, so any
+        // validator missing from the database is registered, as it would have been by import.
+        if slashing_protection
+            .get_validator_id(&def.voting_public_key)
+            .is_err()
+        {
+            eprintln!(
+                "WARNING: {} was not registered with slashing protection, registering it now.",
+                def.voting_public_key.to_hex_string()
+            );
+            slashing_protection
+                .register_validator(&def.voting_public_key)
+                .map_err(|e| {
+                    format!(
+                        "Error registering validator {}: {:?}",
+                        def.voting_public_key.to_hex_string(),
+                        e
+                    )
+                })?;
+        }
+
+        defs.push(def);
+    }
+
+    // The database may know of validators whose keystores have also been lost.
+    let num_registered = slashing_protection
+        .num_validator_rows()
+        .map_err(|e| format!("Unable to read slashing protection database: {:?}", e))?
+        as usize;
+    if num_registered > keystore_paths.len() {
+        eprintln!(
+            "WARNING: {} validators are registered with slashing protection, but only {} \
+             keystores were found.",
+            num_registered,
+            keystore_paths.len()
+        );
+    }
+
+    defs.save(&validator_dir)
+        .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+
+    eprintln!(
+        "Successfully rebuilt {} with {} validators.",
+        CONFIG_FILENAME,
+        keystore_paths.len()
+    );
+
+    Ok(())
+}
+
+/// Returns a definition for the keystore at `keystore_path`.
+///
+/// The password is read from `secrets_dir` if it contains a password file for the keystore,
+/// otherwise the user is prompted for it. Any password is checked against the keystore.
+fn rebuild_definition(
+    keystore_path: &Path,
+    secrets_dir: &Path,
+    stdin_inputs: bool,
+) -> Result<ValidatorDefinition, String> {
+    let keystore = Keystore::from_json_file(keystore_path)
+        .map_err(|e| format!("Unable to read keystore JSON {:?}: {:?}", keystore_path, e))?;
+
+    // A keystore in a `0x` directory must be the keystore of that public key, otherwise the
+    // directory has been tampered with.
+    let dir_name = keystore_path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if dir_name.starts_with("0x") && dir_name != format!("0x{}", keystore.pubkey()) {
+        return Err(format!(
+            "Keystore {:?} has public key 0x{}, which does not match its directory",
+            keystore_path,
+            keystore.pubkey()
+        ));
+    }
+
+    let password_path = default_keystore_password_path(&keystore, secrets_dir);
+    if password_path.exists() {
+        let password = read_password(&password_path)
+            .map_err(|e| format!("Unable to read password file {:?}: {:?}", password_path, e))?;
+        keystore.decrypt_keypair(password.as_bytes()).map_err(|e| {
+            format!(
+                "Password in {:?} does not decrypt keystore {:?}: {:?}",
+                password_path, keystore_path, e
+            )
+        })?;
+
+        eprintln!(
+            "Read password for {:?} from {:?}.",
+            keystore_path, password_path
+        );
+        return ValidatorDefinition::new_keystore_with_password_path(keystore_path, password_path)
+            .map_err(|e| format!("Unable to create new validator definition: {:?}", e));
+    }
+
+    eprintln!("");
+    eprintln!("Keystore found at {:?}:", keystore_path);
+    eprintln!("");
+    eprintln!(" - Public key: 0x{}", keystore.pubkey());
+    eprintln!(" - UUID: {}", keystore.uuid());
+    eprintln!("");
+    eprintln!(
+        "If you enter the password it will be stored as plain-text in {} so that it is not \
+         required each time the validator client starts.",
+        CONFIG_FILENAME
+    );
+
+    let password_opt = loop {
+        eprintln!("");
+        eprintln!("{}", PASSWORD_PROMPT);
+        let password: ZeroizeString = read_password_from_user(stdin_inputs)?;
+
+        if password.as_ref().is_empty() {
+            eprintln!("Continuing without password.");
+            break None;
+        }
+
+        match keystore.decrypt_keypair(password.as_ref()) {
+            Ok(_) => {
+                eprintln!("Password is correct.");
+                break Some(password);
+            }
+            Err(eth2_keystore::Error::InvalidPassword) if stdin_inputs => {
+                return Err(format!("Invalid password for keystore {:?}", keystore_path));
+            }
+            Err(eth2_keystore::Error::InvalidPassword) => {
+                eprintln!("Invalid password");
+            }
+            Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+        }
+    };
+
+    ValidatorDefinition::new_keystore_with_password(keystore_path, password_opt)
+        .map_err(|e| format!("Unable to create new validator definition: {:?}", e))
+}
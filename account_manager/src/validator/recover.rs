@@ -87,6 +87,11 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
     let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
     let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
     let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    // The range is checked before the mnemonic is read, so that an invalid range is reported
+    // before the user enters their mnemonic.
+    let end_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} plus --{} is too large", FIRST_INDEX_FLAG, COUNT_FLAG))?;
 
     eprintln!("secrets-dir path: {:?}", secrets_dir);
 
@@ -101,7 +106,7 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
     let seed = Seed::new(&mnemonic, "");
 
-    for index in first_index..first_index + count {
+    for index in first_index..end_index {
         let voting_password = random_password();
         let withdrawal_password = random_password();
 
@@ -136,8 +141,8 @@ pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), Strin
 
         println!(
             "{}/{}\tIndex: {}\t0x{}",
-            index - first_index,
-            count - first_index,
+            index - first_index + 1,
+            count,
             index,
             voting_pubkey
         );
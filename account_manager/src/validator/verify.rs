@@ -0,0 +1,326 @@
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    default_keystore_password_path,
+    eth2_keystore::Keystore,
+    read_password, read_password_from_user,
+    validator_definitions::{
+        recursively_find_voting_keystores, SigningDefinition, ValidatorDefinition,
+        ValidatorDefinitions, CONFIG_FILENAME,
+    },
+};
+use clap::{App, Arg, ArgMatches};
+use serde_derive::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const CMD: &str = "verify";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const DIR_FLAG: &str = "directory";
+pub const PASSWORD_DIR_FLAG: &str = "password-dir";
+pub const FORMAT_FLAG: &str = "format";
+
+pub const PASSWORD_PROMPT: &str = "Enter the keystore password:";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Checks that one or more EIP-2335 keystores can be decrypted, without importing them \
+            or modifying any files. If neither --keystore nor --directory is present, each \
+            validator in validator_definitions.yml is checked instead: its keystore must have \
+            the public key of its definition and of the directory containing it, and must be \
+            decrypted by its stored password, if any. This may be used to check the validator \
+            directory before starting the validator client.",
+        )
+        .arg(
+            Arg::with_name(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("Path to a single keystore to be verified.")
+                .conflicts_with(DIR_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DIR_FLAG)
+                .long(DIR_FLAG)
+                .value_name("KEYSTORES_DIRECTORY")
+                .help(
+                    "Path to a directory which contains zero or more keystores to be verified. \
+                    This directory and all sub-directories are searched in the same way as the \
+                    import command.",
+                )
+                .conflicts_with(KEYSTORE_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(PASSWORD_DIR_FLAG)
+                .long(PASSWORD_DIR_FLAG)
+                .value_name("PASSWORD_DIRECTORY")
+                .help(
+                    "If present, the password for each keystore is read from the file in this \
+                    directory named after its public key (e.g., 0x1234...), rather than being \
+                    prompted for. Only used with --keystore or --directory.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FORMAT_FLAG)
+                .long(FORMAT_FLAG)
+                .value_name("FORMAT")
+                .help("The format in which to print the results.")
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+/// The outcome of verifying a single keystore.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerifyResult {
+    pub path: PathBuf,
+    /// `None` if the keystore could not be read.
+    pub pubkey: Option<String>,
+    pub valid: bool,
+    /// The reason that the keystore could not be verified, if it is not valid.
+    pub error: Option<String>,
+    /// A check which could not be performed on a valid keystore, e.g. because the validator has
+    /// no stored password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+impl VerifyResult {
+    fn new(path: PathBuf, outcome: Result<Verified, (Option<String>, String)>) -> Self {
+        match outcome {
+            Ok(verified) => VerifyResult {
+                path,
+                pubkey: Some(verified.pubkey),
+                valid: true,
+                error: None,
+                warning: verified.warning,
+            },
+            Err((pubkey, e)) => VerifyResult {
+                path,
+                pubkey,
+                valid: false,
+                error: Some(e),
+                warning: None,
+            },
+        }
+    }
+}
+
+/// The public key of a keystore which has been verified, along with any check that was skipped.
+struct Verified {
+    pubkey: String,
+    warning: Option<String>,
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let keystore: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORE_FLAG)?;
+    let keystores_dir: Option<PathBuf> = clap_utils::parse_optional(matches, DIR_FLAG)?;
+    let password_dir: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_DIR_FLAG)?;
+    let format: String = clap_utils::parse_required(matches, FORMAT_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let results = match (keystore, keystores_dir) {
+        (Some(keystore), None) => {
+            verify_keystores(vec![keystore], password_dir.as_deref(), stdin_inputs)
+        }
+        (None, Some(keystores_dir)) => {
+            let mut keystores = vec![];
+            recursively_find_voting_keystores(&keystores_dir, &mut keystores)
+                .map_err(|e| format!("Unable to search {:?}: {:?}", keystores_dir, e))?;
+            verify_keystores(keystores, password_dir.as_deref(), stdin_inputs)
+        }
+        _ => verify_validator_dir(&validator_dir)?,
+    };
+
+    if format == "json" {
+        let json = serde_json::to_string_pretty(&results)
+            .map_err(|e| format!("Unable to encode results as JSON: {:?}", e))?;
+        println!("{}", json);
+    } else {
+        for result in &results {
+            match (&result.error, &result.warning) {
+                (None, None) => println!("OK\t{:?}", result.path),
+                (None, Some(warning)) => println!("OK\t{:?}\t{}", result.path, warning),
+                (Some(e), _) => println!("FAILED\t{:?}\t{}", result.path, e),
+            }
+        }
+    }
+
+    let num_failed = results.iter().filter(|result| !result.valid).count();
+    if num_failed > 0 {
+        return Err(format!(
+            "{} of {} keystores could not be verified",
+            num_failed,
+            results.len()
+        ));
+    }
+
+    eprintln!("Successfully verified {} keystores.", results.len());
+
+    Ok(())
+}
+
+/// Verifies each keystore in `keystore_paths`, in order of their paths.
+fn verify_keystores(
+    mut keystore_paths: Vec<PathBuf>,
+    password_dir: Option<&Path>,
+    stdin_inputs: bool,
+) -> Vec<VerifyResult> {
+    keystore_paths.sort();
+    keystore_paths
+        .into_iter()
+        .map(|path| {
+            let outcome = verify_keystore(&path, password_dir, stdin_inputs);
+            VerifyResult::new(path, outcome)
+        })
+        .collect()
+}
+
+/// Reads the keystore at `path` and checks that it decrypts to its public key.
+///
+/// If the keystore cannot be verified, the public key is also returned alongside the error, if
+/// the keystore could be read.
+fn verify_keystore(
+    path: &Path,
+    password_dir: Option<&Path>,
+    stdin_inputs: bool,
+) -> Result<Verified, (Option<String>, String)> {
+    let keystore = Keystore::from_json_file(path)
+        .map_err(|e| (None, format!("Unable to read keystore JSON: {:?}", e)))?;
+    let pubkey = format!("0x{}", keystore.pubkey());
+    let fail = |e: String| (Some(pubkey.clone()), e);
+
+    let keypair = if let Some(password_dir) = password_dir {
+        let password_path = default_keystore_password_path(&keystore, password_dir);
+        let password = read_password(&password_path).map_err(|e| {
+            fail(format!(
+                "Unable to read password file {:?}: {:?}",
+                password_path, e
+            ))
+        })?;
+        keystore.decrypt_keypair(password.as_bytes())
+    } else {
+        eprintln!("");
+        eprintln!("Keystore found at {:?}:", path);
+        eprintln!(" - Public key: {}", pubkey);
+        eprintln!("");
+        eprintln!("{}", PASSWORD_PROMPT);
+        let password = read_password_from_user(stdin_inputs).map_err(fail)?;
+        keystore.decrypt_keypair(password.as_ref())
+    }
+    .map_err(|e| fail(format!("Unable to decrypt keystore: {:?}", e)))?;
+
+    if Some(keypair.pk) != keystore.public_key() {
+        return Err(fail(
+            "Keystore does not decrypt to its public key".to_string(),
+        ));
+    }
+
+    Ok(Verified {
+        pubkey,
+        warning: None,
+    })
+}
+
+/// Verifies the keystore of each validator in the definitions file of `validator_dir`.
+fn verify_validator_dir(validator_dir: &Path) -> Result<Vec<VerifyResult>, String> {
+    let defs = ValidatorDefinitions::open(validator_dir).map_err(|e| {
+        format!(
+            "Unable to open {} in {:?}: {:?}",
+            CONFIG_FILENAME, validator_dir, e
+        )
+    })?;
+
+    Ok(defs
+        .as_slice()
+        .iter()
+        .map(|def| {
+            let voting_keystore_path = match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } => voting_keystore_path.clone(),
+            };
+            VerifyResult::new(voting_keystore_path, verify_validator(def))
+        })
+        .collect())
+}
+
+/// Reads the keystore of the validator defined by `def` and checks that it has the public key of
+/// the definition and of the directory containing it, and that it decrypts with the stored
+/// password, if any. Decrypting the keystore also verifies its checksum.
+///
+/// If the keystore cannot be verified, the public key is also returned alongside the error, if
+/// the keystore could be read.
+fn verify_validator(def: &ValidatorDefinition) -> Result<Verified, (Option<String>, String)> {
+    let voting_keystore_path = match &def.signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => voting_keystore_path,
+    };
+    let keystore = Keystore::from_json_file(voting_keystore_path)
+        .map_err(|e| (None, format!("Unable to read keystore JSON: {:?}", e)))?;
+    let pubkey = format!("0x{}", keystore.pubkey());
+    let fail = |e: String| (Some(pubkey.clone()), e);
+
+    if keystore.public_key().as_ref() != Some(&def.voting_public_key) {
+        return Err(fail(format!(
+            "Keystore does not match the public key {} of its definition",
+            def.voting_public_key
+        )));
+    }
+
+    // Directories which are not named after a public key, e.g. those created by
+    // `import --dir-naming`, cannot be checked.
+    let dir_name = voting_keystore_path
+        .parent()
+        .and_then(Path::file_name)
+        .and_then(|dir_name| dir_name.to_str())
+        .filter(|dir_name| dir_name.starts_with("0x"));
+    if let Some(dir_name) = dir_name {
+        if !dir_name.eq_ignore_ascii_case(&pubkey) {
+            return Err(fail(format!(
+                "Keystore is in the directory of public key {}",
+                dir_name
+            )));
+        }
+    }
+
+    let password = def
+        .signing_definition
+        .voting_keystore_password()
+        .map_err(|e| fail(format!("Unable to read stored password: {:?}", e)))?;
+    let password = match password {
+        Some(password) => password,
+        None => {
+            return Ok(Verified {
+                pubkey,
+                warning: Some(
+                    "No password is stored, so the keystore could not be decrypted".to_string(),
+                ),
+            })
+        }
+    };
+
+    let keypair = keystore
+        .decrypt_keypair(password.as_bytes())
+        .map_err(|e| fail(format!("Unable to decrypt keystore: {:?}", e)))?;
+    if Some(keypair.pk) != keystore.public_key() {
+        return Err(fail(
+            "Keystore does not decrypt to its public key".to_string(),
+        ));
+    }
+
+    Ok(Verified {
+        pubkey,
+        warning: None,
+    })
+}
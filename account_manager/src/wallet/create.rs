@@ -24,6 +24,7 @@ pub const TYPE_FLAG: &str = "type";
 pub const MNEMONIC_FLAG: &str = "mnemonic-output-path";
 pub const STDIN_INPUTS_FLAG: &str = "stdin-inputs";
 pub const MNEMONIC_LENGTH_FLAG: &str = "mnemonic-length";
+pub const FIRST_INDEX_FLAG: &str = "first-index";
 pub const MNEMONIC_TYPES: &[MnemonicType] = &[
     MnemonicType::Words12,
     MnemonicType::Words15,
@@ -100,10 +101,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 })
                 .default_value("24"),
         )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help(
+                    "The index of the first validator which will be created from the wallet. \
+                    Defaults to 0.",
+                )
+                .takes_value(true),
+        )
 }
 
 pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), String> {
     let mnemonic_output_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let first_index: Option<u32> = clap_utils::parse_optional(matches, FIRST_INDEX_FLAG)?;
 
     // Create a new random mnemonic.
     //
@@ -114,7 +126,13 @@ pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), Str
         Language::English,
     );
 
-    let wallet = create_wallet_from_mnemonic(matches, &wallet_base_dir.as_path(), &mnemonic)?;
+    let mut wallet = create_wallet_from_mnemonic(matches, &wallet_base_dir.as_path(), &mnemonic)?;
+
+    if let Some(first_index) = first_index {
+        wallet
+            .set_nextaccount(first_index)
+            .map_err(|e| format!("Unable to set --{}: {:?}", FIRST_INDEX_FLAG, e))?;
+    }
 
     if let Some(path) = mnemonic_output_path {
         create_with_600_perms(&path, mnemonic.phrase().as_bytes())
@@ -160,6 +160,35 @@ pub fn is_password_sufficiently_complex(password: &[u8]) -> Result<(), String> {
     }
 }
 
+/// Estimates the entropy of `password` in bits, assuming each character is chosen uniformly from
+/// the union of the character classes (lowercase, uppercase, digits and symbols) that it uses.
+///
+/// This is a coarse heuristic which overestimates the strength of dictionary words and patterns,
+/// so it should only be used to reject passwords which are obviously weak.
+pub fn estimate_password_entropy(password: &str) -> f64 {
+    let (mut lower, mut upper, mut digit, mut symbol) = (false, false, false, false);
+    for c in password.chars() {
+        match c {
+            'a'..='z' => lower = true,
+            'A'..='Z' => upper = true,
+            '0'..='9' => digit = true,
+            _ => symbol = true,
+        }
+    }
+
+    let pool_size = [(lower, 26), (upper, 26), (digit, 10), (symbol, 33)]
+        .iter()
+        .filter(|(present, _)| *present)
+        .map(|(_, size)| size)
+        .sum::<u32>();
+
+    if pool_size == 0 {
+        0.0
+    } else {
+        password.chars().count() as f64 * f64::from(pool_size).log2()
+    }
+}
+
 /// Returns a random 24-word english mnemonic.
 pub fn random_mnemonic() -> Mnemonic {
     Mnemonic::new(MnemonicType::Words24, Language::English)
@@ -250,6 +279,26 @@ mod test {
         is_password_sufficiently_complex(b"TestPass").unwrap();
     }
 
+    #[test]
+    fn password_entropy() {
+        let assert_entropy = |password: &str, expected: f64| {
+            let entropy = estimate_password_entropy(password);
+            assert!(
+                (entropy - expected).abs() < f64::EPSILON,
+                "{:?} should have {} bits of entropy, not {}",
+                password,
+                expected,
+                entropy
+            );
+        };
+
+        assert_entropy("", 0.0);
+        assert_entropy("aaaa", 4.0 * 26_f64.log2());
+        assert_entropy("aA1!", 4.0 * 95_f64.log2());
+        assert!(estimate_password_entropy("cats") < estimate_password_entropy("cats🐱"));
+        assert!(estimate_password_entropy("cats") < estimate_password_entropy("Cats"));
+    }
+
     #[test]
     fn unicode_characters() {
         assert_eq!(count_unicode_characters(b""), 0);
@@ -3,7 +3,9 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
-use crate::{create_with_600_perms, default_keystore_password_path, ZeroizeString};
+use crate::{
+    create_with_600_perms, default_keystore_password_path, read_password, PlainText, ZeroizeString,
+};
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
 use regex::Regex;
@@ -11,13 +13,16 @@ use serde_derive::{Deserialize, Serialize};
 use slog::{error, Logger};
 use std::collections::HashSet;
 use std::fs::{self, OpenOptions};
-use std::io;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use types::PublicKey;
+use std::str::FromStr;
+use types::{GraffitiString, PublicKey};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
 /// The file name for the serialized `ValidatorDefinitions` struct.
 pub const CONFIG_FILENAME: &str = "validator_definitions.yml";
+/// The file name for the serialized `ValidatorDefinitions` struct, when it is saved as TOML.
+pub const TOML_CONFIG_FILENAME: &str = "validator_definitions.toml";
 
 #[derive(Debug)]
 pub enum Error {
@@ -25,10 +30,16 @@ pub enum Error {
     UnableToOpenFile(io::Error),
     /// The config file could not be parsed as YAML.
     UnableToParseFile(serde_yaml::Error),
+    /// The config file could not be parsed as TOML.
+    UnableToParseTomlFile(toml::de::Error),
+    /// Both a YAML and a TOML config file exist, so it is unclear which should be used.
+    MultipleConfigFiles,
     /// There was an error whilst performing the recursive keystore search function.
     UnableToSearchForKeystores(io::Error),
     /// The config file could not be serialized as YAML.
     UnableToEncodeFile(serde_yaml::Error),
+    /// The config file could not be serialized as TOML.
+    UnableToEncodeTomlFile(toml::ser::Error),
     /// The config file could not be written to the filesystem.
     UnableToWriteFile(io::Error),
     /// The public key from the keystore is invalid.
@@ -57,6 +68,26 @@ pub enum SigningDefinition {
     },
 }
 
+impl SigningDefinition {
+    /// Returns the password for the voting keystore, reading it from
+    /// `voting_keystore_password_path` if it is not stored in the definition itself.
+    ///
+    /// Returns `Ok(None)` if no password is available, in which case the user must be prompted.
+    pub fn voting_keystore_password(&self) -> Result<Option<PlainText>, io::Error> {
+        match self {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password: Some(password),
+                ..
+            } => Ok(Some(password.as_ref().to_vec().into())),
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_path: Some(path),
+                ..
+            } => read_password(path).map(Some),
+            SigningDefinition::LocalKeystore { .. } => Ok(None),
+        }
+    }
+}
+
 /// A validator that may be initialized by this validator client.
 ///
 /// Presently there is only a single variant, however we expect more variants to arise (e.g.,
@@ -67,6 +98,9 @@ pub struct ValidatorDefinition {
     pub voting_public_key: PublicKey,
     #[serde(default)]
     pub description: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub graffiti: Option<GraffitiString>,
     #[serde(flatten)]
     pub signing_definition: SigningDefinition,
 }
@@ -81,6 +115,34 @@ impl ValidatorDefinition {
     pub fn new_keystore_with_password<P: AsRef<Path>>(
         voting_keystore_path: P,
         voting_keystore_password: Option<ZeroizeString>,
+    ) -> Result<Self, Error> {
+        Self::new_keystore(voting_keystore_path, None, voting_keystore_password)
+    }
+
+    /// Create a new definition for a voting keystore at the given `voting_keystore_path` that can
+    /// be unlocked with the password in the file at `voting_keystore_password_path`.
+    ///
+    /// This allows many definitions to share a single password file, rather than each storing a
+    /// copy of the password.
+    ///
+    /// ## Notes
+    ///
+    /// This function does not check the password against the keystore.
+    pub fn new_keystore_with_password_path<P: AsRef<Path>>(
+        voting_keystore_path: P,
+        voting_keystore_password_path: PathBuf,
+    ) -> Result<Self, Error> {
+        Self::new_keystore(
+            voting_keystore_path,
+            Some(voting_keystore_password_path),
+            None,
+        )
+    }
+
+    fn new_keystore<P: AsRef<Path>>(
+        voting_keystore_path: P,
+        voting_keystore_password_path: Option<PathBuf>,
+        voting_keystore_password: Option<ZeroizeString>,
     ) -> Result<Self, Error> {
         let voting_keystore_path = voting_keystore_path.as_ref().into();
         let keystore =
@@ -91,19 +153,86 @@ impl ValidatorDefinition {
             enabled: true,
             voting_public_key,
             description: keystore.description().unwrap_or("").to_string(),
+            graffiti: None,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
-                voting_keystore_password_path: None,
+                voting_keystore_password_path,
                 voting_keystore_password,
             },
         })
     }
 }
 
+/// The formats in which `ValidatorDefinitions` may be saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefinitionsFormat {
+    /// Saved in the `CONFIG_FILENAME` file.
+    Yaml,
+    /// Saved in the `TOML_CONFIG_FILENAME` file.
+    Toml,
+}
+
+impl DefinitionsFormat {
+    /// Returns the name of the file in which definitions of this format are saved.
+    pub fn filename(self) -> &'static str {
+        match self {
+            DefinitionsFormat::Yaml => CONFIG_FILENAME,
+            DefinitionsFormat::Toml => TOML_CONFIG_FILENAME,
+        }
+    }
+
+    /// Returns the format of the definitions file in `validators_dir`, or `None` if there is no
+    /// definitions file.
+    pub fn detect<P: AsRef<Path>>(validators_dir: P) -> Result<Option<Self>, Error> {
+        let yaml_exists = validators_dir.as_ref().join(CONFIG_FILENAME).exists();
+        let toml_exists = validators_dir.as_ref().join(TOML_CONFIG_FILENAME).exists();
+        match (yaml_exists, toml_exists) {
+            (true, true) => Err(Error::MultipleConfigFiles),
+            (true, false) => Ok(Some(DefinitionsFormat::Yaml)),
+            (false, true) => Ok(Some(DefinitionsFormat::Toml)),
+            (false, false) => Ok(None),
+        }
+    }
+}
+
+impl Default for DefinitionsFormat {
+    fn default() -> Self {
+        DefinitionsFormat::Yaml
+    }
+}
+
+impl FromStr for DefinitionsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "yaml" => Ok(DefinitionsFormat::Yaml),
+            "toml" => Ok(DefinitionsFormat::Toml),
+            other => Err(format!(
+                "Unknown definitions format {:?}, expected \"yaml\" or \"toml\"",
+                other
+            )),
+        }
+    }
+}
+
+/// The layout of a TOML definitions file, since a TOML document must be a table rather than a
+/// list.
+#[derive(Serialize, Deserialize)]
+struct TomlDefinitions {
+    validators: Vec<ValidatorDefinition>,
+}
+
 /// A list of `ValidatorDefinition` that serves as a serde-able configuration file which defines a
 /// list of validators to be initialized by this validator client.
 #[derive(Default, Serialize, Deserialize)]
-pub struct ValidatorDefinitions(Vec<ValidatorDefinition>);
+#[serde(transparent)]
+pub struct ValidatorDefinitions {
+    definitions: Vec<ValidatorDefinition>,
+    /// The format of the file that `self` was read from, which is used when it is saved.
+    #[serde(skip)]
+    format: DefinitionsFormat,
+}
 
 impl ValidatorDefinitions {
     /// Open an existing file or create a new, empty one if it does not exist.
@@ -111,8 +240,7 @@ impl ValidatorDefinitions {
         ensure_dir_exists(validators_dir.as_ref()).map_err(|_| {
             Error::UnableToCreateValidatorDir(PathBuf::from(validators_dir.as_ref()))
         })?;
-        let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
-        if !config_path.exists() {
+        if DefinitionsFormat::detect(&validators_dir)?.is_none() {
             let this = Self::default();
             this.save(&validators_dir)?;
         }
@@ -120,15 +248,36 @@ impl ValidatorDefinitions {
     }
 
     /// Open an existing file, returning an error if the file does not exist.
+    ///
+    /// The file may be either the YAML `CONFIG_FILENAME` or the TOML `TOML_CONFIG_FILENAME`, and
+    /// is saved in the same format it was read in unless `Self::set_format` is called.
     pub fn open<P: AsRef<Path>>(validators_dir: P) -> Result<Self, Error> {
-        let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
-        let file = OpenOptions::new()
+        let format = DefinitionsFormat::detect(&validators_dir)?.unwrap_or_default();
+        let config_path = validators_dir.as_ref().join(format.filename());
+        let mut file = OpenOptions::new()
             .write(true)
             .read(true)
             .create_new(false)
             .open(&config_path)
             .map_err(Error::UnableToOpenFile)?;
-        serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)
+            .map_err(Error::UnableToOpenFile)?;
+
+        let definitions = match format {
+            DefinitionsFormat::Yaml => {
+                serde_yaml::from_str(&contents).map_err(Error::UnableToParseFile)?
+            }
+            DefinitionsFormat::Toml => {
+                toml::from_str::<TomlDefinitions>(&contents)
+                    .map_err(Error::UnableToParseTomlFile)?
+                    .validators
+            }
+        };
+        Ok(Self {
+            definitions,
+            format,
+        })
     }
 
     /// Perform a recursive, exhaustive search through `validators_dir` and add any keystores
@@ -154,7 +303,7 @@ impl ValidatorDefinitions {
             .map_err(Error::UnableToSearchForKeystores)?;
 
         let known_paths: HashSet<&PathBuf> = self
-            .0
+            .definitions
             .iter()
             .map(|def| match &def.signing_definition {
                 SigningDefinition::LocalKeystore {
@@ -215,6 +364,7 @@ impl ValidatorDefinitions {
                     enabled: true,
                     voting_public_key,
                     description: keystore.description().unwrap_or("").to_string(),
+                    graffiti: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
@@ -226,39 +376,78 @@ impl ValidatorDefinitions {
 
         let new_defs_count = new_defs.len();
 
-        self.0.append(&mut new_defs);
+        self.definitions.append(&mut new_defs);
 
         Ok(new_defs_count)
     }
 
-    /// Encodes `self` as a YAML string it writes it to the `CONFIG_FILENAME` file in the
-    /// `validators_dir` directory.
+    /// Encodes `self` as a YAML or TOML string (according to `Self::format`) and writes it to the
+    /// `CONFIG_FILENAME` or `TOML_CONFIG_FILENAME` file in the `validators_dir` directory.
     ///
-    /// Will create a new file if it does not exist or over-write any existing file.
+    /// Will create a new file if it does not exist or over-write any existing file. The file of
+    /// the other format is removed, so that the validator client only ever finds one of them.
     pub fn save<P: AsRef<Path>>(&self, validators_dir: P) -> Result<(), Error> {
-        let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
-        let bytes = serde_yaml::to_vec(self).map_err(Error::UnableToEncodeFile)?;
+        let config_path = validators_dir.as_ref().join(self.format.filename());
+        let bytes = match self.format {
+            DefinitionsFormat::Yaml => {
+                serde_yaml::to_vec(self).map_err(Error::UnableToEncodeFile)?
+            }
+            DefinitionsFormat::Toml => toml::to_vec(&TomlDefinitions {
+                validators: self.definitions.clone(),
+            })
+            .map_err(Error::UnableToEncodeTomlFile)?,
+        };
 
         if config_path.exists() {
-            fs::write(config_path, &bytes).map_err(Error::UnableToWriteFile)
+            fs::write(config_path, &bytes).map_err(Error::UnableToWriteFile)?;
         } else {
-            create_with_600_perms(&config_path, &bytes).map_err(Error::UnableToWriteFile)
+            create_with_600_perms(&config_path, &bytes).map_err(Error::UnableToWriteFile)?;
         }
+
+        let other_format = match self.format {
+            DefinitionsFormat::Yaml => DefinitionsFormat::Toml,
+            DefinitionsFormat::Toml => DefinitionsFormat::Yaml,
+        };
+        let other_path = validators_dir.as_ref().join(other_format.filename());
+        if other_path.exists() {
+            fs::remove_file(other_path).map_err(Error::UnableToWriteFile)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the format in which `self` is saved.
+    pub fn format(&self) -> DefinitionsFormat {
+        self.format
+    }
+
+    /// Sets the format in which `self` is saved.
+    pub fn set_format(&mut self, format: DefinitionsFormat) {
+        self.format = format
     }
 
     /// Adds a new `ValidatorDefinition` to `self`.
     pub fn push(&mut self, def: ValidatorDefinition) {
-        self.0.push(def)
+        self.definitions.push(def)
+    }
+
+    /// Removes the most recently added `ValidatorDefinition` from `self`.
+    pub fn pop(&mut self) -> Option<ValidatorDefinition> {
+        self.definitions.pop()
+    }
+
+    /// Retains only the `ValidatorDefinition` in `self` for which `f` returns `true`.
+    pub fn retain<F: FnMut(&ValidatorDefinition) -> bool>(&mut self, f: F) {
+        self.definitions.retain(f)
     }
 
     /// Returns a slice of all `ValidatorDefinition` in `self`.
     pub fn as_slice(&self) -> &[ValidatorDefinition] {
-        self.0.as_slice()
+        self.definitions.as_slice()
     }
 
     /// Returns a mutable slice of all `ValidatorDefinition` in `self`.
     pub fn as_mut_slice(&mut self) -> &mut [ValidatorDefinition] {
-        self.0.as_mut_slice()
+        self.definitions.as_mut_slice()
     }
 }
 
@@ -272,18 +461,34 @@ pub fn recursively_find_voting_keystores<P: AsRef<Path>>(
     dir: P,
     matches: &mut Vec<PathBuf>,
 ) -> Result<(), io::Error> {
+    recursively_find_keystores_matching(dir, &is_voting_keystore, matches, &mut vec![])
+}
+
+/// As per `recursively_find_voting_keystores`, except `is_keystore` determines which file names
+/// represent keystores.
+///
+/// Any files which are not keystores are added to `rejected`.
+pub fn recursively_find_keystores_matching<P, F>(
+    dir: P,
+    is_keystore: &F,
+    matches: &mut Vec<PathBuf>,
+    rejected: &mut Vec<PathBuf>,
+) -> Result<(), io::Error>
+where
+    P: AsRef<Path>,
+    F: Fn(&str) -> bool,
+{
     fs::read_dir(dir)?.try_for_each(|dir_entry| {
         let dir_entry = dir_entry?;
         let file_type = dir_entry.file_type()?;
         if file_type.is_dir() {
-            recursively_find_voting_keystores(dir_entry.path(), matches)?
-        } else if file_type.is_file()
-            && dir_entry
-                .file_name()
-                .to_str()
-                .map_or(false, is_voting_keystore)
-        {
-            matches.push(dir_entry.path())
+            recursively_find_keystores_matching(dir_entry.path(), is_keystore, matches, rejected)?
+        } else if file_type.is_file() {
+            if dir_entry.file_name().to_str().map_or(false, is_keystore) {
+                matches.push(dir_entry.path())
+            } else {
+                rejected.push(dir_entry.path())
+            }
         }
         Ok(())
     })
@@ -335,6 +540,100 @@ pub fn is_voting_keystore(file_name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tempfile::tempdir;
+    use types::Keypair;
+
+    fn definitions() -> ValidatorDefinitions {
+        let mut defs = ValidatorDefinitions::default();
+        defs.push(ValidatorDefinition {
+            enabled: true,
+            voting_public_key: Keypair::random().pk,
+            description: "first".to_string(),
+            graffiti: Some(GraffitiString::from_str("cats").unwrap()),
+            signing_definition: SigningDefinition::LocalKeystore {
+                voting_keystore_path: PathBuf::from("/a/voting-keystore.json"),
+                voting_keystore_password_path: None,
+                voting_keystore_password: Some(ZeroizeString::from("dogs".to_string())),
+            },
+        });
+        defs.push(ValidatorDefinition {
+            enabled: false,
+            voting_public_key: Keypair::random().pk,
+            description: String::new(),
+            graffiti: None,
+            signing_definition: SigningDefinition::LocalKeystore {
+                voting_keystore_path: PathBuf::from("/b/voting-keystore.json"),
+                voting_keystore_password_path: Some(PathBuf::from("/secrets/b")),
+                voting_keystore_password: None,
+            },
+        });
+        defs
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let yaml_dir = tempdir().unwrap();
+        let toml_dir = tempdir().unwrap();
+
+        let mut defs = definitions();
+        defs.save(yaml_dir.path()).unwrap();
+        defs.set_format(DefinitionsFormat::Toml);
+        defs.save(toml_dir.path()).unwrap();
+
+        assert!(!toml_dir.path().join(CONFIG_FILENAME).exists());
+        let yaml_defs = ValidatorDefinitions::open(yaml_dir.path()).unwrap();
+        let toml_defs = ValidatorDefinitions::open(toml_dir.path()).unwrap();
+        assert_eq!(yaml_defs.format(), DefinitionsFormat::Yaml);
+        assert_eq!(toml_defs.format(), DefinitionsFormat::Toml);
+        assert!(toml_defs.as_slice() == yaml_defs.as_slice());
+        assert!(toml_defs.as_slice() == defs.as_slice());
+    }
+
+    #[test]
+    fn empty_toml_round_trip() {
+        let dir = tempdir().unwrap();
+
+        let mut defs = ValidatorDefinitions::default();
+        defs.set_format(DefinitionsFormat::Toml);
+        defs.save(dir.path()).unwrap();
+
+        let defs = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert_eq!(defs.format(), DefinitionsFormat::Toml);
+        assert!(defs.as_slice().is_empty());
+    }
+
+    #[test]
+    fn save_replaces_other_format() {
+        let dir = tempdir().unwrap();
+
+        let mut defs = definitions();
+        defs.save(dir.path()).unwrap();
+        defs.set_format(DefinitionsFormat::Toml);
+        defs.save(dir.path()).unwrap();
+
+        assert!(!dir.path().join(CONFIG_FILENAME).exists());
+        assert!(dir.path().join(TOML_CONFIG_FILENAME).exists());
+        assert_eq!(
+            ValidatorDefinitions::open(dir.path()).unwrap().format(),
+            DefinitionsFormat::Toml
+        );
+
+        // A file is never parsed as the format of the other file name.
+        fs::copy(
+            dir.path().join(TOML_CONFIG_FILENAME),
+            dir.path().join(CONFIG_FILENAME),
+        )
+        .unwrap();
+        assert!(matches!(
+            ValidatorDefinitions::open(dir.path()),
+            Err(Error::MultipleConfigFiles)
+        ));
+        fs::remove_file(dir.path().join(TOML_CONFIG_FILENAME)).unwrap();
+        assert!(matches!(
+            ValidatorDefinitions::open(dir.path()),
+            Err(Error::UnableToParseFile(_))
+        ));
+    }
 
     #[test]
     fn voting_keystore_filename_lighthouse() {
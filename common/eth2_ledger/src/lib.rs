@@ -0,0 +1,297 @@
+//! Derives EIP-2334 BLS keys on a Ledger device using the Eth2 functionality of the Ledger
+//! Ethereum app, so that the keys are never present on this machine.
+//!
+//! The app only exposes the public keys, it does not sign with BLS keys. This is sufficient for
+//! withdrawal keys, which are only needed to create withdrawal credentials until withdrawals are
+//! enabled.
+//!
+//! Devices are only available when compiled with the `hid` feature.
+
+use bls::PublicKey;
+use eth2_wallet::{KeyType, ValidatorPath};
+
+/// The USB vendor ID of Ledger devices.
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// The HID usage page of the APDU interface of Ledger devices.
+pub const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+const CLA: u8 = 0xe0;
+const INS_GET_ETH2_PUBLIC_KEY: u8 = 0x0e;
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
+
+const SW_OK: u16 = 0x9000;
+const SW_USER_REJECTED: u16 = 0x6985;
+const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+const SW_CLA_NOT_SUPPORTED: u16 = 0x6e00;
+
+/// The maximum number of nodes in a path accepted by the Ethereum app.
+const MAX_PATH_NODES: usize = 10;
+
+const HID_PACKET_SIZE: usize = 64;
+const HID_CHANNEL: u16 = 0x0101;
+const HID_TAG_APDU: u8 = 0x05;
+#[cfg(feature = "hid")]
+const HID_READ_TIMEOUT_MS: i32 = 60_000;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// Compiled without the `hid` feature.
+    HidUnsupported,
+    NoDevice,
+    Hid(String),
+    /// The request was rejected on the device.
+    UserRejected,
+    /// The Ethereum app is not open on the device, or it does not support Eth2 keys.
+    AppUnavailable,
+    Status(u16),
+    InvalidPath(String),
+    InvalidResponse(String),
+    InvalidPublicKey(String),
+}
+
+/// A Ledger device running the Ethereum app.
+pub struct Ledger {
+    #[cfg(feature = "hid")]
+    device: hidapi::HidDevice,
+}
+
+impl Ledger {
+    /// Opens the first Ledger device that is connected.
+    #[cfg(feature = "hid")]
+    pub fn open() -> Result<Self, Error> {
+        let api = hidapi::HidApi::new().map_err(|e| Error::Hid(format!("{:?}", e)))?;
+        let info = api
+            .device_list()
+            .filter(|info| info.vendor_id() == LEDGER_VENDOR_ID)
+            // The usage page is not reported on all platforms, in which case the APDU interface
+            // is the first interface.
+            .find(|info| info.usage_page() == LEDGER_USAGE_PAGE || info.interface_number() == 0)
+            .ok_or(Error::NoDevice)?;
+        let device = info
+            .open_device(&api)
+            .map_err(|e| Error::Hid(format!("{:?}", e)))?;
+
+        Ok(Self { device })
+    }
+
+    /// Always returns an error, since HID devices are not supported without the `hid` feature.
+    #[cfg(not(feature = "hid"))]
+    pub fn open() -> Result<Self, Error> {
+        Err(Error::HidUnsupported)
+    }
+
+    /// Returns the public key of the withdrawal key at `index`, i.e. `m/12381/3600/index/0`.
+    ///
+    /// If `confirm` is true, the public key is shown on the device and must be approved there.
+    pub fn withdrawal_public_key(&self, index: u32, confirm: bool) -> Result<PublicKey, Error> {
+        self.eth2_public_key(&ValidatorPath::new(index, KeyType::Withdrawal), confirm)
+    }
+
+    /// Returns the public key of the BLS key at `path`.
+    ///
+    /// If `confirm` is true, the public key is shown on the device and must be approved there.
+    pub fn eth2_public_key(&self, path: &ValidatorPath, confirm: bool) -> Result<PublicKey, Error> {
+        let response = self.exchange(&get_eth2_public_key_apdu(path, confirm)?)?;
+        PublicKey::deserialize(&response).map_err(|e| Error::InvalidPublicKey(format!("{:?}", e)))
+    }
+
+    /// Sends `apdu` to the device, returning the response data if the status word indicates
+    /// success.
+    fn exchange(&self, apdu: &[u8]) -> Result<Vec<u8>, Error> {
+        for packet in wrap_apdu(apdu) {
+            self.write_packet(&packet)?;
+        }
+
+        let mut response = ResponseReader::default();
+        loop {
+            let packet = self.read_packet()?;
+            if let Some(data) = response.push(&packet)? {
+                return parse_status(data);
+            }
+        }
+    }
+
+    #[cfg(feature = "hid")]
+    fn write_packet(&self, packet: &[u8; HID_PACKET_SIZE]) -> Result<(), Error> {
+        // The first byte is the report ID, which is always zero.
+        let mut report = [0; HID_PACKET_SIZE + 1];
+        report[1..].copy_from_slice(packet);
+        self.device
+            .write(&report)
+            .map_err(|e| Error::Hid(format!("{:?}", e)))
+            .map(|_| ())
+    }
+
+    #[cfg(not(feature = "hid"))]
+    fn write_packet(&self, _packet: &[u8; HID_PACKET_SIZE]) -> Result<(), Error> {
+        Err(Error::HidUnsupported)
+    }
+
+    #[cfg(feature = "hid")]
+    fn read_packet(&self) -> Result<[u8; HID_PACKET_SIZE], Error> {
+        let mut packet = [0; HID_PACKET_SIZE];
+        let len = self
+            .device
+            .read_timeout(&mut packet, HID_READ_TIMEOUT_MS)
+            .map_err(|e| Error::Hid(format!("{:?}", e)))?;
+        if len == 0 {
+            return Err(Error::Hid("Timed out waiting for the device".to_string()));
+        }
+        Ok(packet)
+    }
+
+    #[cfg(not(feature = "hid"))]
+    fn read_packet(&self) -> Result<[u8; HID_PACKET_SIZE], Error> {
+        Err(Error::HidUnsupported)
+    }
+}
+
+/// Returns the APDU which requests the public key at `path` from the Ethereum app.
+fn get_eth2_public_key_apdu(path: &ValidatorPath, confirm: bool) -> Result<Vec<u8>, Error> {
+    let nodes = path.iter_nodes().copied().collect::<Vec<_>>();
+    if nodes.is_empty() || nodes.len() > MAX_PATH_NODES {
+        return Err(Error::InvalidPath(path.to_string()));
+    }
+
+    let mut data = vec![nodes.len() as u8];
+    for node in nodes {
+        data.extend_from_slice(&node.to_be_bytes());
+    }
+
+    let p1 = if confirm { P1_CONFIRM } else { P1_NON_CONFIRM };
+    let mut apdu = vec![CLA, INS_GET_ETH2_PUBLIC_KEY, p1, 0x00, data.len() as u8];
+    apdu.extend_from_slice(&data);
+    Ok(apdu)
+}
+
+/// Splits `apdu` into HID packets.
+///
+/// Each packet has a header of the channel, the tag and the sequence number, and the first packet
+/// also contains the length of the APDU. The last packet is padded with zeros.
+fn wrap_apdu(apdu: &[u8]) -> Vec<[u8; HID_PACKET_SIZE]> {
+    let mut payload = (apdu.len() as u16).to_be_bytes().to_vec();
+    payload.extend_from_slice(apdu);
+
+    payload
+        .chunks(HID_PACKET_SIZE - 5)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = [0; HID_PACKET_SIZE];
+            packet[0..2].copy_from_slice(&HID_CHANNEL.to_be_bytes());
+            packet[2] = HID_TAG_APDU;
+            packet[3..5].copy_from_slice(&(sequence as u16).to_be_bytes());
+            packet[5..5 + chunk.len()].copy_from_slice(chunk);
+            packet
+        })
+        .collect()
+}
+
+/// Reassembles a response from HID packets.
+#[derive(Default)]
+struct ResponseReader {
+    expected_len: Option<usize>,
+    data: Vec<u8>,
+    sequence: u16,
+}
+
+impl ResponseReader {
+    /// Adds the next `packet` of the response, returning the response once it is complete.
+    fn push(&mut self, packet: &[u8; HID_PACKET_SIZE]) -> Result<Option<Vec<u8>>, Error> {
+        if packet[0..2] != HID_CHANNEL.to_be_bytes() || packet[2] != HID_TAG_APDU {
+            return Err(Error::InvalidResponse(
+                "Unexpected packet header".to_string(),
+            ));
+        }
+        if packet[3..5] != self.sequence.to_be_bytes() {
+            return Err(Error::InvalidResponse(format!(
+                "Expected packet {}",
+                self.sequence
+            )));
+        }
+        self.sequence += 1;
+
+        let chunk = match self.expected_len {
+            Some(_) => &packet[5..],
+            None => {
+                self.expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+                &packet[7..]
+            }
+        };
+        let expected_len = self.expected_len.unwrap_or_default();
+        let remaining = expected_len - self.data.len();
+        self.data
+            .extend_from_slice(&chunk[..std::cmp::min(remaining, chunk.len())]);
+
+        if self.data.len() == expected_len {
+            Ok(Some(std::mem::take(&mut self.data)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Splits the status word from the end of `response`, returning the data if it indicates success.
+fn parse_status(mut response: Vec<u8>) -> Result<Vec<u8>, Error> {
+    if response.len() < 2 {
+        return Err(Error::InvalidResponse("Missing status word".to_string()));
+    }
+    let status = u16::from_be_bytes([response[response.len() - 2], response[response.len() - 1]]);
+    response.truncate(response.len() - 2);
+
+    match status {
+        SW_OK => Ok(response),
+        SW_USER_REJECTED => Err(Error::UserRejected),
+        SW_INS_NOT_SUPPORTED | SW_CLA_NOT_SUPPORTED => Err(Error::AppUnavailable),
+        other => Err(Error::Status(other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn withdrawal_key_apdu() {
+        let apdu =
+            get_eth2_public_key_apdu(&ValidatorPath::new(3, KeyType::Withdrawal), true).unwrap();
+
+        let mut expected = vec![0xe0, 0x0e, 0x01, 0x00, 17, 4];
+        for node in &[12381u32, 3600, 3, 0] {
+            expected.extend_from_slice(&node.to_be_bytes());
+        }
+        assert_eq!(apdu, expected);
+    }
+
+    #[test]
+    fn apdu_round_trip() {
+        for len in &[0, 1, 57, 58, 59, 200] {
+            let apdu = (0..*len).map(|i| i as u8).collect::<Vec<_>>();
+            let packets = wrap_apdu(&apdu);
+            assert_eq!(packets.len(), (len + 2 + 58) / 59);
+
+            let mut reader = ResponseReader::default();
+            let (last, rest) = packets.split_last().unwrap();
+            for packet in rest {
+                assert_eq!(reader.push(packet).unwrap(), None);
+            }
+            assert_eq!(reader.push(last).unwrap(), Some(apdu));
+        }
+    }
+
+    #[test]
+    fn out_of_order_packets() {
+        let packets = wrap_apdu(&[0; 100]);
+        let mut reader = ResponseReader::default();
+        assert!(reader.push(&packets[1]).is_err());
+    }
+
+    #[test]
+    fn status_words() {
+        assert_eq!(parse_status(vec![1, 2, 0x90, 0x00]), Ok(vec![1, 2]));
+        assert_eq!(parse_status(vec![0x69, 0x85]), Err(Error::UserRejected));
+        assert_eq!(parse_status(vec![0x6e, 0x00]), Err(Error::AppUnavailable));
+        assert_eq!(parse_status(vec![0x6a, 0x80]), Err(Error::Status(0x6a80)));
+        assert!(parse_status(vec![0x90]).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use crate::{
     filesystem::{read, update},
     Error,
 };
-use eth2_wallet::{Uuid, ValidatorKeystores, Wallet};
+use eth2_wallet::{PathTemplate, Uuid, ValidatorKeystores, Wallet};
 use lockfile::Lockfile;
 use std::path::{Path, PathBuf};
 
@@ -81,14 +81,54 @@ impl LockedWallet {
         voting_keystore_password: &[u8],
         withdrawal_keystore_password: &[u8],
     ) -> Result<ValidatorKeystores, Error> {
-        let keystores = self.wallet.next_validator(
+        self.next_validator_with_template(
             wallet_password,
             voting_keystore_password,
             withdrawal_keystore_password,
+            &PathTemplate::default(),
+        )
+    }
+
+    /// Calls `Wallet::next_validator_with_template` on the underlying `wallet`.
+    ///
+    /// Ensures that the wallet JSON file is updated after each call.
+    ///
+    /// ## Errors
+    ///
+    /// - If there is an error generating the validator keys.
+    /// - If there is a file-system error.
+    pub fn next_validator_with_template(
+        &mut self,
+        wallet_password: &[u8],
+        voting_keystore_password: &[u8],
+        withdrawal_keystore_password: &[u8],
+        template: &PathTemplate,
+    ) -> Result<ValidatorKeystores, Error> {
+        let keystores = self.wallet.next_validator_with_template(
+            wallet_password,
+            voting_keystore_password,
+            withdrawal_keystore_password,
+            template,
         )?;
 
         update(&self.wallet_dir, &self.wallet)?;
 
         Ok(keystores)
     }
+
+    /// Calls `Wallet::set_nextaccount` on the underlying `wallet`.
+    ///
+    /// Ensures that the wallet JSON file is updated after each call.
+    ///
+    /// ## Errors
+    ///
+    /// - If `nextaccount` is less than the current `nextaccount` of the wallet.
+    /// - If there is a file-system error.
+    pub fn set_nextaccount(&mut self, nextaccount: u32) -> Result<(), Error> {
+        self.wallet.set_nextaccount(nextaccount)?;
+
+        update(&self.wallet_dir, &self.wallet)?;
+
+        Ok(())
+    }
 }
@@ -94,6 +94,38 @@ impl<T: Encode + Decode> CacheArena<T> {
         range: Range<usize>,
         replace_with: I,
     ) -> Result<(), Error> {
+        let backing_range = self.backing_range(alloc_id, range)?;
+        let prev_len = self.backing.len();
+
+        self.backing.splice(backing_range, replace_with);
+
+        self.resize_offsets(alloc_id, prev_len)
+    }
+
+    /// Similar to `Vec::splice`, however the range is relative to some allocation (`alloc_id`).
+    ///
+    /// Unlike `Self::splice_forgetful`, the replaced items are collected and returned.
+    fn splice<I: IntoIterator<Item = T>>(
+        &mut self,
+        alloc_id: usize,
+        range: Range<usize>,
+        replace_with: I,
+    ) -> Result<Vec<T>, Error> {
+        let backing_range = self.backing_range(alloc_id, range)?;
+        let prev_len = self.backing.len();
+
+        let removed = self
+            .backing
+            .splice(backing_range, replace_with)
+            .collect::<Vec<_>>();
+
+        self.resize_offsets(alloc_id, prev_len)?;
+
+        Ok(removed)
+    }
+
+    /// Translate a `range` relative to `alloc_id` into a range of `self.backing`.
+    fn backing_range(&self, alloc_id: usize, range: Range<usize>) -> Result<Range<usize>, Error> {
         let offset = *self
             .offsets
             .get(alloc_id)
@@ -104,17 +136,17 @@ impl<T: Encode + Decode> CacheArena<T> {
             .ok_or(Error::RangeOverFlow)?;
         let end = range.end.checked_add(offset).ok_or(Error::RangeOverFlow)?;
 
-        let prev_len = self.backing.len();
-
-        self.backing.splice(start..end, replace_with);
+        Ok(start..end)
+    }
 
+    /// Update `self.offsets` after the allocation at `alloc_id` has been spliced, where
+    /// `prev_len` was the length of `self.backing` prior to the splice.
+    fn resize_offsets(&mut self, alloc_id: usize, prev_len: usize) -> Result<(), Error> {
         match prev_len.cmp(&self.backing.len()) {
-            Ordering::Greater => self.shrink(alloc_id, prev_len - self.backing.len())?,
-            Ordering::Less => self.grow(alloc_id, self.backing.len() - prev_len)?,
-            Ordering::Equal => {}
+            Ordering::Greater => self.shrink(alloc_id, prev_len - self.backing.len()),
+            Ordering::Less => self.grow(alloc_id, self.backing.len() - prev_len),
+            Ordering::Equal => Ok(()),
         }
-
-        Ok(())
     }
 
     /// Returns the length of the specified allocation.
@@ -228,6 +260,24 @@ impl<T: Encode + Decode> CacheArenaAllocation<T> {
         Ok(())
     }
 
+    /// Replace the items in `range` (relative to this allocation) with `replace_with`, returning
+    /// the items that were replaced.
+    ///
+    /// An error is returned if this allocation is not known to the given `arena` or if `range`
+    /// is not within the bounds of this allocation.
+    pub fn splice(
+        &self,
+        arena: &mut CacheArena<T>,
+        range: Range<usize>,
+        replace_with: Vec<T>,
+    ) -> Result<Vec<T>, Error> {
+        let len = arena.len(self.alloc_id)?;
+        if range.start > range.end || range.end > len {
+            return Err(Error::RangeOverFlow);
+        }
+        arena.splice(self.alloc_id, range, replace_with)
+    }
+
     /// Get the i'th item in the `arena` (relative to this allocation).
     ///
     /// An error is returned if this allocation is not known to the given `arena`.
@@ -361,6 +411,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn splice_returns_replaced() {
+        let arena = &mut CacheArena::default();
+        let first = arena.alloc();
+        let second = arena.alloc();
+
+        first
+            .extend_with_vec(arena, (0..4).map(hash).collect())
+            .expect("should extend first");
+        second
+            .extend_with_vec(arena, (4..8).map(hash).collect())
+            .expect("should extend second");
+
+        let removed = first
+            .splice(arena, 1..3, vec![hash(10), hash(11), hash(12)])
+            .expect("should splice");
+        assert_eq!(removed, vec![hash(1), hash(2)]);
+
+        let collected = first
+            .iter(arena)
+            .expect("should get iter")
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            collected,
+            vec![hash(0), hash(10), hash(11), hash(12), hash(3)]
+        );
+
+        let collected = second
+            .iter(arena)
+            .expect("should get iter")
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(collected, (4..8).map(hash).collect::<Vec<_>>());
+
+        assert_eq!(
+            second.splice(arena, 2..5, vec![]),
+            Err(super::Error::RangeOverFlow),
+            "should not splice beyond the allocation"
+        );
+    }
+
+    #[test]
+    fn get_near_usize_max() {
+        let arena = &mut CacheArena::default();
+        let _first = arena.alloc();
+        let second = arena.alloc();
+
+        second
+            .extend_with_vec(arena, (0..4).map(hash).collect())
+            .expect("should extend");
+
+        for i in &[usize::max_value() - 1, usize::max_value()] {
+            assert_eq!(
+                second.get(arena, *i).expect("should exist"),
+                None,
+                "should not wrap around to an existing item"
+            );
+            assert_eq!(
+                second.get_mut(arena, *i).expect("should exist"),
+                None,
+                "should not wrap around to an existing item"
+            );
+        }
+    }
+
     #[test]
     fn single() {
         let arena = &mut CacheArena::default();
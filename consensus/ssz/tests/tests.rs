@@ -1,4 +1,4 @@
-use ethereum_types::H256;
+use ethereum_types::{H160, H256};
 use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 
@@ -27,6 +27,13 @@ mod round_trip {
         round_trip(items);
     }
 
+    #[test]
+    fn h160() {
+        let items: Vec<H160> = vec![H160::zero(), H160::from([1; 20]), H160::random()];
+
+        round_trip(items);
+    }
+
     #[test]
     fn h256() {
         let items: Vec<H256> = vec![H256::zero(), H256::from([1; 32]), H256::random()];
@@ -0,0 +1,48 @@
+use crate::{
+    test_utils::TestRandom, Address, ChainSpec, Hash256, PublicKeyBytes, SecretKey,
+    SignedBlsToExecutionChange, SignedRoot,
+};
+
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use test_random_derive::TestRandom;
+use tree_hash_derive::TreeHash;
+
+/// A request to change the withdrawal credentials of a validator from a BLS withdrawal key to an
+/// execution address.
+///
+/// Spec v1.3.0 (Capella)
+#[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash, TestRandom)]
+pub struct BlsToExecutionChange {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// The withdrawal public key from which the withdrawal credentials were derived.
+    pub from_bls_pubkey: PublicKeyBytes,
+    pub to_execution_address: Address,
+}
+
+impl SignedRoot for BlsToExecutionChange {}
+
+impl BlsToExecutionChange {
+    pub fn sign(
+        self,
+        secret_key: &SecretKey,
+        genesis_validators_root: Hash256,
+        spec: &ChainSpec,
+    ) -> SignedBlsToExecutionChange {
+        let domain = spec.get_bls_to_execution_change_domain(genesis_validators_root);
+        let message = self.signing_root(domain);
+        SignedBlsToExecutionChange {
+            message: self,
+            signature: secret_key.sign(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ssz_and_tree_hash_tests!(BlsToExecutionChange);
+}
@@ -17,6 +17,7 @@ pub enum Domain {
     VoluntaryExit,
     SelectionProof,
     AggregateAndProof,
+    BlsToExecutionChange,
 }
 
 /// Holds all the "constants" for a BeaconChain.
@@ -93,6 +94,7 @@ pub struct ChainSpec {
     domain_voluntary_exit: u32,
     domain_selection_proof: u32,
     domain_aggregate_and_proof: u32,
+    domain_bls_to_execution_change: u32,
 
     /*
      * Fork choice
@@ -157,6 +159,7 @@ impl ChainSpec {
             Domain::VoluntaryExit => self.domain_voluntary_exit,
             Domain::SelectionProof => self.domain_selection_proof,
             Domain::AggregateAndProof => self.domain_aggregate_and_proof,
+            Domain::BlsToExecutionChange => self.domain_bls_to_execution_change,
         }
     }
 
@@ -184,6 +187,18 @@ impl ChainSpec {
         self.compute_domain(Domain::Deposit, self.genesis_fork_version, Hash256::zero())
     }
 
+    /// Get the domain for a `BlsToExecutionChange` signature.
+    ///
+    /// A change of withdrawal credentials is valid across forks, thus the domain is computed with
+    /// the genesis fork version.
+    pub fn get_bls_to_execution_change_domain(&self, genesis_validators_root: Hash256) -> Hash256 {
+        self.compute_domain(
+            Domain::BlsToExecutionChange,
+            self.genesis_fork_version,
+            genesis_validators_root,
+        )
+    }
+
     /// Return the 32-byte fork data root for the `current_version` and `genesis_validators_root`.
     ///
     /// This is used primarily in signature domains to avoid collisions across forks/chains.
@@ -307,6 +322,7 @@ impl ChainSpec {
             domain_voluntary_exit: 4,
             domain_selection_proof: 5,
             domain_aggregate_and_proof: 6,
+            domain_bls_to_execution_change: 10,
 
             /*
              * Fork choice
@@ -444,6 +460,11 @@ mod tests {
             spec.domain_aggregate_and_proof,
             &spec,
         );
+        test_domain(
+            Domain::BlsToExecutionChange,
+            spec.domain_bls_to_execution_change,
+            &spec,
+        );
     }
 }
 
@@ -788,6 +809,7 @@ impl YamlConfig {
              * Constants, not configurable.
              */
             genesis_slot: chain_spec.genesis_slot,
+            domain_bls_to_execution_change: chain_spec.domain_bls_to_execution_change,
             far_future_epoch: chain_spec.far_future_epoch,
             base_rewards_per_epoch: chain_spec.base_rewards_per_epoch,
             deposit_contract_tree_depth: chain_spec.deposit_contract_tree_depth,
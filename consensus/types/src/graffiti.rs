@@ -6,6 +6,7 @@ use regex::bytes::Regex;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use ssz::{Decode, DecodeError, Encode};
 use std::fmt;
+use std::str::FromStr;
 use tree_hash::TreeHash;
 
 pub const GRAFFITI_BYTES_LEN: usize = 32;
@@ -42,6 +43,57 @@ impl Into<[u8; GRAFFITI_BYTES_LEN]> for Graffiti {
     }
 }
 
+/// A UTF-8 string which is guaranteed to fit within the `GRAFFITI_BYTES_LEN` bytes of a
+/// `Graffiti`.
+///
+/// Useful for storing graffiti in human-readable configuration files.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct GraffitiString(String);
+
+impl GraffitiString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for GraffitiString {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.as_bytes().len() > GRAFFITI_BYTES_LEN {
+            return Err(format!(
+                "Graffiti is too long, {} bytes maximum but got {}",
+                GRAFFITI_BYTES_LEN,
+                s.as_bytes().len()
+            ));
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl<'de> Deserialize<'de> for GraffitiString {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+impl From<GraffitiString> for Graffiti {
+    fn from(graffiti_string: GraffitiString) -> Graffiti {
+        let graffiti_bytes = graffiti_string.0.as_bytes();
+        let mut graffiti = [0; GRAFFITI_BYTES_LEN];
+
+        // Panic-free because `graffiti_bytes.len()` <= `GRAFFITI_BYTES_LEN`.
+        graffiti[..graffiti_bytes.len()].copy_from_slice(graffiti_bytes);
+
+        graffiti.into()
+    }
+}
+
 pub mod serde_graffiti {
     use super::*;
 
@@ -24,6 +24,9 @@ pub trait TPublicKey: Sized + Clone {
 
     /// Deserialize `self` from compressed bytes.
     fn deserialize(bytes: &[u8]) -> Result<Self, Error>;
+
+    /// Returns the sum of `self` and `other`.
+    fn add(&self, other: &Self) -> Result<Self, Error>;
 }
 
 /// A BLS aggregate public key that is generic across some BLS point (`Pub`).
@@ -69,6 +72,19 @@ where
             })
         }
     }
+
+    /// Returns the sum of `self` and `other`, which is the public key of the sum of their secret
+    /// keys.
+    ///
+    /// Errors if the sum is the point at infinity, which is not a valid public key.
+    pub fn add(&self, other: &Self) -> Result<Self, Error> {
+        let point = self.point.add(&other.point)?;
+        if point.serialize()[..] == INFINITY_PUBLIC_KEY[..] {
+            Err(Error::InvalidInfinityPublicKey)
+        } else {
+            Ok(Self { point })
+        }
+    }
 }
 
 impl<Pub: TPublicKey> Eq for GenericPublicKey<Pub> {}
@@ -134,6 +134,12 @@ impl TPublicKey for blst_core::PublicKey {
         }
         Self::key_validate(&bytes).map_err(Into::into)
     }
+
+    fn add(&self, other: &Self) -> Result<Self, Error> {
+        blst_core::AggregatePublicKey::aggregate(&[self, other], false)
+            .map(|aggregate| aggregate.to_public_key())
+            .map_err(Into::into)
+    }
 }
 
 /// A wrapper that allows for `PartialEq` and `Clone` impls.
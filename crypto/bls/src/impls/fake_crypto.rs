@@ -50,6 +50,10 @@ impl TPublicKey for PublicKey {
         pubkey.0[..].copy_from_slice(&bytes[0..PUBLIC_KEY_BYTES_LEN]);
         Ok(pubkey)
     }
+
+    fn add(&self, _other: &Self) -> Result<Self, Error> {
+        Ok(self.clone())
+    }
 }
 
 impl Eq for PublicKey {}
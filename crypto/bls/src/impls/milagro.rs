@@ -84,6 +84,12 @@ impl TPublicKey for milagro::PublicKey {
     fn deserialize(bytes: &[u8]) -> Result<Self, Error> {
         Self::from_bytes(&bytes).map_err(Into::into)
     }
+
+    fn add(&self, other: &Self) -> Result<Self, Error> {
+        let mut aggregate = milagro::AggregatePublicKey::from_public_key(self);
+        aggregate.add(other);
+        Ok(milagro::PublicKey::new_from_raw(&aggregate.point))
+    }
 }
 
 impl TAggregatePublicKey for milagro::AggregatePublicKey {}
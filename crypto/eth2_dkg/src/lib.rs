@@ -0,0 +1,373 @@
+//! Provides the cryptography for a distributed key generation (DKG) ceremony, in which `n`
+//! operators create a BLS validator key that is shared between them, such that any `threshold` of
+//! them can sign on behalf of the validator but no smaller set of them can.
+//!
+//! This is the Joint-Feldman protocol (Pedersen's DKG):
+//!
+//! 1. Each operator, as a dealer, creates a random `Polynomial` of degree `threshold - 1` and
+//!    publishes its `commitments` to all operators.
+//! 2. Each dealer sends `Polynomial::share` for each operator to that operator alone.
+//! 3. Each operator checks the shares it received with `verify_share`, and then adds them with
+//!    `combine_shares` to create its key share.
+//!
+//! The validator public key and the public key of every key share are derived from the
+//! commitments alone, using `combine_commitments` and `evaluate_commitments`. The validator secret
+//! key is never created, although it may be recovered from `threshold` key shares with
+//! `recover_secret_key`.
+//!
+//! Operator indices are 1-based, since the share at index 0 is the validator secret key.
+
+use bls::{PublicKey, SecretKey, ZeroizeHash, SECRET_KEY_BYTES_LEN};
+use num_bigint_dig::BigUint;
+use zeroize::Zeroizing;
+
+/// The order of the BLS-381 curve, as in EIP-2333.
+const R: &str = "52435875175126190479447740508185965837690552500527637822603658699938581184513";
+
+/// A secret scalar, which is zeroized on drop.
+type Scalar = Zeroizing<BigUint>;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The threshold is zero or greater than the number of operators.
+    InvalidThreshold {
+        threshold: usize,
+        operators: usize,
+    },
+    /// Operator indices start at 1.
+    InvalidIndex(u64),
+    /// There are no commitments to evaluate.
+    NoCommitments,
+    /// The commitments of the dealers do not have the same threshold.
+    InconsistentCommitments,
+    /// Fewer key shares than the threshold were provided.
+    InsufficientShares {
+        got: usize,
+        threshold: usize,
+    },
+    /// The same operator index was given twice.
+    DuplicateIndex(u64),
+    /// The resulting secret key is zero, which is not a valid BLS secret key.
+    ZeroSecretKey,
+    Bls(bls::Error),
+}
+
+impl From<bls::Error> for Error {
+    fn from(e: bls::Error) -> Self {
+        Error::Bls(e)
+    }
+}
+
+/// Checks that a `threshold` of `operators` is valid.
+pub fn check_threshold(threshold: usize, operators: usize) -> Result<(), Error> {
+    if threshold == 0 || threshold > operators {
+        Err(Error::InvalidThreshold {
+            threshold,
+            operators,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// A secret polynomial over the scalar field, created by a dealer.
+///
+/// The coefficients are zeroized on drop.
+pub struct Polynomial {
+    coefficients: Vec<Scalar>,
+}
+
+impl Polynomial {
+    /// Creates a random polynomial of degree `threshold - 1`.
+    pub fn random(threshold: usize) -> Result<Self, Error> {
+        if threshold == 0 {
+            return Err(Error::InvalidThreshold {
+                threshold,
+                operators: 0,
+            });
+        }
+
+        // Random secret keys are random, non-zero scalars.
+        let coefficients = (0..threshold)
+            .map(|_| scalar_from_secret_key(&SecretKey::random()))
+            .collect();
+        Ok(Self { coefficients })
+    }
+
+    /// Returns the threshold of the key shares created from this polynomial.
+    pub fn threshold(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Returns the public commitments to the coefficients, i.e. the public key of each
+    /// coefficient.
+    pub fn commitments(&self) -> Result<Vec<PublicKey>, Error> {
+        self.coefficients
+            .iter()
+            .map(|coefficient| Ok(secret_key_from_scalar(coefficient)?.public_key()))
+            .collect()
+    }
+
+    /// Returns the share of the operator at `index`, i.e. the polynomial evaluated at `index`.
+    pub fn share(&self, index: u64) -> Result<SecretKey, Error> {
+        if index == 0 {
+            return Err(Error::InvalidIndex(index));
+        }
+
+        // Horner's method, so that only multiplications by `index` are required.
+        let r = r();
+        let mut result = Scalar::new(BigUint::default());
+        for coefficient in self.coefficients.iter().rev() {
+            *result *= index;
+            *result += &**coefficient;
+            *result %= &r;
+        }
+        secret_key_from_scalar(&result)
+    }
+}
+
+/// Returns the public key of the share at `index` of the polynomial committed to by `commitments`.
+pub fn evaluate_commitments(commitments: &[PublicKey], index: u64) -> Result<PublicKey, Error> {
+    if index == 0 {
+        return Err(Error::InvalidIndex(index));
+    }
+
+    let (last, rest) = commitments.split_last().ok_or(Error::NoCommitments)?;
+
+    // Horner's method, so that only multiplications by `index` are required.
+    let mut result = last.clone();
+    for commitment in rest.iter().rev() {
+        result = multiply(&result, index)?.add(commitment)?;
+    }
+    Ok(result)
+}
+
+/// Returns true if `share` is the share at `index` of the polynomial committed to by
+/// `commitments`.
+pub fn verify_share(
+    commitments: &[PublicKey],
+    index: u64,
+    share: &SecretKey,
+) -> Result<bool, Error> {
+    Ok(evaluate_commitments(commitments, index)? == share.public_key())
+}
+
+/// Returns the commitments to the sum of the polynomials committed to by each of the
+/// `commitments`. The first commitment is the validator public key.
+pub fn combine_commitments(commitments: &[Vec<PublicKey>]) -> Result<Vec<PublicKey>, Error> {
+    let threshold = commitments.first().ok_or(Error::NoCommitments)?.len();
+    if threshold == 0 || commitments.iter().any(|c| c.len() != threshold) {
+        return Err(Error::InconsistentCommitments);
+    }
+
+    (0..threshold)
+        .map(|k| {
+            commitments[1..]
+                .iter()
+                .try_fold(commitments[0][k].clone(), |sum, c| sum.add(&c[k]))
+                .map_err(Into::into)
+        })
+        .collect()
+}
+
+/// Returns the key share of an operator from the `shares` it received from every dealer.
+pub fn combine_shares(shares: &[SecretKey]) -> Result<SecretKey, Error> {
+    let r = r();
+    let mut result = Scalar::new(BigUint::default());
+    for share in shares {
+        *result += &*scalar_from_secret_key(share);
+        *result %= &r;
+    }
+    secret_key_from_scalar(&result)
+}
+
+/// Recovers the validator secret key from at least `threshold` of the key shares, given with
+/// their operator indices, by Lagrange interpolation at zero.
+pub fn recover_secret_key(
+    shares: &[(u64, SecretKey)],
+    threshold: usize,
+) -> Result<SecretKey, Error> {
+    if shares.len() < threshold || threshold == 0 {
+        return Err(Error::InsufficientShares {
+            got: shares.len(),
+            threshold,
+        });
+    }
+    let shares = &shares[..threshold];
+    for (i, (index, _)) in shares.iter().enumerate() {
+        if *index == 0 {
+            return Err(Error::InvalidIndex(*index));
+        }
+        if shares[..i].iter().any(|(other, _)| other == index) {
+            return Err(Error::DuplicateIndex(*index));
+        }
+    }
+
+    let r = r();
+    let mut result = Scalar::new(BigUint::default());
+    for (index, share) in shares {
+        // The Lagrange coefficient at zero is the product of `j / (j - i)` for all other `j`.
+        let mut numerator = BigUint::from(1u64);
+        let mut denominator = BigUint::from(1u64);
+        for (other, _) in shares.iter().filter(|(other, _)| other != index) {
+            numerator = (numerator * *other) % &r;
+            denominator = (denominator * ((BigUint::from(*other) + &r - *index) % &r)) % &r;
+        }
+        // `r` is prime, so the inverse is `denominator ^ (r - 2)`.
+        let inverse = denominator.modpow(&(r.clone() - 2u64), &r);
+        let mut term = scalar_from_secret_key(share);
+        *term *= &numerator;
+        *term %= &r;
+        *term *= &inverse;
+        *result += &*term;
+        *result %= &r;
+    }
+    secret_key_from_scalar(&result)
+}
+
+fn r() -> BigUint {
+    BigUint::parse_bytes(R.as_bytes(), 10).expect("must be able to parse R")
+}
+
+fn scalar_from_secret_key(secret_key: &SecretKey) -> Scalar {
+    Scalar::new(BigUint::from_bytes_be(secret_key.serialize().as_bytes()))
+}
+
+fn secret_key_from_scalar(scalar: &BigUint) -> Result<SecretKey, Error> {
+    if *scalar == BigUint::default() {
+        return Err(Error::ZeroSecretKey);
+    }
+
+    let be_bytes = Zeroizing::new(scalar.to_bytes_be());
+    let mut bytes = ZeroizeHash::zero();
+    bytes.as_mut_bytes()[SECRET_KEY_BYTES_LEN - be_bytes.len()..].copy_from_slice(&be_bytes);
+    SecretKey::deserialize(bytes.as_bytes()).map_err(Into::into)
+}
+
+/// Multiplies `point` by the non-zero `scalar` by doubling and adding.
+fn multiply(point: &PublicKey, scalar: u64) -> Result<PublicKey, Error> {
+    if scalar == 0 {
+        return Err(Error::InvalidIndex(scalar));
+    }
+
+    let mut result = point.clone();
+    for bit in (0..(63 - scalar.leading_zeros())).rev() {
+        result = result.add(&result)?;
+        if scalar >> bit & 1 == 1 {
+            result = result.add(point)?;
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs a ceremony between `operators`, returning the combined commitments and the key share
+    /// of each operator.
+    fn ceremony(threshold: usize, operators: u64) -> (Vec<PublicKey>, Vec<SecretKey>) {
+        let polynomials = (0..operators)
+            .map(|_| Polynomial::random(threshold).unwrap())
+            .collect::<Vec<_>>();
+        let commitments = polynomials
+            .iter()
+            .map(|p| p.commitments().unwrap())
+            .collect::<Vec<_>>();
+
+        let key_shares = (1..=operators)
+            .map(|index| {
+                let shares = polynomials
+                    .iter()
+                    .zip(&commitments)
+                    .map(|(polynomial, commitments)| {
+                        let share = polynomial.share(index).unwrap();
+                        assert!(verify_share(commitments, index, &share).unwrap());
+                        share
+                    })
+                    .collect::<Vec<_>>();
+                combine_shares(&shares).unwrap()
+            })
+            .collect();
+
+        (combine_commitments(&commitments).unwrap(), key_shares)
+    }
+
+    #[test]
+    fn key_shares_match_commitments() {
+        let (commitments, key_shares) = ceremony(3, 4);
+        for (index, key_share) in (1..).zip(&key_shares) {
+            assert_eq!(
+                evaluate_commitments(&commitments, index).unwrap(),
+                key_share.public_key()
+            );
+        }
+    }
+
+    #[test]
+    fn threshold_recovers_validator_key() {
+        let (commitments, key_shares) = ceremony(3, 5);
+        let validator_pubkey = &commitments[0];
+        let indexed = (1..).zip(key_shares).collect::<Vec<_>>();
+
+        for subset in &[[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let shares = subset
+                .iter()
+                .map(|&i| (indexed[i].0, indexed[i].1.clone()))
+                .collect::<Vec<_>>();
+            let secret_key = recover_secret_key(&shares, 3).unwrap();
+            assert_eq!(&secret_key.public_key(), validator_pubkey);
+        }
+
+        // Fewer shares than the threshold do not recover the key.
+        let shares = indexed[..2]
+            .iter()
+            .map(|(i, s)| (*i, s.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            recover_secret_key(&shares, 3).err(),
+            Some(Error::InsufficientShares {
+                got: 2,
+                threshold: 3
+            })
+        );
+        let wrong_threshold = recover_secret_key(&shares, 2).unwrap();
+        assert_ne!(&wrong_threshold.public_key(), validator_pubkey);
+    }
+
+    #[test]
+    fn invalid_share_is_rejected() {
+        let polynomial = Polynomial::random(2).unwrap();
+        let commitments = polynomial.commitments().unwrap();
+        let share = polynomial.share(2).unwrap();
+
+        assert!(verify_share(&commitments, 2, &share).unwrap());
+        assert!(!verify_share(&commitments, 1, &share).unwrap());
+        assert!(!verify_share(&commitments, 2, &SecretKey::random()).unwrap());
+    }
+
+    #[test]
+    fn multiply_matches_scalar_multiplication() {
+        let point = secret_key_from_scalar(&BigUint::from(7u64))
+            .unwrap()
+            .public_key();
+
+        for scalar in &[1u64, 2, 3, 10, 255, 1 << 40] {
+            let expected = secret_key_from_scalar(&(BigUint::from(7u64) * *scalar))
+                .unwrap()
+                .public_key();
+            assert_eq!(multiply(&point, *scalar).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        assert!(check_threshold(0, 3).is_err());
+        assert!(check_threshold(4, 3).is_err());
+        assert!(check_threshold(3, 3).is_ok());
+        assert_eq!(
+            Polynomial::random(2).unwrap().share(0).err(),
+            Some(Error::InvalidIndex(0))
+        );
+    }
+}
@@ -4,8 +4,11 @@ mod wallet;
 pub mod json_wallet;
 
 pub use bip39;
-pub use validator_path::{KeyType, ValidatorPath, COIN_TYPE, PURPOSE};
+pub use validator_path::{
+    KeyType, PathTemplate, ValidatorPath, COIN_TYPE, INDEX_PLACEHOLDER, PURPOSE,
+};
 pub use wallet::{
-    recover_validator_secret, recover_validator_secret_from_mnemonic, DerivedKey, Error,
-    KeystoreError, PlainText, Uuid, ValidatorKeystores, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_from_mnemonic,
+    recover_validator_secret_with_template, DerivedKey, Error, KeystoreError, PlainText, Uuid,
+    ValidatorKeystores, Wallet, WalletBuilder,
 };
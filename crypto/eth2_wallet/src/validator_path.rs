@@ -1,9 +1,13 @@
 use std::fmt;
 use std::iter::Iterator;
+use std::str::FromStr;
 
 pub const PURPOSE: u32 = 12381;
 pub const COIN_TYPE: u32 = 3600;
 
+/// The node of a `PathTemplate` which is replaced by the index of the validator.
+pub const INDEX_PLACEHOLDER: &str = "{index}";
+
 pub enum KeyType {
     Voting,
     Withdrawal,
@@ -39,3 +43,90 @@ impl fmt::Display for ValidatorPath {
         Ok(())
     }
 }
+
+/// A template for the path of a voting key, in which `INDEX_PLACEHOLDER` is replaced by the index
+/// of the validator (e.g., `m/12381/3600/{index}/0/0`).
+///
+/// Allows reproducing keys which were derived with a non-standard path. As in EIP-2334, the
+/// withdrawal key is the parent of the voting key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathTemplate(Vec<Option<u32>>);
+
+impl PathTemplate {
+    /// Returns the path of the `key_type` for the validator at `index`.
+    pub fn path(&self, index: u32, key_type: KeyType) -> ValidatorPath {
+        let mut vec: Vec<u32> = self.0.iter().map(|node| node.unwrap_or(index)).collect();
+
+        match key_type {
+            KeyType::Voting => {}
+            KeyType::Withdrawal => {
+                vec.pop();
+            }
+        }
+
+        ValidatorPath(vec)
+    }
+}
+
+impl Default for PathTemplate {
+    /// Returns the standard EIP-2334 template, `m/12381/3600/{index}/0/0`.
+    fn default() -> Self {
+        Self(vec![Some(PURPOSE), Some(COIN_TYPE), None, Some(0), Some(0)])
+    }
+}
+
+impl FromStr for PathTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut nodes = s.split('/');
+
+        if nodes.next() != Some("m") {
+            return Err(format!("Path template {} must start with m/", s));
+        }
+
+        let nodes = nodes
+            .map(|node| {
+                if node == INDEX_PLACEHOLDER {
+                    Ok(None)
+                } else {
+                    node.parse::<u32>().map(Some).map_err(|_| {
+                        format!(
+                            "Invalid node {:?} in path template {}, nodes must be integers or {}",
+                            node, s, INDEX_PLACEHOLDER
+                        )
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if nodes.iter().filter(|node| node.is_none()).count() != 1 {
+            return Err(format!(
+                "Path template {} must contain {} exactly once",
+                s, INDEX_PLACEHOLDER
+            ));
+        }
+
+        // The withdrawal key is the parent of the voting key, so it must not be the master key.
+        if nodes.len() < 2 {
+            return Err(format!("Path template {} must have at least two nodes", s));
+        }
+
+        Ok(Self(nodes))
+    }
+}
+
+impl fmt::Display for PathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+
+        for node in &self.0 {
+            match node {
+                Some(node) => write!(f, "/{}", node)?,
+                None => write!(f, "/{}", INDEX_PLACEHOLDER)?,
+            }
+        }
+
+        Ok(())
+    }
+}
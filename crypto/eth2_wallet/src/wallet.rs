@@ -3,7 +3,7 @@ use crate::{
         Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, JsonWallet,
         Kdf, KdfModule, Sha256Checksum, TypeField, Version,
     },
-    KeyType, ValidatorPath,
+    KeyType, PathTemplate, ValidatorPath,
 };
 pub use bip39::{Mnemonic, Seed as Bip39Seed};
 pub use eth2_key_derivation::{DerivedKey, DerivedKeyError};
@@ -185,14 +185,40 @@ impl Wallet {
         wallet_password: &[u8],
         voting_keystore_password: &[u8],
         withdrawal_keystore_password: &[u8],
+    ) -> Result<ValidatorKeystores, Error> {
+        self.next_validator_with_template(
+            wallet_password,
+            voting_keystore_password,
+            withdrawal_keystore_password,
+            &PathTemplate::default(),
+        )
+    }
+
+    /// As `Self::next_validator`, but derives the keys with `template` rather than the standard
+    /// EIP-2334 path.
+    ///
+    /// ## Errors
+    ///
+    /// As `Self::next_validator`.
+    pub fn next_validator_with_template(
+        &mut self,
+        wallet_password: &[u8],
+        voting_keystore_password: &[u8],
+        withdrawal_keystore_password: &[u8],
+        template: &PathTemplate,
     ) -> Result<ValidatorKeystores, Error> {
         // Helper closure to reduce code duplication when generating keys.
         //
         // It is not a function on `self` to help protect against generating keys without
         // incrementing `nextaccount`.
         let derive = |key_type: KeyType, password: &[u8]| -> Result<Keystore, Error> {
-            let (secret, path) =
-                recover_validator_secret(&self, wallet_password, self.json.nextaccount, key_type)?;
+            let (secret, path) = recover_validator_secret_with_template(
+                &self,
+                wallet_password,
+                self.json.nextaccount,
+                key_type,
+                template,
+            )?;
 
             let keypair = keypair_from_secret(secret.as_bytes())?;
 
@@ -304,7 +330,25 @@ pub fn recover_validator_secret(
     index: u32,
     key_type: KeyType,
 ) -> Result<(PlainText, ValidatorPath), Error> {
-    let path = ValidatorPath::new(index, key_type);
+    recover_validator_secret_with_template(
+        wallet,
+        wallet_password,
+        index,
+        key_type,
+        &PathTemplate::default(),
+    )
+}
+
+/// As `recover_validator_secret`, but derives the key with `template` rather than the standard
+/// EIP-2334 path.
+pub fn recover_validator_secret_with_template(
+    wallet: &Wallet,
+    wallet_password: &[u8],
+    index: u32,
+    key_type: KeyType,
+    template: &PathTemplate,
+) -> Result<(PlainText, ValidatorPath), Error> {
+    let path = template.path(index, key_type);
     let secret = wallet.decrypt_seed(wallet_password)?;
     let master = DerivedKey::from_seed(secret.as_bytes()).map_err(Error::from)?;
 
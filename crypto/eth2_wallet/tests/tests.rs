@@ -2,7 +2,8 @@
 
 use eth2_wallet::{
     bip39::{Language, Mnemonic, Seed},
-    recover_validator_secret, DerivedKey, Error, KeyType, KeystoreError, Wallet, WalletBuilder,
+    recover_validator_secret, recover_validator_secret_with_template, DerivedKey, Error, KeyType,
+    KeystoreError, PathTemplate, Wallet, WalletBuilder,
 };
 use std::fs::OpenOptions;
 use tempfile::tempdir;
@@ -279,3 +280,100 @@ fn key_derivation_from_seed() {
         assert_eq!(wallet.nextaccount(), i + 1, "updated nextaccount");
     }
 }
+
+#[test]
+fn path_template_parsing() {
+    for template in &[
+        "m/12381/3600/{index}/0/0",
+        "m/12381/60/0/{index}",
+        "m/{index}/1",
+    ] {
+        assert_eq!(
+            format!("{}", template.parse::<PathTemplate>().unwrap()),
+            *template,
+            "template should round trip"
+        );
+    }
+
+    assert_eq!(
+        "m/12381/3600/{index}/0/0".parse::<PathTemplate>().unwrap(),
+        PathTemplate::default(),
+        "standard template should be the default"
+    );
+
+    for template in &[
+        "",
+        "m",
+        "12381/3600/{index}/0/0",
+        "m/12381/3600/0/0",
+        "m/12381/{index}/{index}/0",
+        "m/12381'/3600/{index}/0/0",
+        "m/12381/3600/{index}/0/",
+        "m/{index}",
+    ] {
+        assert!(
+            template.parse::<PathTemplate>().is_err(),
+            "{} should be invalid",
+            template
+        );
+    }
+}
+
+#[test]
+fn key_derivation_with_template() {
+    let template: PathTemplate = "m/12381/60/0/{index}".parse().unwrap();
+    let mut wallet = wallet_from_seed();
+    wallet.set_nextaccount(3).unwrap();
+
+    let keystores = wallet
+        .next_validator_with_template(
+            WALLET_PASSWORD,
+            VOTING_KEYSTORE_PASSWORD,
+            WITHDRAWAL_KEYSTORE_PASSWORD,
+            &template,
+        )
+        .expect("should generate keystores");
+
+    assert_eq!(keystores.voting.path().unwrap(), "m/12381/60/0/3");
+    assert_eq!(keystores.withdrawal.path().unwrap(), "m/12381/60/0");
+    assert_eq!(wallet.nextaccount(), 4, "updated nextaccount");
+
+    let master = DerivedKey::from_seed(SEED).expect("should derive master key");
+    let withdrawal = master.child(12381).child(60).child(0);
+    let voting = withdrawal.child(3);
+
+    let voting_keypair = keystores
+        .voting
+        .decrypt_keypair(VOTING_KEYSTORE_PASSWORD)
+        .expect("should decrypt voting keypair");
+    assert_eq!(
+        voting_keypair.sk.serialize().as_ref(),
+        voting.secret(),
+        "voting secret should match manually derived"
+    );
+
+    let withdrawal_keypair = keystores
+        .withdrawal
+        .decrypt_keypair(WITHDRAWAL_KEYSTORE_PASSWORD)
+        .expect("should decrypt withdrawal keypair");
+    assert_eq!(
+        withdrawal_keypair.sk.serialize().as_ref(),
+        withdrawal.secret(),
+        "withdrawal secret should match manually derived"
+    );
+
+    let (secret, path) = recover_validator_secret_with_template(
+        &wallet,
+        WALLET_PASSWORD,
+        3,
+        KeyType::Voting,
+        &template,
+    )
+    .expect("should recover voting secret");
+    assert_eq!(format!("{}", path), "m/12381/60/0/3");
+    assert_eq!(
+        secret.as_bytes(),
+        voting.secret(),
+        "voting secret should match recovered"
+    );
+}
@@ -1,9 +1,20 @@
 #![cfg(not(debug_assertions))]
 
 use account_manager::{
+    dkg::{self, deal, finalize, init, ClusterDefinition},
     validator::{
+        bls_to_execution_change, change_password, common,
         create::*,
-        import::{self, CMD as IMPORT_CMD},
+        delete::{self, CMD as DELETE_CMD},
+        deposit_data, exit,
+        export::{self, CMD as EXPORT_CMD},
+        import::{self, ImportReport, KeystoreStatus, CMD as IMPORT_CMD},
+        import_mnemonic,
+        list::{self as validator_list, ValidatorSummary},
+        other_client::{self, Prysm, Teku},
+        rebuild_definitions::{self, CMD as REBUILD_DEFINITIONS_CMD},
+        recover,
+        verify::{self, VerifyResult, CMD as VERIFY_CMD},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -14,20 +25,43 @@ use account_manager::{
     CMD as ACCOUNT_CMD, WALLETS_DIR_FLAG, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
-    validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
+    default_keystore_password_path,
+    eth2_keystore::{
+        decrypt, default_kdf, encrypt,
+        json_keystore::{Aes128Ctr, Cipher, Crypto, Kdf},
+        keypair_from_secret, Keystore, KeystoreBuilder, IV_SIZE, SALT_SIZE,
+    },
+    eth2_wallet::{
+        bip39::{Language, Mnemonic, Seed},
+        DerivedKey,
+    },
+    validator_definitions::{
+        DefinitionsFormat, SigningDefinition, ValidatorDefinition, ValidatorDefinitions,
+        CONFIG_FILENAME, TOML_CONFIG_FILENAME,
+    },
     ZeroizeString,
 };
-use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use bls::get_withdrawal_credentials;
+use eth2_network_config::{Eth2NetworkConfig, DEFAULT_HARDCODED_NETWORK};
+use slashing_protection::{
+    interchange::{Interchange, InterchangeData, InterchangeMetadata, SignedBlock},
+    SlashingDatabase, SLASHING_PROTECTION_FILENAME, SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+};
+use ssz::Decode;
+use std::collections::HashMap;
 use std::env;
 use std::fs::{self, File};
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
+use std::time::{Duration, Instant};
 use tempfile::{tempdir, TempDir};
-use types::{Keypair, PublicKey};
-use validator_dir::ValidatorDir;
+use types::{
+    DepositMessage, Domain, Epoch, EthSpec, Hash256, Keypair, MainnetEthSpec, PublicKey, Signature,
+    SignedBlsToExecutionChange, SignedRoot, SignedVoluntaryExit, Slot, GRAFFITI_BYTES_LEN,
+};
+use validator_dir::{ValidatorDir, VOTING_KEYSTORE_FILE};
 
 // TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
 // access to an IPC endpoint during testing or adding support for deposit submission via HTTP and
@@ -404,6 +438,99 @@ fn validator_create() {
     assert_eq!(dir_validator_count(validator_dir.path()), 6);
 }
 
+#[test]
+fn validator_create_derivation_path() {
+    const TEMPLATE: &str = "m/12381/60/0/{index}";
+
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    output_result(
+        wallet_cmd()
+            .arg(format!("--{}", WALLETS_DIR_FLAG))
+            .arg(base_dir.path().as_os_str())
+            .arg(CREATE_CMD)
+            .arg(format!("--{}", NAME_FLAG))
+            .arg(&wallet.name)
+            .arg(format!("--{}", PASSWORD_FLAG))
+            .arg(wallet.password_path().as_os_str())
+            .arg(format!("--{}", MNEMONIC_FLAG))
+            .arg(wallet.mnemonic_path().as_os_str())
+            .arg(format!(
+                "--{}",
+                account_manager::wallet::create::FIRST_INDEX_FLAG
+            ))
+            .arg("5"),
+    )
+    .unwrap();
+    let phrase = fs::read_to_string(wallet.mnemonic_path()).unwrap();
+
+    let create_cmd = |template: &str, first_index: Option<&str>| {
+        let mut cmd = validator_cmd();
+        cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(CREATE_CMD)
+            .arg(format!("--{}", WALLETS_DIR_FLAG))
+            .arg(base_dir.path().as_os_str())
+            .arg(format!("--{}", WALLET_NAME_FLAG))
+            .arg(&wallet.name)
+            .arg(format!("--{}", WALLET_PASSWORD_FLAG))
+            .arg(wallet.password_path().as_os_str())
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.path().as_os_str())
+            .arg(format!("--{}", DERIVATION_PATH_FLAG))
+            .arg(template)
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg("1")
+            .stdin(Stdio::null());
+        if let Some(first_index) = first_index {
+            cmd.arg(format!(
+                "--{}",
+                account_manager::validator::create::FIRST_INDEX_FLAG
+            ))
+            .arg(first_index);
+        }
+        cmd
+    };
+
+    // The first validator is created at the index the wallet was created with.
+    output_result(&mut create_cmd(TEMPLATE, None)).unwrap();
+    // Indices which the wallet has already used are refused.
+    let err = output_result(&mut create_cmd(TEMPLATE, Some("5"))).unwrap_err();
+    assert!(err.contains("lower than the index of the next validator"));
+    output_result(&mut create_cmd(TEMPLATE, Some("9"))).unwrap();
+
+    // The voting keys are derived from the seed following the template.
+    let mnemonic = Mnemonic::from_phrase(phrase.trim(), Language::English).unwrap();
+    let seed = Seed::new(&mnemonic, "");
+    assert_eq!(dir_validator_count(validator_dir.path()), 2);
+    for index in &[5, 9] {
+        let secret = DerivedKey::from_seed(seed.as_bytes())
+            .unwrap()
+            .child(12381)
+            .child(60)
+            .child(0)
+            .child(*index);
+        let pubkey = keypair_from_secret(secret.secret()).unwrap().pk;
+
+        let keystore = Keystore::from_json_file(
+            validator_dir
+                .path()
+                .join(pubkey.to_hex_string())
+                .join(VOTING_KEYSTORE_FILE),
+        )
+        .unwrap();
+        assert_eq!(keystore.path().unwrap(), format!("m/12381/60/0/{}", index));
+    }
+
+    // Invalid templates are refused.
+    let err = output_result(&mut create_cmd("m/12381/3600/0/0", None)).unwrap_err();
+    assert!(err.contains("exactly once"));
+    assert_eq!(dir_validator_count(validator_dir.path()), 2);
+}
+
 #[test]
 fn validator_import_launchpad() {
     const PASSWORD: &str = "cats";
@@ -482,6 +609,7 @@ fn validator_import_launchpad() {
     let expected_def = ValidatorDefinition {
         enabled: true,
         description: "".into(),
+        graffiti: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
@@ -496,6 +624,4148 @@ fn validator_import_launchpad() {
     );
 }
 
+/// Writes a new random keystore encrypted with `password` to `dir/file_name`.
+fn write_keystore<P: AsRef<Path>>(dir: P, file_name: &str, password: &str) -> Keystore {
+    let keypair = Keypair::random();
+    let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    File::create(dir.as_ref().join(file_name))
+        .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+        .unwrap();
+
+    keystore
+}
+
+/// Returns the `lighthouse account validator import` command for the given directories.
+fn import_cmd<P: AsRef<Path>>(src_dir: P, dst_dir: P) -> Command {
+    let mut cmd = validator_cmd();
+    cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.as_ref().as_os_str())
+        .arg(IMPORT_CMD)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG)) // Using tty does not work well with tests.
+        .arg(format!("--{}", import::DIR_FLAG))
+        .arg(src_dir.as_ref().as_os_str());
+    cmd
+}
+
+#[test]
+fn validator_import_stdin_inputs_has_no_delay() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "dogs");
+
+    let start = Instant::now();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Omit the password for both keystores to avoid the cost of decryption.
+    child.stdin.as_mut().unwrap().write_all(b"\n\n").unwrap();
+
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+    assert!(
+        start.elapsed() < Duration::from_secs(2),
+        "non-interactive import should not pause between keystores"
+    );
+}
+
+#[test]
+fn validator_import_slashing_protection() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let interchange_dir = tempdir().unwrap();
+
+    let voting_pubkey = write_keystore(src_dir.path(), "keystore-0.json", "cats")
+        .public_key()
+        .unwrap();
+
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+    let interchange = Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        },
+        data: vec![InterchangeData {
+            pubkey: voting_pubkey.clone(),
+            signed_blocks: vec![SignedBlock {
+                slot: Slot::new(10),
+                signing_root: Some(Hash256::repeat_byte(1)),
+            }],
+            signed_attestations: vec![],
+        }],
+    };
+    let interchange_path = interchange_dir.path().join("interchange.json");
+    interchange
+        .write_to(File::create(&interchange_path).unwrap())
+        .unwrap();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::SLASHING_PROTECTION_FLAG))
+        .arg(interchange_path.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+
+    assert!(child.wait().unwrap().success());
+
+    // A conflicting block at the same slot must be refused.
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    slashing_db
+        .check_and_insert_block_signing_root(
+            &voting_pubkey,
+            Slot::new(10),
+            Hash256::repeat_byte(2).into(),
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn validator_import_export_slashing_protection() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let interchange_dir = tempdir().unwrap();
+
+    let voting_pubkey = write_keystore(src_dir.path(), "keystore-0.json", "cats")
+        .public_key()
+        .unwrap();
+    let new_voting_pubkey = write_keystore(src_dir.path(), "keystore-1.json", "cats")
+        .public_key()
+        .unwrap();
+
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+    let interchange = Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        },
+        data: vec![InterchangeData {
+            pubkey: voting_pubkey.clone(),
+            signed_blocks: vec![SignedBlock {
+                slot: Slot::new(10),
+                signing_root: Some(Hash256::repeat_byte(1)),
+            }],
+            signed_attestations: vec![],
+        }],
+    };
+    let interchange_path = interchange_dir.path().join("interchange.json");
+    interchange
+        .write_to(File::create(&interchange_path).unwrap())
+        .unwrap();
+    let export_path = interchange_dir.path().join("export.json");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::SLASHING_PROTECTION_FLAG))
+        .arg(interchange_path.as_os_str())
+        .arg(format!("--{}", import::EXPORT_SLASHING_PROTECTION_FLAG))
+        .arg(export_path.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // The validator without any history is exported with an empty record.
+    let exported = Interchange::from_json_reader(File::open(&export_path).unwrap()).unwrap();
+    let mut expected = interchange.clone();
+    expected.data.push(InterchangeData {
+        pubkey: new_voting_pubkey.clone(),
+        signed_blocks: vec![],
+        signed_attestations: vec![],
+    });
+    assert!(exported.equiv(&expected));
+
+    // Importing the export into a fresh database restores the history of both validators.
+    let fresh_db_dir = tempdir().unwrap();
+    let fresh_db =
+        SlashingDatabase::create(&fresh_db_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    let outcomes = fresh_db
+        .import_interchange_info(exported, genesis_validators_root)
+        .unwrap();
+    assert!(outcomes.iter().all(|outcome| !outcome.failed()));
+    assert!(fresh_db
+        .export_interchange_info(genesis_validators_root)
+        .unwrap()
+        .equiv(&interchange));
+    fresh_db.get_validator_id(&new_voting_pubkey).unwrap();
+    fresh_db
+        .check_and_insert_block_signing_root(
+            &voting_pubkey,
+            Slot::new(10),
+            Hash256::repeat_byte(2).into(),
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn validator_import_register_only() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "dogs"),
+    ];
+
+    // No passwords are required.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::REGISTER_ONLY_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    for keystore in &keystores {
+        slashing_db
+            .get_validator_id(&keystore.public_key().unwrap())
+            .unwrap();
+    }
+
+    // Nothing is copied and no definitions are added.
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+    assert!(!dst_dir.path().join(CONFIG_FILENAME).exists());
+}
+
+#[test]
+fn validator_import_slashing_protection_network() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+
+    // A database created for another network is refused.
+    let slashing_db_path = dst_dir.path().join(SLASHING_PROTECTION_FILENAME);
+    let slashing_db = SlashingDatabase::create(&slashing_db_path).unwrap();
+    slashing_db
+        .set_genesis_validators_root(Hash256::repeat_byte(0xaa))
+        .unwrap();
+    drop(slashing_db);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("Check --network"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    // A dry run does not record the network of a database which has not recorded one.
+    let dst_dir = tempdir().unwrap();
+    let slashing_db_path = dst_dir.path().join(SLASHING_PROTECTION_FILENAME);
+    drop(SlashingDatabase::create(&slashing_db_path).unwrap());
+    let slashing_protection = fs::read(&slashing_db_path).unwrap();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::DRY_RUN_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(fs::read(&slashing_db_path).unwrap(), slashing_protection);
+    let slashing_db = SlashingDatabase::open(&slashing_db_path).unwrap();
+    assert_eq!(slashing_db.genesis_validators_root().unwrap(), None);
+    drop(slashing_db);
+
+    // A new database is recorded as being for the selected network.
+    let dst_dir = tempdir().unwrap();
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    assert_eq!(
+        slashing_db.genesis_validators_root().unwrap(),
+        Some(genesis_validators_root)
+    );
+    slashing_db
+        .get_validator_id(&keystore.public_key().unwrap())
+        .unwrap();
+}
+
+#[test]
+fn validator_import_signing_history() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let voting_pubkey = keystore.public_key().unwrap();
+
+    // Record a block signed by the key, as though it was previously used in this directory.
+    let slashing_db =
+        SlashingDatabase::create(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    slashing_db.register_validator(&voting_pubkey).unwrap();
+    slashing_db
+        .check_and_insert_block_signing_root(
+            &voting_pubkey,
+            Slot::new(10),
+            Hash256::repeat_byte(1).into(),
+        )
+        .unwrap();
+    drop(slashing_db);
+
+    // The import is refused without acknowledging the risk.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::I_UNDERSTAND_SLASHING_RISK_FLAG));
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::I_UNDERSTAND_SLASHING_RISK_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+}
+
+#[test]
+fn validator_import_dry_run() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "cats");
+
+    let wrong_password_file = password_dir.path().join("wrong");
+    fs::write(&wrong_password_file, "dogs\n").unwrap();
+    let password_file = password_dir.path().join("password");
+    fs::write(&password_file, "cats\n").unwrap();
+
+    // A dry run into an empty directory creates neither the definitions nor the database.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::DRY_RUN_FLAG))
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("2 keystores would be imported"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+    assert!(!dst_dir.path().join(CONFIG_FILENAME).exists());
+    assert!(!dst_dir.path().join(SLASHING_PROTECTION_FILENAME).exists());
+
+    // Import one of the keystores, so that the other is the only one left to import.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::COUNT_FLAG))
+        .arg("1")
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+
+    let definitions = fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap();
+    let slashing_protection = fs::read(dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::DRY_RUN_FLAG))
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("1 keystores would be imported"));
+    assert!(stderr.contains("1 keystores would be skipped"));
+
+    // Every keystore is checked, rather than stopping at the first invalid password.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::DRY_RUN_FLAG))
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(wrong_password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("1 keystores would fail to import"));
+
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+    assert_eq!(
+        fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap(),
+        definitions
+    );
+    assert_eq!(
+        fs::read(dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap(),
+        slashing_protection
+    );
+}
+
+#[test]
+fn validator_import_password_env() {
+    const PASSWORD_VAR: &str = "LIGHTHOUSE_TEST_KEYSTORE_PASSWORD";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "cats");
+
+    // An incorrect password fails the import rather than prompting.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::PASSWORD_ENV_FLAG))
+        .arg(PASSWORD_VAR)
+        .env(PASSWORD_VAR, "dogs")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::PASSWORD_ENV_FLAG))
+        .arg(PASSWORD_VAR)
+        .env(PASSWORD_VAR, "cats")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::PASSWORD_PROMPT));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    for def in defs.as_slice() {
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), b"cats");
+    }
+}
+
+#[test]
+fn validator_import_password_file() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "cats");
+
+    let wrong_password_file = password_dir.path().join("wrong");
+    fs::write(&wrong_password_file, "dogs\n").unwrap();
+    let password_file = password_dir.path().join("password");
+    fs::write(&password_file, "cats\n").unwrap();
+
+    // An incorrect password fails the import rather than prompting.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(wrong_password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::PASSWORD_PROMPT));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    for def in defs.as_slice() {
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), b"cats");
+    }
+}
+
+#[test]
+fn validator_import_threads() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    for i in 0..4 {
+        write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats");
+    }
+    let dogs = write_keystore(src_dir.path(), "keystore-4.json", "dogs");
+
+    let password_file = password_dir.path().join("password");
+    fs::write(&password_file, "cats\n").unwrap();
+
+    let import_with_threads = |threads: &str| {
+        import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::THREADS_FLAG))
+            .arg(threads)
+            .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::CONTINUE_ON_ERROR_FLAG))
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    let output = import_with_threads("0");
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    // The keystore with another password is still reported as failed once the others have been
+    // imported.
+    let output = import_with_threads("2");
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 4);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 4);
+    assert!(!defs
+        .as_slice()
+        .iter()
+        .any(|def| def.voting_public_key == dogs.public_key().unwrap()));
+}
+
+#[test]
+fn validator_import_password_map() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let map_dir = tempdir().unwrap();
+
+    let cats = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let dogs = write_keystore(src_dir.path(), "keystore-1.json", "dogs");
+
+    let import_with_map = |map: serde_json::Value| {
+        let map_path = map_dir.path().join("passwords.json");
+        fs::write(&map_path, map.to_string()).unwrap();
+        import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::PASSWORD_MAP_FLAG))
+            .arg(map_path.as_os_str())
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    // A keystore without a password in the map fails the import rather than prompting.
+    let output = import_with_map(serde_json::json!({
+        format!("0x{}", cats.pubkey()): "cats",
+    }));
+    assert!(!output.status.success());
+    assert!(!from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::PASSWORD_PROMPT));
+
+    let output = import_with_map(serde_json::json!({
+        format!("0x{}", cats.pubkey()): "cats",
+        format!("0x{}", dogs.pubkey()): "dogs",
+    }));
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    for (keystore, expected) in &[(&cats, b"cats"), (&dogs, b"dogs")] {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should be imported");
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), *expected);
+    }
+}
+
+#[test]
+fn validator_import_secrets_dir() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let cats = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let dogs = write_keystore(src_dir.path(), "keystore-1.json", "dogs");
+
+    let import_with_secrets_dir = || {
+        import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.path().as_os_str())
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    // A keystore without a password file fails the import rather than prompting.
+    fs::write(
+        secrets_dir.path().join(format!("0x{}", cats.pubkey())),
+        "cats",
+    )
+    .unwrap();
+    let output = import_with_secrets_dir();
+    assert!(!output.status.success());
+    assert!(!from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::PASSWORD_PROMPT));
+
+    // An incorrect password is not prompted for again.
+    fs::write(
+        secrets_dir.path().join(format!("0x{}", dogs.pubkey())),
+        "cats",
+    )
+    .unwrap();
+    let output = import_with_secrets_dir();
+    assert!(!output.status.success());
+    assert!(!from_utf8(&output.stderr)
+        .unwrap()
+        .contains(import::PASSWORD_PROMPT));
+
+    fs::write(
+        secrets_dir.path().join(format!("0x{}", dogs.pubkey())),
+        "dogs\n",
+    )
+    .unwrap();
+    let output = import_with_secrets_dir();
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    for (keystore, expected) in &[(&cats, b"cats"), (&dogs, b"dogs")] {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should be imported");
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), *expected);
+    }
+}
+
+#[test]
+fn validator_import_network_mismatch() {
+    let src_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let deposit_data = serde_json::json!([{
+        "pubkey": keystore.pubkey(),
+        "fork_version": "00002009",
+        "network_name": "pyrmont",
+    }]);
+    fs::write(
+        src_dir
+            .path()
+            .join(format!("{}1607000000.json", import::DEPOSIT_DATA_PREFIX)),
+        deposit_data.to_string(),
+    )
+    .unwrap();
+    // Deposit data which cannot be read does not prevent the check of the other files.
+    fs::write(
+        src_dir
+            .path()
+            .join(format!("{}1607000001.json", import::DEPOSIT_DATA_PREFIX)),
+        "{",
+    )
+    .unwrap();
+
+    // The network is a top-level flag, so it must precede the account manager subcommand.
+    let run_import = |network: Option<&str>, args: &[&str]| {
+        let dst_dir = tempdir().unwrap();
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_lighthouse"));
+        if let Some(network) = network {
+            cmd.arg("--network").arg(network);
+        }
+        let mut child = cmd
+            .arg(ACCOUNT_CMD)
+            .arg(VALIDATOR_CMD)
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(IMPORT_CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", import::DIR_FLAG))
+            .arg(src_dir.path().as_os_str())
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        let count = dir_validator_count(dst_dir.path());
+        (output, count)
+    };
+    let strict = format!("--{}", import::STRICT_FLAG);
+
+    // The keystore matches the selected network.
+    let (output, count) = run_import(Some("pyrmont"), &[&strict]);
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(!stderr.contains("deposit data for"));
+    assert!(stderr.contains("Unable to parse deposit data"));
+    assert_eq!(count, 1);
+
+    // The keystore is for another network, which is only a warning by default.
+    let (output, count) = run_import(None, &[]);
+    assert!(output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("has deposit data for pyrmont"));
+    assert_eq!(count, 1);
+
+    let (output, count) = run_import(None, &[&strict]);
+    assert!(!output.status.success());
+    assert_eq!(count, 0);
+}
+
+#[test]
+fn validator_import_dir_naming() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let dir_naming = format!("--{}", import::DIR_NAMING_FLAG);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .args(&[dir_naming.as_str(), "uuid"])
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let dest_dir = dst_dir.path().join(keystore.uuid().to_string());
+    let dest_keystore = dest_dir.join("keystore-0.json");
+    assert!(dest_keystore.exists());
+    assert!(!dst_dir
+        .path()
+        .join(format!("0x{}", keystore.pubkey()))
+        .exists());
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    match &defs.as_slice()[0].signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } => assert_eq!(voting_keystore_path, &dest_keystore),
+    }
+
+    // The keystore is still skipped based on its public key, under any scheme.
+    for scheme in &["uuid", "pubkey"] {
+        let output = import_cmd(src_dir.path(), dst_dir.path())
+            .args(&[dir_naming.as_str(), scheme])
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert!(from_utf8(&output.stderr)
+            .unwrap()
+            .contains("Skipping import of keystore for existing public key"));
+    }
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+
+    // Keystores without a voting key path cannot be named by index.
+    let output = import_cmd(src_dir.path(), tempdir().unwrap().path())
+        .args(&[dir_naming.as_str(), "index"])
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn validator_import_reencrypt() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let src_bytes = fs::read(src_dir.path().join("keystore-0.json")).unwrap();
+    let password_file = password_dir.path().join("password.txt");
+    fs::write(&password_file, "new-password\n").unwrap();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::REENCRYPT_PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // The source keystore is untouched.
+    assert_eq!(
+        fs::read(src_dir.path().join("keystore-0.json")).unwrap(),
+        src_bytes
+    );
+
+    let dest_keystore = Keystore::from_json_file(
+        dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .join("keystore-0.json"),
+    )
+    .unwrap();
+    assert_eq!(dest_keystore.pubkey(), keystore.pubkey());
+    assert!(dest_keystore.decrypt_keypair(b"new-password").is_ok());
+    assert!(dest_keystore.decrypt_keypair(b"cats").is_err());
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    let password = defs.as_slice()[0]
+        .signing_definition
+        .voting_keystore_password()
+        .unwrap()
+        .expect("password should be stored");
+    assert_eq!(password.as_bytes(), b"new-password");
+
+    // The original password is required for re-encryption.
+    let mut child = import_cmd(src_dir.path(), tempdir().unwrap().path())
+        .arg(format!("--{}", import::REENCRYPT_PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+}
+
+#[test]
+fn validator_import_reencrypt_kdf() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let password_file = password_dir.path().join("password.txt");
+    fs::write(&password_file, "new-password\n").unwrap();
+
+    let reencrypt_cmd = |dst_dir: &Path, kdf: &str, cost: u32| {
+        let mut cmd = import_cmd(src_dir.path(), dst_dir);
+        cmd.arg(format!("--{}", import::REENCRYPT_PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", common::KDF_FLAG))
+            .arg(kdf)
+            .arg(format!("--{}", common::KDF_COST_FLAG))
+            .arg(cost.to_string())
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped());
+        cmd
+    };
+
+    let mut child = reencrypt_cmd(dst_dir.path(), "pbkdf2", common::MIN_PBKDF2_C)
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let dest_keystore = Keystore::from_json_file(
+        dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .join("keystore-0.json"),
+    )
+    .unwrap();
+    match dest_keystore.kdf() {
+        Kdf::Pbkdf2(params) => assert_eq!(params.c, common::MIN_PBKDF2_C),
+        other => panic!("unexpected kdf {:?}", other),
+    }
+    assert!(dest_keystore.decrypt_keypair(b"new-password").is_ok());
+
+    // Costs below the minimum, and scrypt costs which are not a power of two, are refused.
+    for (kdf, cost) in &[
+        ("scrypt", common::MIN_SCRYPT_N / 2),
+        ("scrypt", common::MIN_SCRYPT_N + 1),
+        ("pbkdf2", common::MIN_PBKDF2_C - 1),
+    ] {
+        let dst_dir = tempdir().unwrap();
+        let mut child = reencrypt_cmd(dst_dir.path(), kdf, *cost).spawn().unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        assert!(!child.wait().unwrap().success());
+        assert_eq!(dir_validator_count(dst_dir.path()), 0);
+    }
+}
+
+#[test]
+fn validator_import_min_password_entropy() {
+    let src_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    let run_import = |new_password: &str| {
+        let dst_dir = tempdir().unwrap();
+        let password_file = password_dir.path().join("password.txt");
+        fs::write(&password_file, new_password).unwrap();
+
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::REENCRYPT_PASSWORD_FILE_FLAG))
+            .arg(password_file.as_os_str())
+            .arg(format!("--{}", import::MIN_PASSWORD_ENTROPY_FLAG))
+            .arg("60")
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        let success = child.wait().unwrap().success();
+        assert_eq!(dir_validator_count(dst_dir.path()), success as usize);
+        success
+    };
+
+    assert!(!run_import("password"), "weak password should be rejected");
+    assert!(
+        run_import("Correct-Horse-Battery-Staple-9"),
+        "strong password should be accepted"
+    );
+}
+
+#[test]
+fn validator_import_manifest() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let manifest_dir = tempdir().unwrap();
+    let manifest = manifest_dir.path().join("manifest.jsonl");
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-2.json", "cats"),
+    ];
+
+    let run_import = |args: &[&str], stdin: &[u8]| {
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::MANIFEST_FLAG))
+            .arg(manifest.as_os_str())
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(stdin).unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // Import the first keystore, as if the import was interrupted after it.
+    let output = run_import(&[&format!("--{}", import::COUNT_FLAG), "1"], b"cats\n");
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&manifest).unwrap().lines().count(), 1);
+
+    // Simulate a crash part way through writing the next entry.
+    let mut file = fs::OpenOptions::new().append(true).open(&manifest).unwrap();
+    file.write_all(b"{\"path\":").unwrap();
+    drop(file);
+
+    // The first keystore is skipped without being read, so only one password is required for
+    // each of the remaining keystores.
+    let output = run_import(&[], b"cats\ncats\n");
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Skipping 1 keystores which were completed according to the manifest"));
+    assert!(!stderr.contains(&format!("0x{}", keystores[0].pubkey())));
+    assert!(stderr.contains("Successfully imported 2 validators (1 skipped, 0 failed)"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+
+    // All keystores are now recorded, so none are read or prompted for.
+    let output = run_import(&[], b"");
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Successfully imported 0 validators (3 skipped, 0 failed)"));
+    check_slashing_protection(
+        &dst_dir,
+        keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap()),
+    );
+}
+
+#[test]
+#[cfg(unix)]
+fn validator_import_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+    let set_mode = |path: &Path, mode| {
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+    };
+
+    let src_dir = tempdir().unwrap();
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    set_mode(&src_dir.path().join("keystore-0.json"), 0o644);
+
+    // An existing definitions file with permissive permissions is restricted.
+    let dst_dir = tempdir().unwrap();
+    ValidatorDefinitions::default()
+        .save(dst_dir.path())
+        .unwrap();
+    let defs_path = dst_dir.path().join(CONFIG_FILENAME);
+    set_mode(&defs_path, 0o644);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let dest_dir = dst_dir.path().join(format!("0x{}", keystore.pubkey()));
+    assert_eq!(mode(&dest_dir), 0o700);
+    assert_eq!(mode(&dest_dir.join("keystore-0.json")), 0o600);
+    assert_eq!(mode(&defs_path), 0o600);
+}
+
+#[test]
+fn validator_import_without_confirmation() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "cats");
+
+    // Imports reading their inputs from stdin are never asked for confirmation, so the first line
+    // is taken as the password.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::REUSE_PASSWORD_FLAG))
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("Do you want to continue with the import?"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+}
+
+#[test]
+fn validator_import_from_other_client() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let keys_dir = src_dir.path().join(Teku::KEYS_DIR);
+    let secrets_dir = src_dir.path().join(Teku::PASSWORDS_DIR);
+    fs::create_dir_all(&keys_dir).unwrap();
+    fs::create_dir_all(&secrets_dir).unwrap();
+
+    // The names of these keystores are not recognised by the default import.
+    let with_password = write_keystore(&keys_dir, "validator_a.json", "cats");
+    fs::write(secrets_dir.join("validator_a.txt"), "cats\n").unwrap();
+    let without_password = write_keystore(&keys_dir, "validator_b.json", "dogs");
+
+    // Only the keystore without a password file is prompted for.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::IMPORT_FROM_OTHER_CLIENT_FLAG))
+        .arg(other_client::TEKU)
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"dogs\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert_eq!(stderr.matches(import::PASSWORD_PROMPT).count(), 1);
+    assert!(stderr.contains("Successfully imported 2 validators (0 skipped, 0 failed)"));
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    for (keystore, password) in &[(&with_password, "cats"), (&without_password, "dogs")] {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should be imported");
+        assert_eq!(
+            def.signing_definition
+                .voting_keystore_password()
+                .unwrap()
+                .unwrap()
+                .as_bytes(),
+            password.as_bytes()
+        );
+    }
+
+    // An incorrect password file fails the import rather than prompting.
+    let dst_dir = tempdir().unwrap();
+    fs::write(secrets_dir.join("validator_a.txt"), "dogs\n").unwrap();
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::IMPORT_FROM_OTHER_CLIENT_FLAG))
+        .arg(other_client::TEKU)
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+}
+
+#[test]
+fn validator_import_from_teku_directory() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    // Each keystore is stored alongside its password, without `keys` and `passwords` directories.
+    let keystore_a = write_keystore(src_dir.path(), "validator_a.json", "cats");
+    fs::write(src_dir.path().join("validator_a.txt"), "cats\n").unwrap();
+    let keystore_b = write_keystore(src_dir.path(), "validator_b.json", "dogs");
+    fs::write(src_dir.path().join("validator_b.txt"), "dogs\n").unwrap();
+    fs::write(src_dir.path().join("deposit_data-1.json"), "[]").unwrap();
+
+    let output = output_result(
+        import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::IMPORT_FROM_OTHER_CLIENT_FLAG))
+            .arg(other_client::TEKU)
+            .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+            .stdin(Stdio::null()),
+    )
+    .unwrap();
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert_eq!(stderr.matches(import::PASSWORD_PROMPT).count(), 0);
+    assert!(stderr.contains("Successfully imported 2 validators (0 skipped, 0 failed)"));
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for (keystore, password) in &[(&keystore_a, "cats"), (&keystore_b, "dogs")] {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should be imported");
+        assert_eq!(
+            def.signing_definition
+                .voting_keystore_password()
+                .unwrap()
+                .unwrap()
+                .as_bytes(),
+            password.as_bytes()
+        );
+    }
+}
+
+/// Writes a Prysm accounts keystore for a non-HD wallet in `wallet_dir`, containing `keypairs`.
+fn write_prysm_wallet(wallet_dir: &Path, wallet_password: &str, keypairs: &[Keypair]) {
+    let accounts = serde_json::json!({
+        "private_keys": keypairs
+            .iter()
+            .map(|keypair| base64::encode(keypair.sk.serialize().as_bytes()))
+            .collect::<Vec<_>>(),
+        "public_keys": keypairs
+            .iter()
+            .map(|keypair| base64::encode(&keypair.pk.serialize()[..]))
+            .collect::<Vec<_>>(),
+    });
+
+    let kdf = default_kdf(vec![1; SALT_SIZE]);
+    let cipher = Cipher::Aes128Ctr(Aes128Ctr {
+        iv: vec![2; IV_SIZE].into(),
+    });
+    let (cipher_text, checksum) = encrypt(
+        accounts.to_string().as_bytes(),
+        wallet_password.as_bytes(),
+        &kdf,
+        &cipher,
+    )
+    .unwrap();
+    let keystore = serde_json::json!({
+        "crypto": {
+            "kdf": { "function": kdf.function(), "params": kdf, "message": "" },
+            "checksum": { "function": "sha256", "params": {}, "message": hex::encode(checksum) },
+            "cipher": {
+                "function": cipher.function(),
+                "params": cipher,
+                "message": hex::encode(cipher_text),
+            },
+        },
+        "uuid": "c2ffbc2e-6f93-4a5e-8e2b-3a4a9a4b4cfc",
+        "version": 4,
+        "name": "all-accounts.keystore.json",
+    });
+
+    let path = wallet_dir.join(Prysm::ACCOUNTS_KEYSTORES[0]);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, keystore.to_string()).unwrap();
+}
+
+#[test]
+fn validator_import_from_prysm() {
+    let wallet_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    let keypairs = vec![Keypair::random(), Keypair::random()];
+    write_prysm_wallet(wallet_dir.path(), "prysm-wallet-password", &keypairs);
+
+    let import_prysm = |dst_dir: &Path, wallet_password: &[u8]| {
+        let mut child = import_cmd(wallet_dir.path(), dst_dir)
+            .arg(format!("--{}", import::IMPORT_FROM_OTHER_CLIENT_FLAG))
+            .arg(other_client::PRYSM)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(wallet_password)
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // An incorrect wallet password fails the import.
+    let output = import_prysm(dst_dir.path(), b"wrong-password\n");
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    // The wallet password is only prompted for once, and unlocks every converted keystore.
+    let output = import_prysm(dst_dir.path(), b"prysm-wallet-password\n");
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert_eq!(stderr.matches(import::WALLET_PASSWORD_PROMPT).count(), 1);
+    assert!(!stderr.contains(import::PASSWORD_PROMPT));
+    check_slashing_protection(&dst_dir, keypairs.iter().map(|keypair| keypair.pk.clone()));
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), keypairs.len());
+    for keypair in &keypairs {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keypair.pk)
+            .expect("keystore should be imported");
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), b"prysm-wallet-password");
+    }
+
+    // The wallet password may be read from a file instead.
+    let other_dst_dir = tempdir().unwrap();
+    let wallet_password_path = password_dir.path().join("wallet-password");
+    fs::write(&wallet_password_path, "prysm-wallet-password\n").unwrap();
+    let output = import_cmd(wallet_dir.path(), other_dst_dir.path())
+        .arg(format!("--{}", import::IMPORT_FROM_OTHER_CLIENT_FLAG))
+        .arg(other_client::PRYSM)
+        .arg(format!("--{}", import::WALLET_PASSWORD_FILE_FLAG))
+        .arg(wallet_password_path.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(other_dst_dir.path()), keypairs.len());
+}
+
+#[test]
+fn validator_import_no_create() {
+    let src_dir = tempdir().unwrap();
+    let parent_dir = tempdir().unwrap();
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    let run_import = |dst_dir: &Path| {
+        let mut child = import_cmd(src_dir.path(), dst_dir)
+            .arg(format!("--{}", import::NO_CREATE_FLAG))
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        child.wait().unwrap().success()
+    };
+
+    let missing_dir = parent_dir.path().join("validators");
+    assert!(!run_import(&missing_dir));
+    assert!(!missing_dir.exists());
+
+    fs::create_dir(&missing_dir).unwrap();
+    assert!(run_import(&missing_dir));
+    assert_eq!(dir_validator_count(&missing_dir), 1);
+}
+
+#[test]
+fn validator_import_unreadable_keystore() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    fs::write(src_dir.path().join("keystore-1.json"), "{").unwrap();
+
+    let run_import = |continue_on_error: bool| {
+        let mut cmd = import_cmd(src_dir.path(), dst_dir.path());
+        if continue_on_error {
+            cmd.arg(format!("--{}", import::CONTINUE_ON_ERROR_FLAG));
+        }
+        let mut child = cmd
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // An unreadable keystore stops the import before anything is imported.
+    let output = run_import(false);
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("Unable to read keystore JSON"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    // Otherwise it is reported as failed, and the other keystores are imported.
+    let output = run_import(true);
+    assert!(!output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Successfully imported 1 validators (0 skipped, 1 failed)"));
+    assert!(stderr.contains("Failed to import 1 of 2 keystores"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+}
+
+#[test]
+fn validator_import_log_file() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let log_dir = tempdir().unwrap();
+    let log_file = log_dir.path().join("import.log");
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "secret-cats");
+    write_keystore(src_dir.path(), "keystore-1.json", "secret-dogs");
+
+    // The transcript includes the output which is omitted by `--quiet`.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::LOG_FILE_FLAG))
+        .arg(log_file.as_os_str())
+        .arg(format!("--{}", import::QUIET_FLAG))
+        .arg(format!("--{}", import::CONTINUE_ON_ERROR_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"secret-cats\nwrong-password\n")
+        .unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    let transcript = fs::read_to_string(&log_file).unwrap();
+    for event in &[
+        format!(
+            "Keystore found at {:?}",
+            src_dir.path().join("keystore-0.json")
+        ),
+        "Password is correct".to_string(),
+        format!(
+            "Invalid password for keystore {:?}",
+            src_dir.path().join("keystore-1.json")
+        ),
+        "Registered validator with slashing protection".to_string(),
+        format!("pubkey: 0x{}", keystore.pubkey()),
+        "Successfully imported 1 validators (0 skipped, 1 failed)".to_string(),
+        "Import failed: Failed to import 1 of 2 keystores".to_string(),
+    ] {
+        assert!(transcript.contains(event.as_str()), "missing {:?}", event);
+    }
+    assert!(!transcript.contains("wrong-password"));
+    assert!(!transcript.contains("secret-cats"));
+    assert!(!transcript.contains("secret-dogs"));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&log_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn validator_import_deterministic_order() {
+    let src_dir = tempdir().unwrap();
+    let report_dir = tempdir().unwrap();
+    // Created out of order, and across sub-directories, so that the directory iteration order is
+    // unlikely to be sorted.
+    for (sub_dir, name) in &[
+        ("b", "keystore-2.json"),
+        ("a", "keystore-1.json"),
+        ("b", "keystore-0.json"),
+        ("a", "keystore-3.json"),
+    ] {
+        let dir = src_dir.path().join(sub_dir);
+        fs::create_dir_all(&dir).unwrap();
+        write_keystore(&dir, name, "cats");
+    }
+
+    let import_report = |report_name: &str| {
+        let dst_dir = tempdir().unwrap();
+        let report_path = report_dir.path().join(report_name);
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::JSON_OUTPUT_FILE_FLAG))
+            .arg(report_path.as_os_str())
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(b"cats\ncats\ncats\ncats\n")
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+
+        let report: ImportReport =
+            serde_json::from_slice(&fs::read(&report_path).unwrap()).unwrap();
+        report
+            .keystores
+            .into_iter()
+            .map(|keystore| (keystore.path, keystore.pubkey))
+            .collect::<Vec<_>>()
+    };
+
+    let first = import_report("first.json");
+    let second = import_report("second.json");
+    assert_eq!(first.len(), 4);
+    assert_eq!(first, second);
+
+    let mut sorted = first.clone();
+    sorted.sort();
+    assert_eq!(first, sorted, "keystores should be imported in path order");
+}
+
+#[test]
+fn validator_import_invalid_password_from_stdin() {
+    let src_dir = tempdir().unwrap();
+    write_keystore(src_dir.path(), "keystore-0.json", "dogs");
+    write_keystore(src_dir.path(), "keystore-1.json", "cats");
+
+    // The first keystore fails on its first invalid password, rather than reading the password
+    // intended for the next keystore.
+    let run_import = |args: &[&str]| {
+        let dst_dir = tempdir().unwrap();
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .args(args)
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(b"cats\ncats\n")
+            .unwrap();
+        assert!(!child.wait().unwrap().success());
+        dir_validator_count(dst_dir.path())
+    };
+
+    assert_eq!(run_import(&[]), 0);
+    assert_eq!(
+        run_import(&[&format!("--{}", import::CONTINUE_ON_ERROR_FLAG)]),
+        1,
+        "the failed keystore should be skipped"
+    );
+    assert_eq!(
+        run_import(&[&format!("--{}", import::MAX_PASSWORD_ATTEMPTS_FLAG), "0"]),
+        0
+    );
+}
+
+/// Writes a zip archive to `path` containing the given `(name, contents)` files.
+fn write_zip(path: &Path, files: &[(&str, Vec<u8>)]) {
+    let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+    for (name, contents) in files {
+        zip.start_file(*name, zip::write::FileOptions::default())
+            .unwrap();
+        zip.write_all(contents).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn validator_export() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let export_dir = tempdir().unwrap();
+    let new_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let voting_pubkey = keystore.public_key().unwrap();
+    write_keystore(src_dir.path(), "keystore-1.json", "dogs");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ndogs\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // Sign a block, so that the exported history is not empty.
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+    SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME))
+        .unwrap()
+        .check_and_insert_block_signing_root(
+            &voting_pubkey,
+            Slot::new(10),
+            Hash256::repeat_byte(1).into(),
+        )
+        .unwrap();
+
+    let export_password_path = export_dir.path().join("password");
+    fs::write(&export_password_path, "mice\n").unwrap();
+    let archive = export_dir.path().join("export.zip");
+    let output = validator_cmd()
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(EXPORT_CMD)
+        .arg(format!("--{}", export::OUTPUT_FLAG))
+        .arg(archive.as_os_str())
+        .arg(format!("--{}", export::PUBKEYS_FLAG))
+        .arg(voting_pubkey.to_hex_string())
+        .arg(format!("--{}", export::INCLUDE_PASSWORDS_FLAG))
+        .arg(export_password_path.as_os_str())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Only the selected validator is exported, along with its history and encrypted password.
+    let mut zip = zip::ZipArchive::new(File::open(&archive).unwrap()).unwrap();
+    let mut names = (0..zip.len())
+        .map(|i| zip.by_index(i).unwrap().name().to_string())
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            format!("{}/{}", voting_pubkey.to_hex_string(), VOTING_KEYSTORE_FILE),
+            export::PASSWORDS_FILENAME.to_string(),
+            export::INTERCHANGE_FILENAME.to_string(),
+        ]
+    );
+    let passwords: HashMap<String, Crypto> =
+        serde_json::from_reader(zip.by_name(export::PASSWORDS_FILENAME).unwrap()).unwrap();
+    assert_eq!(passwords.len(), 1);
+    let password = decrypt(b"mice", &passwords[&voting_pubkey.to_hex_string()]).unwrap();
+    assert_eq!(password.as_bytes(), b"cats");
+    decrypt(b"dogs", &passwords[&voting_pubkey.to_hex_string()]).unwrap_err();
+
+    // An existing archive is not overwritten.
+    let output = validator_cmd()
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(EXPORT_CMD)
+        .arg(format!("--{}", export::OUTPUT_FLAG))
+        .arg(archive.as_os_str())
+        .arg(format!("--{}", export::ALL_FLAG))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    // Importing the archive restores the keystore and its slashing protection history.
+    let mut child = validator_cmd()
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(new_dir.path().as_os_str())
+        .arg(IMPORT_CMD)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG))
+        .arg(format!("--{}", import::ARCHIVE_FLAG))
+        .arg(archive.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(new_dir.path()), 1);
+
+    let new_db =
+        SlashingDatabase::open(&new_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    let exported = new_db
+        .export_interchange_info(genesis_validators_root)
+        .unwrap();
+    assert_eq!(exported.data.len(), 1);
+    assert_eq!(exported.data[0].pubkey, voting_pubkey);
+    new_db
+        .check_and_insert_block_signing_root(
+            &voting_pubkey,
+            Slot::new(10),
+            Hash256::repeat_byte(2).into(),
+        )
+        .unwrap_err();
+}
+
+#[test]
+fn validator_delete() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let export_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let voting_pubkey = keystore.public_key().unwrap();
+    let other_pubkey = write_keystore(src_dir.path(), "keystore-1.json", "dogs")
+        .public_key()
+        .unwrap();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ndogs\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let delete = |pubkey: &PublicKey, export_path: &Path, input: &[u8]| {
+        let mut child = validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(DELETE_CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", delete::PUBKEY_FLAG))
+            .arg(pubkey.to_hex_string())
+            .arg(format!("--{}", delete::EXPORT_SLASHING_PROTECTION_FLAG))
+            .arg(export_path.as_os_str())
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(input).unwrap();
+        child.wait().unwrap().success()
+    };
+    let keystore_dir = dst_dir.path().join(voting_pubkey.to_hex_string());
+    let export_path = export_dir.path().join("interchange.json");
+
+    // Declining the confirmation leaves everything in place.
+    assert!(delete(&voting_pubkey, &export_path, b"n\n"));
+    assert!(keystore_dir.exists());
+    assert!(!export_path.exists());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    assert!(delete(&voting_pubkey, &export_path, b"y\n"));
+    assert!(!keystore_dir.exists());
+    let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert_eq!(defs.as_slice()[0].voting_public_key, other_pubkey);
+
+    let exported = Interchange::from_json_reader(File::open(&export_path).unwrap()).unwrap();
+    assert_eq!(exported.data.len(), 1);
+    assert_eq!(exported.data[0].pubkey, voting_pubkey);
+
+    // A validator which does not exist, or an existing export file, is refused.
+    let other_export_path = export_dir.path().join("other.json");
+    assert!(!delete(&voting_pubkey, &other_export_path, b"y\n"));
+    assert!(!delete(&other_pubkey, &export_path, b"y\n"));
+    assert_eq!(
+        ValidatorDefinitions::open(dst_dir.path())
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn validator_delete_password_file() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+    let export_dir = tempdir().unwrap();
+
+    let pubkeys = (0..3)
+        .map(|i| {
+            write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats")
+                .public_key()
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::REUSE_PASSWORD_FLAG))
+        .arg(format!("--{}", import::STORE_PASSWORD_ONCE_FLAG))
+        .arg(format!("--{}", import::SHARED_PASSWORD_DIR_FLAG))
+        .arg(secrets_dir.path().as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    let shared_password_path = secrets_dir
+        .path()
+        .join(format!("{}0", import::SHARED_PASSWORD_FILE_PREFIX));
+
+    // Give the first validator its own password file.
+    let own_password_path = secrets_dir.path().join("own-password");
+    fs::write(&own_password_path, "cats").unwrap();
+    let mut defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    for def in defs.as_mut_slice() {
+        if def.voting_public_key == pubkeys[0] {
+            let SigningDefinition::LocalKeystore {
+                voting_keystore_password_path,
+                ..
+            } = &mut def.signing_definition;
+            *voting_keystore_password_path = Some(own_password_path.clone());
+        }
+    }
+    defs.save(dst_dir.path()).unwrap();
+
+    let delete = |pubkey: &PublicKey| {
+        let output = validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(DELETE_CMD)
+            .arg(format!("--{}", delete::YES_FLAG))
+            .arg(format!("--{}", delete::PUBKEY_FLAG))
+            .arg(pubkey.to_hex_string())
+            .arg(format!("--{}", delete::EXPORT_SLASHING_PROTECTION_FLAG))
+            .arg(export_dir.path().join(pubkey.to_hex_string()).as_os_str())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    };
+
+    // A password file which is only used by the deleted validator is removed.
+    delete(&pubkeys[0]);
+    assert!(!own_password_path.exists());
+
+    // A password file which is still used by another validator is retained, until the last of
+    // them is deleted.
+    delete(&pubkeys[1]);
+    assert!(shared_password_path.exists());
+    delete(&pubkeys[2]);
+    assert!(!shared_password_path.exists());
+}
+
+#[test]
+fn validator_import_archive() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let temp_dir = tempdir().unwrap();
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+    ];
+    let keystore_bytes = |name: &str| fs::read(src_dir.path().join(name)).unwrap();
+
+    let run_import = |archive: &Path| {
+        let mut child = validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(IMPORT_CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", import::ARCHIVE_FLAG))
+            .arg(archive.as_os_str())
+            .env("TMPDIR", temp_dir.path())
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(b"cats\ncats\n")
+            .unwrap();
+        child.wait().unwrap().success()
+    };
+
+    let archive = src_dir.path().join("validator_keys.zip");
+    write_zip(
+        &archive,
+        &[
+            (
+                "validator_keys/keystore-0.json",
+                keystore_bytes("keystore-0.json"),
+            ),
+            (
+                "validator_keys/keystore-1.json",
+                keystore_bytes("keystore-1.json"),
+            ),
+        ],
+    );
+    assert!(run_import(&archive));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+    check_slashing_protection(
+        &dst_dir,
+        keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap()),
+    );
+    assert_eq!(
+        fs::read_dir(temp_dir.path()).unwrap().count(),
+        0,
+        "the extracted keystores should be removed"
+    );
+
+    // Entries outside of the archive are refused.
+    let traversal = src_dir.path().join("traversal.zip");
+    write_zip(
+        &traversal,
+        &[("../keystore-2.json", keystore_bytes("keystore-0.json"))],
+    );
+    assert!(!run_import(&traversal));
+    assert!(!src_dir.path().join("keystore-2.json").exists());
+    assert!(!temp_dir.path().join("keystore-2.json").exists());
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn validator_rebuild_definitions() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+    ];
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let defs_path = dst_dir.path().join(CONFIG_FILENAME);
+    fs::remove_file(&defs_path).unwrap();
+
+    let rebuild_cmd = || {
+        let mut cmd = validator_cmd();
+        cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(REBUILD_DEFINITIONS_CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.path().as_os_str())
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped());
+        cmd
+    };
+
+    let mut child = rebuild_cmd().spawn().unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains(rebuild_definitions::PASSWORD_PROMPT));
+
+    // Every keystore is recovered, along with its password.
+    let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    assert_eq!(defs.as_slice().len(), keystores.len());
+    for keystore in &keystores {
+        let def = defs
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key == keystore.public_key().unwrap())
+            .expect("keystore should be recovered");
+        assert!(def.enabled);
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .unwrap()
+            .expect("password should be stored");
+        assert_eq!(password.as_bytes(), b"cats");
+    }
+
+    // A readable definitions file is not replaced.
+    let child = rebuild_cmd().spawn().unwrap();
+    assert!(!child.wait_with_output().unwrap().status.success());
+    assert_eq!(
+        ValidatorDefinitions::open(dst_dir.path())
+            .unwrap()
+            .as_slice()
+            .len(),
+        keystores.len()
+    );
+}
+
+#[test]
+fn validator_verify() {
+    let src_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    let good = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let bad = write_keystore(src_dir.path(), "keystore-1.json", "cats");
+    fs::write(
+        default_keystore_password_path(&good, password_dir.path()),
+        "cats\n",
+    )
+    .unwrap();
+    fs::write(
+        default_keystore_password_path(&bad, password_dir.path()),
+        "dogs\n",
+    )
+    .unwrap();
+    let src_paths = dir_paths(src_dir.path());
+
+    let output = validator_cmd()
+        .arg(VERIFY_CMD)
+        .arg(format!("--{}", verify::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .arg(format!("--{}", verify::PASSWORD_DIR_FLAG))
+        .arg(password_dir.path().as_os_str())
+        .arg(format!("--{}", verify::FORMAT_FLAG))
+        .arg("json")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+
+    let results: Vec<VerifyResult> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].path, src_dir.path().join("keystore-0.json"));
+    assert_eq!(results[0].pubkey, Some(format!("0x{}", good.pubkey())));
+    assert!(results[0].valid);
+    assert_eq!(results[1].pubkey, Some(format!("0x{}", bad.pubkey())));
+    assert!(!results[1].valid);
+    assert!(results[1].error.is_some());
+
+    // Passwords can also be entered interactively.
+    let mut child = validator_cmd()
+        .arg(VERIFY_CMD)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG))
+        .arg(format!("--{}", verify::DIR_FLAG))
+        .arg(src_dir.path().as_os_str())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        from_utf8(&output.stdout).unwrap().matches("OK\t").count(),
+        2
+    );
+
+    // Verification never modifies the keystores directory.
+    assert_eq!(dir_paths(src_dir.path()), src_paths);
+}
+
+#[test]
+fn validator_verify_validator_dir() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats"))
+        .collect::<Vec<_>>();
+
+    // The last keystore is imported without a password.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let verify = || {
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(VERIFY_CMD)
+            .arg(format!("--{}", verify::FORMAT_FLAG))
+            .arg("json")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap()
+    };
+
+    let output = verify();
+    assert!(output.status.success());
+    let results: Vec<VerifyResult> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|result| result.valid));
+    assert!(results[0].warning.is_none());
+    assert!(results[2].warning.is_some());
+
+    // Corrupt the checksum of the second keystore.
+    let keystore_path = dst_dir
+        .path()
+        .join(format!("0x{}", keystores[1].pubkey()))
+        .join("keystore-1.json");
+    let mut json: serde_json::Value =
+        serde_json::from_slice(&fs::read(&keystore_path).unwrap()).unwrap();
+    json["crypto"]["checksum"]["message"] = serde_json::Value::String("00".repeat(32));
+    fs::write(&keystore_path, json.to_string()).unwrap();
+
+    let output = verify();
+    assert!(!output.status.success());
+    let results: Vec<VerifyResult> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[1].path, keystore_path);
+    assert_eq!(
+        results[1].pubkey,
+        Some(format!("0x{}", keystores[1].pubkey()))
+    );
+    assert!(!results[1].valid);
+    assert!(results[0].valid && results[2].valid);
+}
+
+#[test]
+fn validator_import_progress() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+
+    for i in 0..3 {
+        write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats");
+    }
+    let password_file = password_dir.path().join("password");
+    fs::write(&password_file, "cats\n").unwrap();
+
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::PROGRESS_FLAG))
+        .arg(format!("--{}", import::PASSWORD_FILE_FLAG))
+        .arg(password_file.as_os_str())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+
+    let stderr = from_utf8(&output.stderr).unwrap();
+    for progress in &["[1/3] 33% ETA", "[2/3] 66% ETA", "[3/3] 100% ETA 0:00:00"] {
+        assert!(
+            stderr.contains(progress),
+            "{:?} should be printed",
+            progress
+        );
+    }
+    assert!(!stderr.contains("Keystore found at"));
+    assert!(stderr.contains("Successfully imported 3 validators (0 skipped, 0 failed)"));
+}
+
+#[test]
+fn validator_import_quiet() {
+    let src_dir = tempdir().unwrap();
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    fs::write(
+        src_dir.path().join("keystore-1.json"),
+        keystore_json_with_path("m/12381/3600/x/0/0"),
+    )
+    .unwrap();
+
+    let run_import = |args: &[&str]| {
+        let dst_dir = tempdir().unwrap();
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .arg(format!("--{}", import::QUIET_FLAG))
+            .arg(format!("--{}", import::CONTINUE_ON_ERROR_FLAG))
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(dir_validator_count(dst_dir.path()), 1);
+        output
+    };
+
+    let output = run_import(&[]);
+    assert!(!output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    for info in &[
+        "Keystore found at",
+        "pubkey:",
+        "Password is correct",
+        "Successfully updated",
+        "Successfully imported",
+    ] {
+        assert!(!stderr.contains(info), "{:?} should not be printed", info);
+    }
+    assert!(stderr.contains("Failed to import keystore"));
+    assert!(stderr.contains(import::KEYSTORE_REUSE_WARNING));
+
+    // The reuse warning is only omitted once the risk has been acknowledged.
+    let output = run_import(&[&format!("--{}", import::I_UNDERSTAND_SLASHING_RISK_FLAG)]);
+    assert!(!output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Failed to import keystore"));
+    assert!(!stderr.contains(import::KEYSTORE_REUSE_WARNING));
+}
+
+#[test]
+fn validator_import_registration_logging() {
+    const REGISTRATION_LOG: &str = "Registered validator with slashing protection";
+
+    let src_dir = tempdir().unwrap();
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+    ];
+
+    for args in &[vec![], vec![format!("--{}", import::ALL_OR_NOTHING_FLAG)]] {
+        let dst_dir = tempdir().unwrap();
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(b"cats\ncats\n")
+            .unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let stderr = from_utf8(&output.stderr).unwrap();
+        let log_lines = stderr
+            .lines()
+            .filter(|line| line.contains(REGISTRATION_LOG))
+            .collect::<Vec<_>>();
+        assert_eq!(log_lines.len(), keystores.len());
+        for keystore in &keystores {
+            assert!(log_lines
+                .iter()
+                .any(|line| line.contains(&format!("0x{}", keystore.pubkey()))));
+        }
+    }
+}
+
+#[test]
+fn validator_import_count_and_offset() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..8)
+        .map(|i| write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats"))
+        .collect::<Vec<_>>();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::OFFSET_FLAG))
+        .arg("2")
+        .arg(format!("--{}", import::COUNT_FLAG))
+        .arg("3")
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.as_mut().unwrap().write_all(b"\n\n\n").unwrap();
+
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+
+    for (i, keystore) in keystores.iter().enumerate() {
+        let dst_keystore_dir = dst_dir.path().join(format!("0x{}", keystore.pubkey()));
+        assert_eq!(
+            dst_keystore_dir.exists(),
+            (2..5).contains(&i),
+            "keystore {} should only be imported if it is within the selection",
+            i
+        );
+    }
+}
+
+#[test]
+fn validator_import_move() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let copied = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(
+        src_dir.path().join("keystore-0.json").exists(),
+        "source should be preserved by a normal import"
+    );
+    fs::remove_file(src_dir.path().join("keystore-0.json")).unwrap();
+
+    let moved = write_keystore(src_dir.path(), "keystore-1.json", "cats");
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::MOVE_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(
+        !src_dir.path().join("keystore-1.json").exists(),
+        "source should be removed by a moving import"
+    );
+
+    for keystore in &[copied, moved] {
+        assert!(dst_dir
+            .path()
+            .join(format!("0x{}", keystore.pubkey()))
+            .exists());
+    }
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn validator_import_disabled() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ENABLED_FLAG))
+        .arg("false")
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // Disabled validators should still be registered with slashing protection.
+    check_slashing_protection(&dst_dir, std::iter::once(keystore.public_key().unwrap()));
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert!(!defs.as_slice()[0].enabled, "validator should be disabled");
+}
+
+#[test]
+fn validator_import_graffiti() {
+    const GRAFFITI: &str = "Lighthouse 🦀";
+
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    // Graffiti longer than 32 bytes should be rejected rather than truncated.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::GRAFFITI_FLAG))
+        .arg("a".repeat(GRAFFITI_BYTES_LEN + 1))
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::GRAFFITI_FLAG))
+        .arg(GRAFFITI)
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    let graffiti = defs.as_slice()[0]
+        .graffiti
+        .clone()
+        .expect("definition should have graffiti");
+    assert_eq!(graffiti.as_str().as_bytes(), GRAFFITI.as_bytes());
+}
+
+#[test]
+fn validator_import_duplicate_pubkeys() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    fs::copy(
+        src_dir.path().join("keystore-0.json"),
+        src_dir.path().join("keystore-1.json"),
+    )
+    .unwrap();
+
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "duplicates should be refused");
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ALLOW_DUPLICATES_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn validator_import_destination_collision() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    // Two different keys with the same voting key path, e.g. from different mnemonics, in files
+    // with the same name in different directories.
+    let keystores = (0..2)
+        .map(|i| {
+            let dir = src_dir.path().join(format!("mnemonic-{}", i));
+            fs::create_dir(&dir).unwrap();
+            let keystore =
+                KeystoreBuilder::new(&Keypair::random(), b"cats", "m/12381/3600/0/0/0".into())
+                    .unwrap()
+                    .build()
+                    .unwrap();
+            keystore
+                .to_json_writer(File::create(dir.join("keystore-0.json")).unwrap())
+                .unwrap();
+            keystore
+        })
+        .collect::<Vec<_>>();
+
+    // Both keystores are named `index-0`. Neither directory exists until the staged import is
+    // committed, so the collision must be detected amongst the staged keystores.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+        .args(&[format!("--{}", import::DIR_NAMING_FLAG).as_str(), "index"])
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("is also being imported into"));
+
+    assert!(!dst_dir.path().join("index-0").exists());
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    for keystore in &keystores {
+        assert!(slashing_db
+            .get_validator_id(&keystore.public_key().unwrap())
+            .is_err());
+    }
+}
+
+#[test]
+fn validator_import_all_or_nothing() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    // Populate the validator directory with an existing validator.
+    let existing_dir = tempdir().unwrap();
+    write_keystore(existing_dir.path(), "keystore-0.json", "cats");
+    let mut child = import_cmd(existing_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let keystores = (0..4)
+        .map(|i| {
+            let password = if i == 2 { "dogs" } else { "cats" };
+            write_keystore(src_dir.path(), &format!("keystore-{}.json", i), password)
+        })
+        .collect::<Vec<_>>();
+
+    let paths_before = dir_paths(dst_dir.path());
+    let defs_before = fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\ncats\ncats\n")
+        .unwrap();
+    assert!(
+        !child.wait().unwrap().success(),
+        "wrong password should abort the import"
+    );
+
+    // The validator directory should be completely unchanged.
+    assert_eq!(dir_paths(dst_dir.path()), paths_before);
+    assert_eq!(
+        fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap(),
+        defs_before
+    );
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    for keystore in &keystores {
+        assert!(slashing_db
+            .get_validator_id(&keystore.public_key().unwrap())
+            .is_err());
+    }
+    drop(slashing_db);
+
+    // With the correct passwords, all of the keystores should be imported.
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\ndogs\ncats\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(dir_validator_count(dst_dir.path()), 5);
+    assert!(!dst_dir.path().join(import::STAGING_DIR_NAME).exists());
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        5
+    );
+    check_slashing_protection(
+        &dst_dir,
+        keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap()),
+    );
+}
+
+/// Writes a keystore with the given derivation `path` and returns its JSON.
+fn keystore_json_with_path(path: &str) -> String {
+    KeystoreBuilder::new(&Keypair::random(), b"cats", path.into())
+        .unwrap()
+        .build()
+        .unwrap()
+        .to_json_string()
+        .unwrap()
+}
+
+/// Runs an import of the single keystore at `src_dir/keystore.json`, returning the output.
+fn import_single_keystore(
+    src_dir: &TempDir,
+    dst_dir: &TempDir,
+    json: &str,
+    args: &[&str],
+) -> Output {
+    let keystore_path = src_dir.path().join("keystore.json");
+    fs::write(&keystore_path, json).unwrap();
+
+    let mut child = validator_cmd()
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(IMPORT_CMD)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG))
+        .arg(format!("--{}", import::KEYSTORE_FLAG))
+        .arg(keystore_path.as_os_str())
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn validator_import_keystore_validation() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let skip_validation = format!("--{}", import::SKIP_VALIDATION_FLAG);
+
+    // A keystore with a voting key path is imported without warnings.
+    let output = import_single_keystore(
+        &src_dir,
+        &dst_dir,
+        &keystore_json_with_path("m/12381/3600/0/0/0"),
+        &[],
+    );
+    assert!(output.status.success());
+    assert!(!from_utf8(&output.stderr).unwrap().contains("is not a"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+
+    // A keystore with an unsupported version is refused, even when skipping validation.
+    let json =
+        keystore_json_with_path("m/12381/3600/1/0/0").replace("\"version\":4", "\"version\":3");
+    assert!(json.contains("\"version\":3"));
+    let output = import_single_keystore(&src_dir, &dst_dir, &json, &[&skip_validation]);
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+
+    // A keystore with an unexpected EIP-2334 path is imported with a warning.
+    let output = import_single_keystore(
+        &src_dir,
+        &dst_dir,
+        &keystore_json_with_path("m/12381/60/2/0"),
+        &[],
+    );
+    assert!(output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("are derived from m/12381/3600"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    // Such a keystore may be for another network, so it is refused by `--strict`.
+    let output = import_single_keystore(
+        &src_dir,
+        &dst_dir,
+        &keystore_json_with_path("m/12381/3600/3/0"),
+        &[&format!("--{}", import::STRICT_FLAG)],
+    );
+    assert!(!output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("is not a voting key path"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    // A keystore with an invalid path is only imported when skipping validation.
+    let json = keystore_json_with_path("m/12381/3600/x/0/0");
+    let output = import_single_keystore(&src_dir, &dst_dir, &json, &[]);
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    let output = import_single_keystore(&src_dir, &dst_dir, &json, &[&skip_validation]);
+    assert!(output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+}
+
+#[test]
+fn validator_import_store_password_once() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+    let password = "shared-import-password";
+
+    for i in 0..5 {
+        write_keystore(src_dir.path(), &format!("keystore-{}.json", i), password);
+    }
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::REUSE_PASSWORD_FLAG))
+        .arg(format!("--{}", import::STORE_PASSWORD_ONCE_FLAG))
+        .arg(format!("--{}", import::SHARED_PASSWORD_DIR_FLAG))
+        .arg(secrets_dir.path().as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(format!("{}\n", password).as_bytes())
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // The password should only be stored in a single file.
+    let password_count = dir_paths(dst_dir.path())
+        .into_iter()
+        .chain(dir_paths(secrets_dir.path()))
+        .filter(|path| path.is_file())
+        .map(|path| {
+            String::from_utf8_lossy(&fs::read(path).unwrap())
+                .matches(password)
+                .count()
+        })
+        .sum::<usize>();
+    assert_eq!(password_count, 1);
+    assert_eq!(
+        dir_paths(secrets_dir.path()),
+        vec![secrets_dir
+            .path()
+            .join(format!("{}0", import::SHARED_PASSWORD_FILE_PREFIX))]
+    );
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 5);
+    for def in defs.as_slice() {
+        assert_eq!(
+            def.signing_definition
+                .voting_keystore_password()
+                .unwrap()
+                .unwrap()
+                .as_bytes(),
+            password.as_bytes()
+        );
+    }
+}
+
+#[test]
+fn validator_import_store_password_once_all_or_nothing() {
+    let existing_dir = tempdir().unwrap();
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    // Populate the validator directory with an existing validator.
+    write_keystore(existing_dir.path(), "keystore-0.json", "cats");
+    let mut child = import_cmd(existing_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // The reused password is invalid for the second keystore.
+    for (i, password) in ["cats", "dogs", "cats"].iter().enumerate() {
+        write_keystore(src_dir.path(), &format!("keystore-{}.json", i), password);
+    }
+
+    let paths_before = dir_paths(dst_dir.path());
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+        .arg(format!("--{}", import::REUSE_PASSWORD_FLAG))
+        .arg(format!("--{}", import::STORE_PASSWORD_ONCE_FLAG))
+        .arg(format!("--{}", import::SHARED_PASSWORD_DIR_FLAG))
+        .arg(secrets_dir.path().as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    // The shared password file is rolled back along with the keystores.
+    assert_eq!(dir_paths(dst_dir.path()), paths_before);
+    assert_eq!(dir_paths(secrets_dir.path()), Vec::<PathBuf>::new());
+}
+
+#[test]
+fn validator_recover() {
+    let mnemonic_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                  abandon about";
+    let mnemonic_path = mnemonic_dir.path().join("mnemonic");
+    fs::write(&mnemonic_path, phrase).unwrap();
+
+    let recover_cmd = |first_index: &str, count: &str| {
+        let mut cmd = validator_cmd();
+        cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(recover::CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", SECRETS_DIR_FLAG))
+            .arg(secrets_dir.path().as_os_str())
+            .arg(format!("--{}", recover::MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", recover::FIRST_INDEX_FLAG))
+            .arg(first_index)
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg(count)
+            .stdin(Stdio::null());
+        cmd
+    };
+
+    // An index range which does not fit in a `u32` is refused.
+    let output = recover_cmd(&u32::MAX.to_string(), "2").output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let output = recover_cmd("1", "2").output().unwrap();
+    assert!(output.status.success());
+    let stdout = from_utf8(&output.stdout).unwrap();
+    assert!(stdout.contains("1/2\tIndex: 1"));
+    assert!(stdout.contains("2/2\tIndex: 2"));
+
+    // The voting keys are derived from the seed following the EIP-2334 path `m/12381/3600/i/0/0`.
+    let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+    let seed = Seed::new(&mnemonic, "");
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+    for index in 1..3 {
+        let secret = DerivedKey::from_seed(seed.as_bytes())
+            .unwrap()
+            .child(12381)
+            .child(3600)
+            .child(index)
+            .child(0)
+            .child(0);
+        let pubkey = keypair_from_secret(secret.secret()).unwrap().pk;
+
+        let keystore = Keystore::from_json_file(
+            dst_dir
+                .path()
+                .join(pubkey.to_hex_string())
+                .join(VOTING_KEYSTORE_FILE),
+        )
+        .unwrap();
+        let password = fs::read(default_keystore_password_path(
+            &keystore,
+            secrets_dir.path(),
+        ))
+        .unwrap();
+        assert_eq!(keystore.decrypt_keypair(&password).unwrap().pk, pubkey);
+    }
+}
+
+#[test]
+fn validator_bls_to_execution_change() {
+    let mnemonic_dir = tempdir().unwrap();
+    let output_dir = tempdir().unwrap();
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                  abandon about";
+    let mnemonic_path = mnemonic_dir.path().join("mnemonic");
+    fs::write(&mnemonic_path, phrase).unwrap();
+    let output_path = output_dir.path().join("changes.json");
+    let execution_address = "0x000000000000000000000000000000000000dead";
+
+    let change_cmd = |address: &str, acknowledge: bool| {
+        let mut cmd = validator_cmd();
+        cmd.arg(bls_to_execution_change::CMD)
+            .arg(format!("--{}", STDIN_INPUTS_FLAG))
+            .arg(format!("--{}", recover::MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", recover::FIRST_INDEX_FLAG))
+            .arg("1")
+            .arg(format!(
+                "--{}",
+                bls_to_execution_change::VALIDATOR_INDICES_FLAG
+            ))
+            .arg("100,42")
+            .arg(format!(
+                "--{}",
+                bls_to_execution_change::EXECUTION_ADDRESS_FLAG
+            ))
+            .arg(address)
+            .arg(format!("--{}", bls_to_execution_change::OUTPUT_FLAG))
+            .arg(output_path.as_os_str())
+            .stdin(Stdio::null());
+        if acknowledge {
+            cmd.arg(format!(
+                "--{}",
+                bls_to_execution_change::I_UNDERSTAND_UNCHECKED_CHANGES_FLAG
+            ));
+        }
+        cmd
+    };
+
+    // An address without the 0x prefix is refused.
+    output_result(&mut change_cmd(&execution_address[2..], true)).unwrap_err();
+    assert!(!output_path.exists());
+
+    // Unchecked changes are only written once the risk is acknowledged.
+    let error = output_result(&mut change_cmd(execution_address, false)).unwrap_err();
+    assert!(error.contains("are not checked"));
+    assert!(!output_path.exists());
+
+    output_result(&mut change_cmd(execution_address, true)).unwrap();
+
+    // The withdrawal keys are derived following the EIP-2334 path `m/12381/3600/i/0`.
+    let changes: Vec<SignedBlsToExecutionChange> =
+        serde_json::from_reader(File::open(&output_path).unwrap()).unwrap();
+    let spec = MainnetEthSpec::default_spec();
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+    let domain = spec.get_bls_to_execution_change_domain(genesis_validators_root);
+    let seed = Seed::new(
+        &Mnemonic::from_phrase(phrase, Language::English).unwrap(),
+        "",
+    );
+    assert_eq!(changes.len(), 2);
+    for (change, (key_index, validator_index)) in changes.iter().zip(vec![(1, 100), (2, 42)]) {
+        let secret = DerivedKey::from_seed(seed.as_bytes())
+            .unwrap()
+            .child(12381)
+            .child(3600)
+            .child(key_index)
+            .child(0);
+        let pubkey = keypair_from_secret(secret.secret()).unwrap().pk;
+
+        assert_eq!(change.message.validator_index, validator_index);
+        assert_eq!(change.message.from_bls_pubkey, (&pubkey).into());
+        assert_eq!(
+            format!("{:?}", change.message.to_execution_address),
+            execution_address
+        );
+        assert!(change
+            .signature
+            .verify(&pubkey, change.message.signing_root(domain)));
+    }
+
+    // An existing file is not overwritten.
+    let contents = fs::read(&output_path).unwrap();
+    output_result(&mut change_cmd(execution_address, true)).unwrap_err();
+    assert_eq!(fs::read(&output_path).unwrap(), contents);
+}
+
+#[test]
+fn validator_exit_offline() {
+    let keystore_dir = tempdir().unwrap();
+    let output_dir = tempdir().unwrap();
+    let keystore = write_keystore(keystore_dir.path(), "keystore.json", "cats");
+    let password_path = keystore_dir.path().join("password.txt");
+    fs::write(&password_path, "cats").unwrap();
+    let pubkey = keystore.public_key().unwrap();
+
+    let exit_cmd = |output_path: &Path, format: &str, fork_version: Option<&str>| {
+        let mut cmd = validator_cmd();
+        cmd.arg(exit::CMD)
+            .arg(format!("--{}", exit::OFFLINE_FLAG))
+            .arg(format!("--{}", exit::KEYSTORE_FLAG))
+            .arg(keystore_dir.path().join("keystore.json").as_os_str())
+            .arg(format!("--{}", exit::PASSWORD_FILE_FLAG))
+            .arg(password_path.as_os_str())
+            .arg(format!("--{}", exit::VALIDATOR_INDEX_FLAG))
+            .arg("42")
+            .arg(format!("--{}", exit::EPOCH_FLAG))
+            .arg("1000")
+            .arg(format!("--{}", exit::OUTPUT_FLAG))
+            .arg(output_path.as_os_str())
+            .arg(format!("--{}", exit::OUTPUT_FORMAT_FLAG))
+            .arg(format)
+            .stdin(Stdio::null());
+        if let Some(fork_version) = fork_version {
+            cmd.arg(format!("--{}", exit::FORK_VERSION_FLAG))
+                .arg(fork_version);
+        }
+        cmd
+    };
+
+    let spec = MainnetEthSpec::default_spec();
+    let genesis_validators_root = Eth2NetworkConfig::constant(DEFAULT_HARDCODED_NETWORK)
+        .unwrap()
+        .unwrap()
+        .beacon_state::<MainnetEthSpec>()
+        .unwrap()
+        .genesis_validators_root;
+    let verify = |signed_exit: &SignedVoluntaryExit, fork_version: [u8; 4]| {
+        assert_eq!(signed_exit.message.validator_index, 42);
+        assert_eq!(signed_exit.message.epoch, Epoch::new(1000));
+        let domain =
+            spec.compute_domain(Domain::VoluntaryExit, fork_version, genesis_validators_root);
+        assert!(signed_exit
+            .signature
+            .verify(&pubkey, signed_exit.message.signing_root(domain)));
+    };
+
+    // By default, the exit is signed with the genesis fork version and written as JSON.
+    let json_path = output_dir.path().join("exit.json");
+    output_result(&mut exit_cmd(&json_path, "json", None)).unwrap();
+    let json_exit: SignedVoluntaryExit =
+        serde_json::from_reader(File::open(&json_path).unwrap()).unwrap();
+    verify(&json_exit, spec.genesis_fork_version);
+
+    // The signed exit is only readable by the user.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(&json_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    // An existing file is not overwritten.
+    let contents = fs::read(&json_path).unwrap();
+    output_result(&mut exit_cmd(&json_path, "ssz", None)).unwrap_err();
+    assert_eq!(fs::read(&json_path).unwrap(), contents);
+
+    let ssz_path = output_dir.path().join("exit.ssz");
+    output_result(&mut exit_cmd(&ssz_path, "ssz", Some("0x01000000"))).unwrap();
+    let ssz_exit = SignedVoluntaryExit::from_ssz_bytes(&fs::read(&ssz_path).unwrap()).unwrap();
+    verify(&ssz_exit, [1, 0, 0, 0]);
+
+    // An invalid fork version is refused.
+    let invalid_path = output_dir.path().join("invalid.json");
+    output_result(&mut exit_cmd(&invalid_path, "json", Some("0x0100"))).unwrap_err();
+    assert!(!invalid_path.exists());
+}
+
+#[test]
+fn validator_change_password() {
+    let validator_dir = tempdir().unwrap();
+    let password_dir = tempdir().unwrap();
+    let old_password = "old-keystore-password";
+    let new_password = "new-keystore-password";
+
+    // One keystore has its password in the definitions, the other in a password file.
+    let inline_keystore = write_keystore(validator_dir.path(), "inline.json", old_password);
+    let file_keystore = write_keystore(validator_dir.path(), "file.json", old_password);
+    let stored_password_path = password_dir.path().join("stored-password.txt");
+    fs::write(&stored_password_path, old_password).unwrap();
+    let mut defs = ValidatorDefinitions::default();
+    defs.push(
+        ValidatorDefinition::new_keystore_with_password(
+            validator_dir.path().join("inline.json"),
+            Some(ZeroizeString::from(old_password.to_string())),
+        )
+        .unwrap(),
+    );
+    defs.push(
+        ValidatorDefinition::new_keystore_with_password_path(
+            validator_dir.path().join("file.json"),
+            stored_password_path.clone(),
+        )
+        .unwrap(),
+    );
+    defs.save(validator_dir.path()).unwrap();
+
+    let new_password_path = password_dir.path().join("new-password.txt");
+    fs::write(&new_password_path, new_password).unwrap();
+    let wrong_password_path = password_dir.path().join("wrong-password.txt");
+    fs::write(&wrong_password_path, "wrong-keystore-password").unwrap();
+
+    let change_password_cmd = |keystore_path: &Path, old_password_path: Option<&Path>| {
+        let mut cmd = validator_cmd();
+        cmd.arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(change_password::CMD)
+            .arg(format!("--{}", change_password::KEYSTORE_FLAG))
+            .arg(keystore_path.as_os_str())
+            .arg(format!("--{}", change_password::NEW_PASSWORD_FILE_FLAG))
+            .arg(new_password_path.as_os_str())
+            .arg(format!("--{}", common::KDF_FLAG))
+            .arg("pbkdf2")
+            .arg(format!("--{}", common::KDF_COST_FLAG))
+            .arg("16384")
+            .stdin(Stdio::null());
+        if let Some(path) = old_password_path {
+            cmd.arg(format!("--{}", change_password::OLD_PASSWORD_FILE_FLAG))
+                .arg(path.as_os_str());
+        }
+        cmd
+    };
+
+    // An incorrect old password leaves the keystore and the definitions unchanged.
+    output_result(&mut change_password_cmd(
+        &validator_dir.path().join("inline.json"),
+        Some(&wrong_password_path),
+    ))
+    .unwrap_err();
+    let keystore = Keystore::from_json_file(validator_dir.path().join("inline.json")).unwrap();
+    assert_eq!(keystore.uuid(), inline_keystore.uuid());
+    assert!(
+        ValidatorDefinitions::open(validator_dir.path())
+            .unwrap()
+            .as_slice()
+            == defs.as_slice()
+    );
+
+    // Otherwise the stored password is used as the old password, and replaced with the new one.
+    for (file_name, original) in &[
+        ("inline.json", &inline_keystore),
+        ("file.json", &file_keystore),
+    ] {
+        output_result(&mut change_password_cmd(
+            &validator_dir.path().join(file_name),
+            None,
+        ))
+        .unwrap();
+
+        let keystore = Keystore::from_json_file(validator_dir.path().join(file_name)).unwrap();
+        assert_eq!(keystore.pubkey(), original.pubkey());
+        assert!(keystore.decrypt_keypair(old_password.as_bytes()).is_err());
+        assert!(keystore.decrypt_keypair(new_password.as_bytes()).is_ok());
+        assert!(!validator_dir
+            .path()
+            .join(format!("{}.tmp", file_name))
+            .exists());
+    }
+
+    let defs = ValidatorDefinitions::open(validator_dir.path()).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+    for def in defs.as_slice() {
+        assert_eq!(
+            def.signing_definition
+                .voting_keystore_password()
+                .unwrap()
+                .unwrap()
+                .as_bytes(),
+            new_password.as_bytes()
+        );
+    }
+    assert_eq!(
+        fs::read_to_string(&stored_password_path).unwrap(),
+        new_password
+    );
+
+    // A password file which is shared with another validator is not replaced.
+    let shared_keystore = write_keystore(validator_dir.path(), "shared.json", new_password);
+    let mut defs = defs;
+    defs.push(
+        ValidatorDefinition::new_keystore_with_password_path(
+            validator_dir.path().join("shared.json"),
+            stored_password_path.clone(),
+        )
+        .unwrap(),
+    );
+    defs.save(validator_dir.path()).unwrap();
+    let error = output_result(&mut change_password_cmd(
+        &validator_dir.path().join("shared.json"),
+        Some(&new_password_path),
+    ))
+    .unwrap_err();
+    assert!(error.contains("is used by other validators"));
+    let keystore = Keystore::from_json_file(validator_dir.path().join("shared.json")).unwrap();
+    assert_eq!(keystore.uuid(), shared_keystore.uuid());
+    assert_eq!(
+        fs::read_to_string(&stored_password_path).unwrap(),
+        new_password
+    );
+
+    // A keystore without a definition is changed using the given old password.
+    let other_dir = tempdir().unwrap();
+    let other_keystore = write_keystore(other_dir.path(), "other.json", old_password);
+    let old_password_path = password_dir.path().join("old-password.txt");
+    fs::write(&old_password_path, old_password).unwrap();
+    let other_path = other_dir.path().join("other.json");
+    output_result(&mut change_password_cmd(
+        &other_path,
+        Some(&old_password_path),
+    ))
+    .unwrap();
+
+    let keystore = Keystore::from_json_file(&other_path).unwrap();
+    assert_eq!(keystore.pubkey(), other_keystore.pubkey());
+    assert!(keystore.decrypt_keypair(new_password.as_bytes()).is_ok());
+}
+
+#[test]
+fn validator_deposit_data() {
+    let keystore_dir = tempdir().unwrap();
+    let output_dir = tempdir().unwrap();
+    let keystores = vec![
+        write_keystore(keystore_dir.path(), "keystore_a.json", "cats"),
+        write_keystore(keystore_dir.path(), "keystore_b.json", "cats"),
+    ];
+    let password_path = keystore_dir.path().join("password.txt");
+    fs::write(&password_path, "cats").unwrap();
+    let withdrawal_keypair = Keypair::random();
+
+    let deposit_data_cmd = |output_path: &Path, withdrawal_flag: &str, withdrawal: &str| {
+        let mut cmd = validator_cmd();
+        cmd.arg(deposit_data::CMD)
+            .arg(format!("--{}", deposit_data::KEYSTORE_FLAG))
+            .arg(keystore_dir.path().join("keystore_a.json").as_os_str())
+            .arg(format!("--{}", deposit_data::KEYSTORE_FLAG))
+            .arg(keystore_dir.path().join("keystore_b.json").as_os_str())
+            .arg(format!("--{}", deposit_data::PASSWORD_FILE_FLAG))
+            .arg(password_path.as_os_str())
+            .arg(format!("--{}", withdrawal_flag))
+            .arg(withdrawal)
+            .arg(format!("--{}", deposit_data::OUTPUT_FLAG))
+            .arg(output_path.as_os_str())
+            .stdin(Stdio::null());
+        cmd
+    };
+
+    let spec = MainnetEthSpec::default_spec();
+    let check_deposit_data = |output_path: &Path, withdrawal_credentials: &str| {
+        let entries: Vec<serde_json::Value> =
+            serde_json::from_reader(File::open(output_path).unwrap()).unwrap();
+        assert_eq!(entries.len(), keystores.len());
+
+        for (entry, keystore) in entries.iter().zip(&keystores) {
+            assert_eq!(entry["pubkey"], keystore.pubkey());
+            assert_eq!(entry["withdrawal_credentials"], withdrawal_credentials);
+            assert_eq!(entry["amount"], spec.max_effective_balance);
+            assert_eq!(
+                entry["fork_version"],
+                hex::encode(spec.genesis_fork_version)
+            );
+
+            // The signature is checked against the deposit domain.
+            let message = DepositMessage {
+                pubkey: keystore.public_key().unwrap().into(),
+                withdrawal_credentials: Hash256::from_slice(
+                    &hex::decode(withdrawal_credentials).unwrap(),
+                ),
+                amount: spec.max_effective_balance,
+            };
+            let signature =
+                Signature::deserialize(&hex::decode(entry["signature"].as_str().unwrap()).unwrap())
+                    .unwrap();
+            assert!(signature.verify(
+                &keystore.public_key().unwrap(),
+                message.signing_root(spec.get_deposit_domain())
+            ));
+        }
+    };
+
+    // 0x01 credentials contain the address.
+    let address_path = output_dir.path().join("deposit_data-address.json");
+    output_result(&mut deposit_data_cmd(
+        &address_path,
+        deposit_data::WITHDRAWAL_ADDRESS_FLAG,
+        "0x000000000000000000000000000000000000dead",
+    ))
+    .unwrap();
+    check_deposit_data(
+        &address_path,
+        "010000000000000000000000000000000000000000000000000000000000dead",
+    );
+
+    // 0x00 credentials contain the hash of the withdrawal public key.
+    let pubkey_path = output_dir.path().join("deposit_data-pubkey.json");
+    output_result(&mut deposit_data_cmd(
+        &pubkey_path,
+        deposit_data::WITHDRAWAL_PUBKEY_FLAG,
+        &withdrawal_keypair.pk.to_hex_string(),
+    ))
+    .unwrap();
+    check_deposit_data(
+        &pubkey_path,
+        &hex::encode(get_withdrawal_credentials(&withdrawal_keypair.pk, 0)),
+    );
+
+    // An existing file is not overwritten.
+    output_result(&mut deposit_data_cmd(
+        &pubkey_path,
+        deposit_data::WITHDRAWAL_ADDRESS_FLAG,
+        "0x000000000000000000000000000000000000dead",
+    ))
+    .unwrap_err();
+
+    // Nothing is written if the withdrawal key cannot be read from a Ledger device.
+    let ledger_path = output_dir.path().join("deposit_data-ledger.json");
+    output_result(&mut deposit_data_cmd(
+        &ledger_path,
+        deposit_data::WITHDRAWAL_LEDGER_INDEX_FLAG,
+        "0",
+    ))
+    .unwrap_err();
+    assert!(!ledger_path.exists());
+}
+
+#[test]
+fn dkg_ceremony() {
+    let exchange_dir = tempdir().unwrap();
+    let participants_dir = tempdir().unwrap();
+    let key_dirs = (0..3).map(|_| tempdir().unwrap()).collect::<Vec<_>>();
+    let output_dirs = (0..3).map(|_| tempdir().unwrap()).collect::<Vec<_>>();
+    let password_dir = tempdir().unwrap();
+    let keystore_password_path = password_dir.path().join("keystore.txt");
+    fs::write(&keystore_password_path, "keystore-password").unwrap();
+
+    let dkg_cmd = |subcommand: &str, index: u64| {
+        let mut cmd = account_cmd();
+        cmd.arg(dkg::CMD)
+            .arg(subcommand)
+            .arg(format!("--{}", dkg::CEREMONY_FLAG))
+            .arg("test-cluster")
+            .arg(format!("--{}", dkg::INDEX_FLAG))
+            .arg(index.to_string())
+            .stdin(Stdio::null());
+        cmd
+    };
+    let deal_cmd = |index: u64, output_dir: &Path| {
+        let mut cmd = dkg_cmd(deal::CMD, index);
+        cmd.arg(format!("--{}", deal::THRESHOLD_FLAG))
+            .arg("2")
+            .arg(format!("--{}", deal::OPERATORS_FLAG))
+            .arg("3")
+            .arg(format!("--{}", deal::PARTICIPANTS_DIR_FLAG))
+            .arg(participants_dir.path().as_os_str())
+            .arg(format!("--{}", deal::OUTPUT_DIR_FLAG))
+            .arg(output_dir.as_os_str());
+        cmd
+    };
+    let finalize_cmd = |index: u64, input_dir: &Path, output_dir: &Path| {
+        let mut cmd = dkg_cmd(finalize::CMD, index);
+        cmd.arg(format!("--{}", finalize::INPUT_DIR_FLAG))
+            .arg(input_dir.as_os_str())
+            .arg(format!("--{}", finalize::OUTPUT_DIR_FLAG))
+            .arg(output_dir.as_os_str())
+            .arg(format!("--{}", dkg::TRANSPORT_KEY_FILE_FLAG))
+            .arg(
+                key_dirs[index as usize - 1]
+                    .path()
+                    .join(dkg::TRANSPORT_KEY_FILENAME)
+                    .as_os_str(),
+            )
+            .arg(format!("--{}", finalize::KEYSTORE_PASSWORD_FILE_FLAG))
+            .arg(keystore_password_path.as_os_str());
+        cmd
+    };
+
+    // Every operator creates its transport key and sends its participant file to the others.
+    for (index, key_dir) in (1..=3).zip(&key_dirs) {
+        output_result(
+            dkg_cmd(init::CMD, index)
+                .arg(format!("--{}", init::OUTPUT_DIR_FLAG))
+                .arg(key_dir.path().as_os_str()),
+        )
+        .unwrap();
+        let filename = dkg::participant_filename(index);
+        fs::copy(
+            key_dir.path().join(&filename),
+            participants_dir.path().join(&filename),
+        )
+        .unwrap();
+    }
+
+    // Every operator deals into the same directory, as if the files had been exchanged.
+    for index in 1..=3 {
+        output_result(&mut deal_cmd(index, exchange_dir.path())).unwrap();
+    }
+
+    let mut clusters = vec![];
+    let mut key_shares = vec![];
+    for (index, output_dir) in (1..=3).zip(&output_dirs) {
+        output_result(&mut finalize_cmd(
+            index,
+            exchange_dir.path(),
+            output_dir.path(),
+        ))
+        .unwrap();
+
+        let cluster: ClusterDefinition = serde_json::from_reader(
+            File::open(output_dir.path().join(dkg::CLUSTER_FILENAME)).unwrap(),
+        )
+        .unwrap();
+        let keypair = Keystore::from_json_file(output_dir.path().join(VOTING_KEYSTORE_FILE))
+            .unwrap()
+            .decrypt_keypair(b"keystore-password")
+            .unwrap();
+        assert_eq!(
+            cluster.key_shares[index as usize - 1].public_key,
+            keypair.pk
+        );
+
+        clusters.push(cluster);
+        key_shares.push((index, keypair.sk));
+    }
+
+    // Every operator has the same cluster, and any two of them can recover the validator key.
+    assert!(clusters.iter().all(|cluster| *cluster == clusters[0]));
+    assert_eq!(clusters[0].threshold, 2);
+    for pair in &[[0, 1], [1, 2], [2, 0]] {
+        let shares = pair
+            .iter()
+            .map(|&i| (key_shares[i].0, key_shares[i].1.clone()))
+            .collect::<Vec<_>>();
+        let secret_key = eth2_dkg::recover_secret_key(&shares, 2).unwrap();
+        assert_eq!(secret_key.public_key(), clusters[0].validator_public_key);
+    }
+
+    let copy_exchange_dir = || {
+        let dir = tempdir().unwrap();
+        for path in dir_paths(exchange_dir.path()) {
+            fs::copy(&path, dir.path().join(path.file_name().unwrap())).unwrap();
+        }
+        dir
+    };
+
+    // Operator 3 cannot decrypt the share sent to operator 2, even when it is relabelled.
+    let stolen_dir = copy_exchange_dir();
+    let mut share: serde_json::Value = serde_json::from_reader(
+        File::open(exchange_dir.path().join(dkg::share_filename(1, 2))).unwrap(),
+    )
+    .unwrap();
+    share["recipient"] = 3.into();
+    serde_json::to_writer(
+        File::create(stolen_dir.path().join(dkg::share_filename(1, 3))).unwrap(),
+        &share,
+    )
+    .unwrap();
+    let output_dir = tempdir().unwrap();
+    let err =
+        output_result(&mut finalize_cmd(3, stolen_dir.path(), output_dir.path())).unwrap_err();
+    assert!(err.contains("Unable to decrypt"));
+    assert!(!output_dir.path().join(VOTING_KEYSTORE_FILE).exists());
+
+    // A share which does not match its commitments is refused.
+    let redealt_dir = tempdir().unwrap();
+    output_result(&mut deal_cmd(2, redealt_dir.path())).unwrap();
+    let tampered_dir = copy_exchange_dir();
+    fs::copy(
+        redealt_dir.path().join(dkg::share_filename(2, 1)),
+        tampered_dir.path().join(dkg::share_filename(2, 1)),
+    )
+    .unwrap();
+    let output_dir = tempdir().unwrap();
+    let err =
+        output_result(&mut finalize_cmd(1, tampered_dir.path(), output_dir.path())).unwrap_err();
+    assert!(err.contains("The share from operator 2 does not match its commitments"));
+    assert!(!output_dir.path().join(VOTING_KEYSTORE_FILE).exists());
+}
+
+#[test]
+fn validator_import_mnemonic() {
+    let mnemonic_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                  abandon about";
+    let mnemonic_path = mnemonic_dir.path().join("mnemonic");
+    fs::write(&mnemonic_path, phrase).unwrap();
+
+    let mut child = validator_cmd()
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(dst_dir.path().as_os_str())
+        .arg(import_mnemonic::CMD)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG))
+        .arg(format!("--{}", import_mnemonic::MNEMONIC_FILE_FLAG))
+        .arg(mnemonic_path.as_os_str())
+        .arg(format!("--{}", import_mnemonic::COUNT_FLAG))
+        .arg("2")
+        .arg(format!("--{}", import_mnemonic::OFFSET_FLAG))
+        .arg("1")
+        .stderr(Stdio::null())
+        .stdout(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"import-mnemonic-password\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // Derive the expected voting keys directly from the seed, following the EIP-2334 path
+    // `m/12381/3600/i/0/0`.
+    let mnemonic = Mnemonic::from_phrase(phrase, Language::English).unwrap();
+    let seed = Seed::new(&mnemonic, "");
+    let expected_pubkeys = (1..3)
+        .map(|index| {
+            let secret = DerivedKey::from_seed(seed.as_bytes())
+                .unwrap()
+                .child(12381)
+                .child(3600)
+                .child(index)
+                .child(0)
+                .child(0);
+            keypair_from_secret(secret.secret()).unwrap().pk
+        })
+        .collect::<Vec<_>>();
+
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    let imported_pubkeys = defs
+        .as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(imported_pubkeys, expected_pubkeys);
+
+    for def in defs.as_slice() {
+        assert!(dst_dir
+            .path()
+            .join(def.voting_public_key.to_hex_string())
+            .join(VOTING_KEYSTORE_FILE)
+            .exists());
+    }
+    check_slashing_protection(&dst_dir, expected_pubkeys.into_iter());
+}
+
+#[test]
+fn validator_import_json_output_file() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let existing_dir = tempdir().unwrap();
+    let report_dir = tempdir().unwrap();
+    let report_path = report_dir.path().join("report.json");
+
+    let keystores = (0..3)
+        .map(|i| write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats"))
+        .collect::<Vec<_>>();
+
+    // Import the first keystore ahead of time so that it is skipped.
+    fs::copy(
+        src_dir.path().join("keystore-0.json"),
+        existing_dir.path().join("keystore-0.json"),
+    )
+    .unwrap();
+    let mut child = import_cmd(existing_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::JSON_OUTPUT_FILE_FLAG))
+        .arg(report_path.as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\ncats\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let report: ImportReport = serde_json::from_slice(&fs::read(&report_path).unwrap()).unwrap();
+    assert_eq!(report.discovered, 3);
+    assert_eq!(report.imported, 2);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(report.failed, 0);
+    assert_eq!(report.error, None);
+    assert!(report.timestamp > 0);
+    assert_eq!(
+        report
+            .keystores
+            .iter()
+            .map(|keystore| (keystore.pubkey.clone().unwrap(), keystore.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                format!("0x{}", keystores[0].pubkey()),
+                KeystoreStatus::Skipped
+            ),
+            (
+                format!("0x{}", keystores[1].pubkey()),
+                KeystoreStatus::Imported
+            ),
+            (
+                format!("0x{}", keystores[2].pubkey()),
+                KeystoreStatus::Imported
+            ),
+        ]
+    );
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+    assert!(!String::from_utf8(fs::read(&report_path).unwrap())
+        .unwrap()
+        .contains("cats"));
+
+    // A failed import should still produce a report.
+    let failed_dir = tempdir().unwrap();
+    write_keystore(failed_dir.path(), "keystore-0.json", "cats");
+    write_keystore(failed_dir.path(), "keystore-1.json", "dogs");
+    let mut child = import_cmd(failed_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::JSON_OUTPUT_FILE_FLAG))
+        .arg(report_path.as_os_str())
+        .arg(format!("--{}", import::ALL_OR_NOTHING_FLAG))
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    let report: ImportReport = serde_json::from_slice(&fs::read(&report_path).unwrap()).unwrap();
+    assert_eq!(report.discovered, 2);
+    assert_eq!(report.imported, 0);
+    assert_eq!(report.skipped, 0);
+    assert_eq!(report.failed, 1);
+    assert!(report.error.is_some());
+    assert_eq!(report.keystores[0].status, KeystoreStatus::NotImported);
+    assert_eq!(report.keystores[1].status, KeystoreStatus::Failed);
+    assert_eq!(dir_validator_count(dst_dir.path()), 3);
+}
+
+#[test]
+fn validator_import_save_failure_does_not_register() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Wait until the definitions have been loaded, then replace the definitions file with a
+    // directory so that saving it fails.
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains(import::PASSWORD_PROMPT) {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+    }
+    let defs_path = dst_dir.path().join(CONFIG_FILENAME);
+    fs::remove_file(&defs_path).unwrap();
+    fs::create_dir(&defs_path).unwrap();
+
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    // The validator must not be registered, and the copied keystore must have been removed.
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    assert!(slashing_db
+        .get_validator_id(&keystore.public_key().unwrap())
+        .is_err());
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+}
+
+#[test]
+fn validator_import_verify_after() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+    ];
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::VERIFY_AFTER_FLAG))
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Corrupt the first imported keystore whilst the second is being imported.
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    let mut num_prompts = 0;
+    let mut line = String::new();
+    while num_prompts < 2 {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+        if line.contains(import::PASSWORD_PROMPT) {
+            num_prompts += 1;
+        }
+    }
+    let corrupted_keystore = dst_dir
+        .path()
+        .join(format!("0x{}", keystores[0].pubkey()))
+        .join("keystore-0.json");
+    fs::write(&corrupted_keystore, "{}").unwrap();
+
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    let mut rest = String::new();
+    stderr.read_to_string(&mut rest).unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    // Only the corrupted keystore is reported.
+    assert!(rest.contains(&format!(
+        "Validator 0x{} failed to load",
+        keystores[0].pubkey()
+    )));
+    assert!(!rest.contains(&format!(
+        "Validator 0x{} failed to load",
+        keystores[1].pubkey()
+    )));
+    assert!(rest.contains("1 of 2 imported validators failed to load"));
+}
+
+#[test]
+fn validator_import_definitions_format() {
+    let src_dir = tempdir().unwrap();
+    let other_src_dir = tempdir().unwrap();
+    let last_src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let other_keystore = write_keystore(other_src_dir.path(), "keystore-0.json", "dogs");
+    write_keystore(last_src_dir.path(), "keystore-0.json", "mice");
+
+    let import = |src_dir: &Path, password: &[u8], format: Option<&str>| {
+        let mut cmd = import_cmd(src_dir, dst_dir.path());
+        if let Some(format) = format {
+            cmd.arg(format!("--{}", import::DEFINITIONS_FORMAT_FLAG))
+                .arg(format);
+        }
+        let mut child = cmd
+            .stderr(Stdio::null())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.as_mut().unwrap().write_all(password).unwrap();
+        assert!(child.wait().unwrap().success());
+    };
+
+    import(src_dir.path(), b"cats\n", Some("toml"));
+    let contents = fs::read_to_string(dst_dir.path().join(TOML_CONFIG_FILENAME)).unwrap();
+    assert!(contents.contains("[[validators]]"));
+    assert!(!dst_dir.path().join(CONFIG_FILENAME).exists());
+
+    // A later import keeps the format of the file.
+    import(other_src_dir.path(), b"dogs\n", None);
+    let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    assert_eq!(defs.format(), DefinitionsFormat::Toml);
+    let pubkeys: Vec<PublicKey> = defs
+        .as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.clone())
+        .collect();
+    assert_eq!(
+        pubkeys,
+        vec![
+            keystore.public_key().unwrap(),
+            other_keystore.public_key().unwrap()
+        ]
+    );
+
+    // The file is converted back to YAML when requested.
+    import(last_src_dir.path(), b"mice\n", Some("yaml"));
+    let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    assert_eq!(defs.format(), DefinitionsFormat::Yaml);
+    assert_eq!(defs.as_slice().len(), 3);
+    assert!(!dst_dir.path().join(TOML_CONFIG_FILENAME).exists());
+}
+
+#[test]
+fn validator_import_concurrent() {
+    let src_dir = tempdir().unwrap();
+    let other_src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let other_keystore = write_keystore(other_src_dir.path(), "keystore-0.json", "dogs");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Wait until the definitions have been loaded, then import another keystore into the same
+    // directory before the first import saves its definition.
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains(import::PASSWORD_PROMPT) {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+    }
+
+    let mut other_child = import_cmd(other_src_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    other_child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"dogs\n")
+        .unwrap();
+    assert!(other_child.wait().unwrap().success());
+
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // Neither import may have overwritten the definition saved by the other.
+    let defs = ValidatorDefinitions::open(dst_dir.path()).unwrap();
+    let pubkeys = defs
+        .as_slice()
+        .iter()
+        .map(|def| def.voting_public_key.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pubkeys,
+        vec![
+            other_keystore.public_key().unwrap(),
+            keystore.public_key().unwrap()
+        ]
+    );
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+}
+
+#[test]
+#[cfg(unix)]
+fn validator_import_interrupted() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-1.json", "cats"),
+    ];
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Interrupt the import whilst the first keystore is being imported.
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains(import::PASSWORD_PROMPT) {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+    }
+    assert!(Command::new("kill")
+        .arg("-INT")
+        .arg(child.id().to_string())
+        .status()
+        .unwrap()
+        .success());
+    while !line.contains("Interrupted") {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+    }
+
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    // The first keystore is completely imported, and the second is untouched.
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+    let defs = ValidatorDefinitions::open(&dst_dir).unwrap();
+    assert_eq!(defs.as_slice().len(), 1);
+    assert_eq!(
+        defs.as_slice()[0].voting_public_key,
+        keystores[0].public_key().unwrap()
+    );
+    check_slashing_protection(
+        &dst_dir,
+        vec![keystores[0].public_key().unwrap()].into_iter(),
+    );
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    assert!(slashing_db
+        .get_validator_id(&keystores[1].public_key().unwrap())
+        .is_err());
+}
+
+#[test]
+fn validator_import_corrupted_copy() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let other_dir = tempdir().unwrap();
+
+    let keystore = write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let other = write_keystore(other_dir.path(), "keystore-0.json", "cats");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Once the keystore has been read, replace the source so that the copy no longer matches.
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    while !line.contains(import::PASSWORD_PROMPT) {
+        line.clear();
+        assert!(stderr.read_line(&mut line).unwrap() > 0);
+    }
+    fs::copy(
+        other_dir.path().join("keystore-0.json"),
+        src_dir.path().join("keystore-0.json"),
+    )
+    .unwrap();
+
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(!child.wait().unwrap().success());
+
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+    assert!(ValidatorDefinitions::open(&dst_dir)
+        .unwrap()
+        .as_slice()
+        .is_empty());
+    let slashing_db =
+        SlashingDatabase::open(&dst_dir.path().join(SLASHING_PROTECTION_FILENAME)).unwrap();
+    for pubkey in &[keystore.public_key().unwrap(), other.public_key().unwrap()] {
+        assert!(slashing_db.get_validator_id(pubkey).is_err());
+    }
+}
+
+#[test]
+fn validator_import_existing_does_not_prompt() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+
+    // Importing the same keystore again must succeed without any password being provided.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Skipping import of keystore for existing public key"));
+    assert!(!stderr.contains(import::PASSWORD_PROMPT));
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+}
+
+#[test]
+fn validator_import_continue_on_error() {
+    let src_dir = tempdir().unwrap();
+    let keystores = vec![
+        write_keystore(src_dir.path(), "keystore-0.json", "cats"),
+        write_keystore(src_dir.path(), "keystore-2.json", "cats"),
+    ];
+    // The keystore between the two valid keystores fails validation.
+    fs::write(
+        src_dir.path().join("keystore-1.json"),
+        keystore_json_with_path("m/12381/3600/x/0/0"),
+    )
+    .unwrap();
+
+    let run_import = |dst_dir: &TempDir, args: &[&str]| {
+        let mut child = import_cmd(src_dir.path(), dst_dir.path())
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .as_mut()
+            .unwrap()
+            .write_all(b"cats\ncats\n")
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+
+    // By default, the import stops at the failing keystore.
+    let dst_dir = tempdir().unwrap();
+    let output = run_import(&dst_dir, &[]);
+    assert!(!output.status.success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 1);
+
+    // The remaining keystores are imported when continuing on error, but the command still fails.
+    let dst_dir = tempdir().unwrap();
+    let output = run_import(
+        &dst_dir,
+        &[&format!("--{}", import::CONTINUE_ON_ERROR_FLAG)],
+    );
+    assert!(!output.status.success());
+    let stderr = from_utf8(&output.stderr).unwrap();
+    assert!(stderr.contains("Failed to import keystore"));
+    assert!(stderr.contains("Successfully imported 2 validators (0 skipped, 1 failed)"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+    assert_eq!(
+        ValidatorDefinitions::open(&dst_dir)
+            .unwrap()
+            .as_slice()
+            .len(),
+        2
+    );
+    check_slashing_protection(
+        &dst_dir,
+        keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap()),
+    );
+}
+
+#[test]
+fn validator_import_backup_dir() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+    let existing_dir = tempdir().unwrap();
+    let backup_dir = tempdir().unwrap();
+
+    write_keystore(existing_dir.path(), "keystore-0.json", "cats");
+    let mut child = import_cmd(existing_dir.path(), dst_dir.path())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let backed_up_files = [SLASHING_PROTECTION_FILENAME, CONFIG_FILENAME];
+    let contents_before = backed_up_files
+        .iter()
+        .map(|file_name| fs::read(dst_dir.path().join(file_name)).unwrap())
+        .collect::<Vec<_>>();
+
+    write_keystore(src_dir.path(), "keystore-0.json", "cats");
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::BACKUP_DIR_FLAG))
+        .arg(backup_dir.path().as_os_str())
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(b"cats\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+
+    // There should be a single, complete backup matching the state before the import.
+    let backups = fs::read_dir(backup_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(backups.len(), 1);
+    for (file_name, contents) in backed_up_files.iter().zip(contents_before) {
+        assert_eq!(fs::read(backups[0].join(file_name)).unwrap(), contents);
+    }
+    assert_ne!(
+        fs::read(backups[0].join(CONFIG_FILENAME)).unwrap(),
+        fs::read(dst_dir.path().join(CONFIG_FILENAME)).unwrap()
+    );
+}
+
+#[test]
+fn validator_import_filename_pattern() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    for i in 0..2 {
+        write_keystore(src_dir.path(), &format!("validator-{}.json", i), "cats");
+    }
+
+    // The files are not recognised as keystores by default, but should be listed as rejected.
+    let output = import_cmd(src_dir.path(), dst_dir.path())
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(from_utf8(&output.stderr)
+        .unwrap()
+        .contains("validator-0.json"));
+    assert_eq!(dir_validator_count(dst_dir.path()), 0);
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::FILENAME_PATTERN_FLAG))
+        .arg("validator-*.json")
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\ncats\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+    assert_eq!(dir_validator_count(dst_dir.path()), 2);
+}
+
+/// Runs `lighthouse account validator list` with the given `format`, returning stdout.
+fn list_validators<P: AsRef<Path>>(validator_dir: P, format: &str) -> String {
+    let output = output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.as_ref().as_os_str())
+            .arg(validator_list::CMD)
+            .arg(format!("--{}", validator_list::FORMAT_FLAG))
+            .arg(format),
+    )
+    .unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn validator_list() {
+    let src_dir = tempdir().unwrap();
+    let dst_dir = tempdir().unwrap();
+
+    let keystores = (0..2)
+        .map(|i| write_keystore(src_dir.path(), &format!("keystore-{}.json", i), "cats"))
+        .collect::<Vec<_>>();
+
+    let mut child = import_cmd(src_dir.path(), dst_dir.path())
+        .arg(format!("--{}", import::GRAFFITI_FLAG))
+        .arg("listed")
+        .stderr(Stdio::null())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .as_mut()
+        .unwrap()
+        .write_all(b"cats\n\n")
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    let text = list_validators(dst_dir.path(), "text");
+    for keystore in &keystores {
+        assert!(text.contains(&format!("0x{}", keystore.pubkey())));
+    }
+
+    let summaries: Vec<ValidatorSummary> =
+        serde_json::from_str(&list_validators(dst_dir.path(), "json")).unwrap();
+    assert_eq!(
+        summaries
+            .iter()
+            .map(|summary| summary.voting_public_key.clone())
+            .collect::<Vec<_>>(),
+        keystores
+            .iter()
+            .map(|keystore| keystore.public_key().unwrap())
+            .collect::<Vec<_>>()
+    );
+    assert!(summaries[0].password_stored);
+    assert!(!summaries[1].password_stored);
+    for (i, summary) in summaries.iter().enumerate() {
+        assert!(summary.enabled);
+        assert_eq!(summary.graffiti.as_ref().unwrap().as_str(), "listed");
+        assert_eq!(summary.signing_method, "local_keystore");
+        assert_eq!(
+            summary.keystore_path,
+            Some(
+                dst_dir
+                    .path()
+                    .join(format!("0x{}", keystores[i].pubkey()))
+                    .join(format!("keystore-{}.json", i))
+            )
+        );
+        assert_eq!(summary.slashing_protection_registered, Some(true));
+    }
+
+    // The `--json` flag is equivalent to `--format json`.
+    let output = output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(dst_dir.path().as_os_str())
+            .arg(validator_list::CMD)
+            .arg(format!("--{}", validator_list::JSON_FLAG)),
+    )
+    .unwrap();
+    let json_summaries: Vec<ValidatorSummary> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json_summaries, summaries);
+}
+
+/// Returns the paths of all files and directories within `dir`, recursively.
+fn dir_paths<P: AsRef<Path>>(dir: P) -> Vec<PathBuf> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            paths.extend(dir_paths(&path));
+        }
+        paths.push(path);
+    }
+    paths.sort();
+    paths
+}
+
 /// Check that all of the given pubkeys have been registered with slashing protection.
 fn check_slashing_protection(validator_dir: &TempDir, pubkeys: impl Iterator<Item = PublicKey>) {
     let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
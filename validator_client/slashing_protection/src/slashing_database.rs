@@ -27,6 +27,13 @@ pub const CONNECTION_TIMEOUT: Duration = Duration::from_millis(100);
 /// Supported version of the interchange format.
 pub const SUPPORTED_INTERCHANGE_FORMAT_VERSION: u64 = 5;
 
+/// The table which records the network that the database is used for. It has a single row, once
+/// the network is known.
+const CREATE_METADATA_TABLE: &str = "CREATE TABLE IF NOT EXISTS metadata (
+    id INTEGER PRIMARY KEY CHECK (id = 0),
+    genesis_validators_root BLOB NOT NULL
+)";
+
 #[derive(Debug, Clone)]
 pub struct SlashingDatabase {
     conn_pool: Pool,
@@ -87,15 +94,28 @@ impl SlashingDatabase {
             params![],
         )?;
 
+        conn.execute(CREATE_METADATA_TABLE, params![])?;
+
         Ok(Self { conn_pool })
     }
 
     /// Open an existing `SlashingDatabase` from disk.
+    ///
+    /// The database is not modified. Databases created by earlier versions are migrated to the
+    /// current schema by the first write which requires it.
     pub fn open(path: &Path) -> Result<Self, NotSafe> {
         let conn_pool = Self::open_conn_pool(&path)?;
         Ok(Self { conn_pool })
     }
 
+    /// Adds any tables which are missing from a database created by an earlier version.
+    ///
+    /// The caller must commit the transaction for the changes to be persisted.
+    fn migrate_in_txn(txn: &Transaction) -> Result<(), NotSafe> {
+        txn.execute(CREATE_METADATA_TABLE, params![])?;
+        Ok(())
+    }
+
     /// Open a new connection pool with all of the necessary settings and tweaks.
     fn open_conn_pool(path: &Path) -> Result<Pool, NotSafe> {
         let manager = SqliteConnectionManager::file(path)
@@ -171,6 +191,7 @@ impl SlashingDatabase {
         public_keys: impl Iterator<Item = &'a PublicKey>,
         txn: &Transaction,
     ) -> Result<(), NotSafe> {
+        Self::migrate_in_txn(txn)?;
         let mut stmt = txn.prepare("INSERT INTO validators (public_key) VALUES (?1)")?;
         for pubkey in public_keys {
             if self.get_validator_id_opt(&txn, pubkey)?.is_none() {
@@ -806,6 +827,66 @@ impl SlashingDatabase {
         Ok(count)
     }
 
+    /// Returns the genesis validators root of the network that the database is used for, if one
+    /// has been recorded by `set_genesis_validators_root`.
+    pub fn genesis_validators_root(&self) -> Result<Option<Hash256>, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+
+        // Databases created before the root was recorded do not have the table until they are
+        // next written to.
+        let has_metadata: bool = txn
+            .prepare(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'metadata')",
+            )?
+            .query_row(params![], |row| row.get(0))?;
+        if !has_metadata {
+            return Ok(None);
+        }
+
+        let root = txn
+            .prepare("SELECT genesis_validators_root FROM metadata WHERE id = 0")?
+            .query_row(params![], |row| hash256_from_row(0, row))
+            .optional()?;
+        Ok(root)
+    }
+
+    /// Record the genesis validators root of the network that the database is used for,
+    /// replacing any previously recorded root.
+    pub fn set_genesis_validators_root(
+        &self,
+        genesis_validators_root: Hash256,
+    ) -> Result<(), NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
+        Self::migrate_in_txn(&txn)?;
+        txn.execute(
+            "INSERT OR REPLACE INTO metadata (id, genesis_validators_root) VALUES (0, ?1)",
+            params![genesis_validators_root.as_bytes()],
+        )?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Returns `true` if any blocks or attestations signed by `public_key` are recorded.
+    ///
+    /// An unregistered validator has no signing history.
+    pub fn has_signing_history(&self, public_key: &PublicKey) -> Result<bool, NotSafe> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+        let validator_id = match self.get_validator_id_opt(&txn, public_key)? {
+            Some(validator_id) => validator_id,
+            None => return Ok(false),
+        };
+        let has_history = txn
+            .prepare(
+                "SELECT EXISTS(SELECT 1 FROM signed_blocks WHERE validator_id = ?1)
+                     OR EXISTS(SELECT 1 FROM signed_attestations WHERE validator_id = ?1)",
+            )?
+            .query_row(params![validator_id], |row| row.get(0))?;
+        Ok(has_history)
+    }
+
     /// Get a summary of a validator's slashing protection data for consumption by the user.
     pub fn validator_summary(
         &self,
@@ -989,4 +1070,111 @@ mod tests {
         let db2 = SlashingDatabase::open(&file).unwrap();
         db2.test_transaction().unwrap_err();
     }
+
+    #[test]
+    fn signing_history() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        assert!(!db.has_signing_history(&pubkey(0)).unwrap());
+        db.register_validator(&pubkey(0)).unwrap();
+        assert!(!db.has_signing_history(&pubkey(0)).unwrap());
+
+        db.check_and_insert_block_signing_root(&pubkey(0), Slot::new(1), Hash256::zero().into())
+            .unwrap();
+        assert!(db.has_signing_history(&pubkey(0)).unwrap());
+        assert!(!db.has_signing_history(&pubkey(1)).unwrap());
+    }
+
+    #[test]
+    fn genesis_validators_root() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+        let db = SlashingDatabase::create(&file).unwrap();
+
+        assert_eq!(db.genesis_validators_root().unwrap(), None);
+        db.set_genesis_validators_root(Hash256::repeat_byte(1))
+            .unwrap();
+        assert_eq!(
+            db.genesis_validators_root().unwrap(),
+            Some(Hash256::repeat_byte(1))
+        );
+        db.set_genesis_validators_root(Hash256::repeat_byte(2))
+            .unwrap();
+        drop(db);
+
+        let db = SlashingDatabase::open(&file).unwrap();
+        assert_eq!(
+            db.genesis_validators_root().unwrap(),
+            Some(Hash256::repeat_byte(2))
+        );
+    }
+
+    #[test]
+    fn migrate_metadata_table() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+
+        // A database created before the metadata table was added to the schema.
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.register_validator(&pubkey(0)).unwrap();
+        db.conn_pool
+            .get()
+            .unwrap()
+            .execute("DROP TABLE metadata", params![])
+            .unwrap();
+        drop(db);
+
+        let has_metadata = |db: &SlashingDatabase| -> bool {
+            db.conn_pool
+                .get()
+                .unwrap()
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'metadata')",
+                    params![],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+
+        // Opening and reading the database does not modify it.
+        let db = SlashingDatabase::open(&file).unwrap();
+        assert_eq!(db.genesis_validators_root().unwrap(), None);
+        assert!(!has_metadata(&db));
+
+        // Registering a validator adds the table.
+        db.register_validator(&pubkey(1)).unwrap();
+        assert!(has_metadata(&db));
+        assert_eq!(db.genesis_validators_root().unwrap(), None);
+        db.set_genesis_validators_root(Hash256::repeat_byte(1))
+            .unwrap();
+        assert_eq!(
+            db.genesis_validators_root().unwrap(),
+            Some(Hash256::repeat_byte(1))
+        );
+        db.get_validator_id(&pubkey(0)).unwrap();
+    }
+
+    #[test]
+    fn set_genesis_validators_root_migrates() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("db.sqlite");
+
+        let db = SlashingDatabase::create(&file).unwrap();
+        db.conn_pool
+            .get()
+            .unwrap()
+            .execute("DROP TABLE metadata", params![])
+            .unwrap();
+        drop(db);
+
+        let db = SlashingDatabase::open(&file).unwrap();
+        db.set_genesis_validators_root(Hash256::repeat_byte(3))
+            .unwrap();
+        assert_eq!(
+            db.genesis_validators_root().unwrap(),
+            Some(Hash256::repeat_byte(3))
+        );
+    }
 }
@@ -226,6 +226,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .ok_or("Unable to produce randao reveal")?
             .into();
 
+        // The graffiti in the validator's definition takes precedence over the `--graffiti` flag.
+        let graffiti = self
+            .validator_store
+            .graffiti(&validator_pubkey)
+            .or(self.graffiti);
+
         let randao_reveal_ref = &randao_reveal;
         let self_ref = &self;
         let validator_pubkey_ref = &validator_pubkey;
@@ -233,7 +239,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .beacon_nodes
             .first_success(RequireSynced::No, |beacon_node| async move {
                 let block = beacon_node
-                    .get_validator_blocks(slot, randao_reveal_ref, self_ref.graffiti.as_ref())
+                    .get_validator_blocks(slot, randao_reveal_ref, graffiti.as_ref())
                     .await
                     .map_err(|e| format!("Error from beacon node when producing block: {:?}", e))?
                     .data;
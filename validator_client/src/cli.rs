@@ -98,7 +98,10 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .arg(
             Arg::with_name("graffiti")
                 .long("graffiti")
-                .help("Specify your custom graffiti to be included in blocks.")
+                .help(
+                    "Specify your custom graffiti to be included in blocks. The graffiti in the \
+                    definition of a validator takes precedence.",
+                )
                 .value_name("GRAFFITI")
                 .takes_value(true)
         )
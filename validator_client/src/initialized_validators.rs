@@ -20,7 +20,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io;
 use std::path::PathBuf;
-use types::{Keypair, PublicKey};
+use types::{Graffiti, Keypair, PublicKey};
 
 use crate::key_cache;
 use crate::key_cache::KeyCache;
@@ -86,6 +86,7 @@ pub enum SigningMethod {
 /// A validator that is ready to sign messages.
 pub struct InitializedValidator {
     signing_method: SigningMethod,
+    graffiti: Option<Graffiti>,
 }
 
 impl InitializedValidator {
@@ -213,6 +214,7 @@ impl InitializedValidator {
                         voting_keystore: voting_keystore.clone(),
                         voting_keypair,
                     },
+                    graffiti: def.graffiti.map(Into::into),
                 })
             }
         }
@@ -327,6 +329,14 @@ impl InitializedValidators {
             .map(|v| v.voting_keypair())
     }
 
+    /// Returns the graffiti in the definition of the validator with the given voting `PublicKey`,
+    /// if that validator is known to `self` **and** the validator is enabled.
+    pub fn graffiti(&self, voting_public_key: &PublicKey) -> Option<Graffiti> {
+        self.validators
+            .get(voting_public_key)
+            .and_then(|v| v.graffiti)
+    }
+
     /// Add a validator definition to `self`, overwriting the on-disk representation of `self`.
     pub async fn add_definition(&mut self, def: ValidatorDefinition) -> Result<(), Error> {
         if self
@@ -607,3 +617,68 @@ impl InitializedValidators {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::null_logger;
+    use eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf},
+        KeystoreBuilder, DKLEN,
+    };
+    use std::str::FromStr;
+    use tempfile::tempdir;
+    use types::GraffitiString;
+
+    #[tokio::test]
+    async fn graffiti_from_definition() {
+        let validator_dir = tempdir().unwrap();
+        let password = "cats";
+
+        let mut defs = ValidatorDefinitions::default();
+        let mut keypairs = vec![];
+        for graffiti in &[Some("meow"), None] {
+            let keypair = Keypair::random();
+            let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), "".into())
+                .unwrap()
+                .kdf(Kdf::Pbkdf2(Pbkdf2 {
+                    dklen: DKLEN,
+                    c: 16,
+                    prf: Prf::HmacSha256,
+                    salt: vec![42; 32].into(),
+                }))
+                .build()
+                .unwrap();
+            let keystore_path = validator_dir
+                .path()
+                .join(format!("0x{}.json", keystore.pubkey()));
+            keystore
+                .to_json_writer(File::create(&keystore_path).unwrap())
+                .unwrap();
+
+            let mut def = ValidatorDefinition::new_keystore_with_password(
+                &keystore_path,
+                Some(ZeroizeString::from(password.to_string())),
+            )
+            .unwrap();
+            def.graffiti = graffiti.map(|graffiti| GraffitiString::from_str(graffiti).unwrap());
+            defs.push(def);
+            keypairs.push(keypair);
+        }
+
+        let validators = InitializedValidators::from_definitions(
+            defs,
+            validator_dir.path().into(),
+            null_logger().unwrap(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            validators.graffiti(&keypairs[0].pk),
+            Some(GraffitiString::from_str("meow").unwrap().into())
+        );
+        assert_eq!(validators.graffiti(&keypairs[1].pk), None);
+        assert_eq!(validators.graffiti(&Keypair::random().pk), None);
+    }
+}
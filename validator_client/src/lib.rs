@@ -240,6 +240,25 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             () = context.executor.exit() => return Err("Shutting down".to_string())
         };
 
+        // A slashing protection database which has been used on another network offers no
+        // protection on this one.
+        match slashing_protection
+            .genesis_validators_root()
+            .map_err(|e| format!("Unable to read slashing protection database: {:?}", e))?
+        {
+            Some(recorded_root) if recorded_root != genesis_validators_root => {
+                return Err(format!(
+                    "The slashing protection database is for the network with genesis validators \
+                     root {:?}, but the beacon node has {:?}",
+                    recorded_root, genesis_validators_root
+                ));
+            }
+            Some(_) => {}
+            None => slashing_protection
+                .set_genesis_validators_root(genesis_validators_root)
+                .map_err(|e| format!("Unable to update slashing protection database: {:?}", e))?,
+        }
+
         // Update the metrics server.
         if let Some(ctx) = &http_metrics_ctx {
             ctx.shared.write().genesis_time = Some(genesis_time);
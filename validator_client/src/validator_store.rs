@@ -10,8 +10,9 @@ use std::path::Path;
 use std::sync::Arc;
 use tempfile::TempDir;
 use types::{
-    Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork, Hash256, Keypair, PublicKey,
-    SelectionProof, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot, Slot,
+    Attestation, BeaconBlock, ChainSpec, Domain, Epoch, EthSpec, Fork, Graffiti, Hash256, Keypair,
+    PublicKey, SelectionProof, Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedRoot,
+    Slot,
 };
 use validator_dir::ValidatorDir;
 
@@ -120,6 +121,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().num_enabled()
     }
 
+    /// Returns the graffiti in the definition of the validator, if it has any.
+    pub fn graffiti(&self, validator_pubkey: &PublicKey) -> Option<Graffiti> {
+        self.validators.read().graffiti(validator_pubkey)
+    }
+
     fn fork(&self) -> Fork {
         self.fork_service.fork()
     }